@@ -0,0 +1,2266 @@
+use std::cmp;
+use std::fmt::Write;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read};
+use std::iter;
+use std::ops;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+use crate::checkflags;
+use crate::diff::Diff;
+use crate::error::{self, Error};
+use crate::highlight::Highlight;
+use crate::highlight::{HighlightCache, HlState, SelectHighlight, SyntaxHighlight};
+use crate::history::History;
+use crate::lang::{is_sep, Language, Syntax};
+use crate::lint::Diagnostic;
+use crate::loader::{Loaded, Loader};
+use crate::log;
+use crate::mapped::MappedFile;
+use crate::modeline::{Modeline, MODELINE_LINES};
+use crate::style::{FontStyle, Style};
+use crate::table;
+use crate::theme::Theme;
+use crate::url;
+use crate::util::{self, Pos};
+
+/// The settings that rows are rendered with, which the editor gets from its config.
+pub trait RenderConfig {
+    /// The number of columns between tab stops.
+    fn tab_stop(&self) -> usize;
+}
+
+// So that a config shared between the buffers can be passed as it is
+impl<T: RenderConfig + ?Sized> RenderConfig for Rc<T> {
+    fn tab_stop(&self) -> usize {
+        (**self).tab_stop()
+    }
+}
+
+/// Renders rows with the tab stop a buffer sets for itself (see [`Modeline`]), in place of the one in the config.
+struct TabStop(usize);
+
+impl RenderConfig for TabStop {
+    fn tab_stop(&self) -> usize {
+        self.0
+    }
+}
+
+/// Holds the text buffer that will be displayed in the editor.
+#[derive(Debug)]
+pub struct TextBuffer {
+    /// Tells the buffer apart from the others, as two can be for files with the same name
+    id: usize,
+    rows: Vec<Row>,
+    file_name: String,
+    is_dirty: bool,
+    /// Whether the file can't be written to, so changes to it can't be saved
+    is_write_protected: bool,
+    /// Whether the user has been warned that they're editing a write-protected file
+    is_write_protect_warned: bool,
+    saved_mode: Mode,
+    syntax: &'static Syntax,
+    history: History,
+    is_table_view: bool,
+    diagnostics: Vec<Diagnostic>,
+    hl_cache: HighlightCache,
+    /// The first row whose highlighting may be out of date. The rows before it are highlighted, each starting in the state the one before it ends in, so highlighting can start from here instead of the first row (see [`TextBuffer::highlight_rows`])
+    hl_stale_from: usize,
+    /// How many times the rows have been changed, so that what is worked out from them can tell when it's out of date (see [`TextBuffer::changes`])
+    changes: usize,
+    /// The file that rows are still to be loaded from, for large read-only files (see [`TextBuffer::load_rows`])
+    mapped: Option<MappedFile>,
+    /// Reads the file in the background, when it takes a while to read (see [`TextBuffer::poll_loader`])
+    loader: Option<Loader>,
+    /// Whether the file couldn't be read all the way, in which case only the rows before that are kept. The [`TextBuffer`] then can't be edited or saved, as saving it would cut off the rest of the file
+    is_partial: bool,
+    /// What the rows are ended with when saved, which is what the file used when it was opened
+    line_ending: LineEnding,
+    /// The settings given in the file's modelines, which override the config for this buffer
+    modeline: Modeline
+}
+
+/// Read-only files at least this many bytes long are mapped into memory, with their rows loaded as they are needed, instead of being read in all at once.
+const MAP_MIN_LEN: u64 = 8 * 1024 * 1024;
+
+/// How long to wait for a file to be read before showing it while the rest is read in the background.
+const LOAD_WAIT: Duration = Duration::from_millis(100);
+
+/// The id of the next [`TextBuffer`] to be created.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+impl TextBuffer {
+    /// Create a new, empty [`TextBuffer`].
+    pub fn new(is_readonly: bool) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            rows: vec![],
+            file_name: String::new(),
+            is_dirty: false,
+            is_write_protected: false,
+            is_write_protect_warned: false,
+            saved_mode: if is_readonly { Mode::View } else { Mode::Insert },
+            syntax: Syntax::UNKNOWN,
+            history: History::new(),
+            is_table_view: false,
+            diagnostics: vec![],
+            hl_cache: HighlightCache::new(),
+            hl_stale_from: 0,
+            changes: 0,
+            mapped: None,
+            loader: None,
+            is_partial: false,
+            line_ending: LineEnding::default(),
+            modeline: Modeline::default()
+        }
+    }
+
+    /// Creates a new [`TextBuffer`] given the text.
+    pub fn from_text(text: &str, is_readonly: bool, config: &dyn RenderConfig) -> Self {
+        let mut buf = Self::new(is_readonly);
+
+        buf.rows = text.lines().map(|s| Row::from_chars(s.to_owned(), config)).collect();
+        buf.line_ending = LineEnding::detect(text.as_bytes());
+
+        buf
+    }
+
+    /// Opens the contents of a file and turns it into the [`TextBuffer`]'s contents.
+    pub fn open(&mut self, path: &str, config: &dyn RenderConfig) -> error::Result<()> {
+        self.file_name = path.to_owned();
+        if let Some(syntax) = self.detect_syntax() {
+            self.syntax = syntax;
+        }
+
+        if self.saved_mode == Mode::View && fs::metadata(path).is_ok_and(|m| m.len() >= MAP_MIN_LEN) {
+            return self.open_mapped(path);
+        }
+
+        self.loader = Some(Loader::spawn(path).map_err(Error::from)?);
+
+        let mut start = Vec::new();
+        let _ = File::open(path).and_then(|file| file.take(LineEnding::DETECT_LEN).read_to_end(&mut start));
+        self.line_ending = LineEnding::detect(&start);
+
+        // Opening for writing doesn't change the file, but fails if we don't have permission to write to it
+        self.is_write_protected = OpenOptions::new()
+            .write(true)
+            .open(&self.file_name)
+            .is_err_and(|err| err.kind() == io::ErrorKind::PermissionDenied);
+
+        // Most files are read well within this, so they are opened as before. Larger ones are shown while the rest is read in the background
+        let deadline = Instant::now() + LOAD_WAIT;
+        while let Some(loader) = &mut self.loader {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+
+            let loaded = match loader.next_timeout(deadline - now) {
+                Some(loaded) => loaded,
+                None => break
+            };
+
+            self.add_loaded(loaded, config)?;
+        }
+
+        self.is_dirty = false;
+        log::debug(&format!("Opened '{path}' ({} rows{}, {} syntax)", 
+            self.rows.len(), 
+            if self.loader.is_some() { " so far" } else { "" },
+            self.syntax.name()
+        ));
+
+        Ok(())
+    }
+
+    /// Adds what the loader read to the buffer, stopping loading when it is done or failed.
+    fn add_loaded(&mut self, loaded: Loaded, config: &dyn RenderConfig) -> error::Result<()> {
+        match loaded {
+            Loaded::Lines(lines, _) => lines
+                .into_iter()
+                .for_each(|l| self.append(l, config)),
+            Loaded::Done => {
+                self.loader = None;
+                self.apply_modeline();
+            }
+            Loaded::Failed(err) => {
+                self.loader = None;
+                self.is_partial = true;
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the modelines in the first and last few rows, using the syntax and tab stop they set instead of the ones from the extension and config.
+    fn apply_modeline(&mut self) {
+        let num_rows = self.rows.len();
+        let tail = cmp::max(MODELINE_LINES, num_rows.saturating_sub(MODELINE_LINES));
+
+        self.modeline = Modeline::find(
+            (0..cmp::min(MODELINE_LINES, num_rows))
+                .chain(tail..num_rows)
+                .map(|idx| self.rows[idx].chars())
+        );
+
+        if let Some(syntax) = self.modeline.syntax() {
+            self.syntax = syntax;
+            self.rows
+                .iter_mut()
+                .for_each(Row::invalidate_highlight);
+            self.invalidate_highlight_from(0);
+        }
+
+        if let Some(tab_stop) = self.modeline.tab_stop() {
+            self.rerender(&TabStop(tab_stop));
+        }
+
+        if self.modeline != Modeline::default() {
+            log::debug(&format!("Applied modeline of '{}' ({} syntax, tab stop {:?})", self.file_name, self.syntax.name(), self.modeline.tab_stop()));
+        }
+    }
+
+    /// Gets the tab stop that the file's modeline sets, if it sets one.
+    pub fn tab_stop(&self) -> Option<usize> {
+        self.modeline.tab_stop()
+    }
+
+    /// Adds the rows read in the background since the last poll, spending up to about `budget` on it so the editor stays responsive. 
+    /// 
+    /// Returns an error if the file couldn't be read all the way, in which case the rows read before that are kept, but the [`TextBuffer`] can no longer be edited or saved.
+    pub fn poll_loader(&mut self, budget: Duration, config: &dyn RenderConfig) -> error::Result<()> {
+        let start = Instant::now();
+
+        while let Some(loader) = &mut self.loader {
+            let loaded = match loader.try_next() {
+                Some(loaded) => loaded,
+                None => break
+            };
+
+            self.add_loaded(loaded, config)?;
+
+            if start.elapsed() >= budget {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets how much of the file has been read, as a percentage, if it is still being read in the background.
+    pub fn load_progress(&self) -> Option<u64> {
+        self.loader.as_ref().map(Loader::progress)
+    }
+
+    /// Maps the file into memory, so that only the rows that are needed are loaded (see [`TextBuffer::load_rows`]).
+    fn open_mapped(&mut self, path: &str) -> error::Result<()> {
+        let mapped = MappedFile::open(path).map_err(Error::from)?;
+
+        // Only the start is checked, as reading the whole file is what mapping it avoids
+        if mapped.bytes().iter().take(8192).any(|&b| b == 0) {
+            return Err(Error::Binary);
+        }
+
+        // The rows aren't loaded yet, so the modelines are read straight from the map. Its tab stop is used when the rows are loaded (see [`TextBuffer::tab_stop`])
+        let mut mapped = mapped;
+        let mut lines: Vec<_> = (0..MODELINE_LINES).map_while(|idx| mapped.line(idx)).collect();
+        lines.extend(mapped.last_lines(MODELINE_LINES));
+        self.modeline = Modeline::find(lines.iter().map(String::as_str));
+        if let Some(syntax) = self.modeline.syntax() {
+            self.syntax = syntax;
+        }
+
+        log::debug(&format!("Mapped '{path}' ({} bytes, {} syntax)", mapped.bytes().len(), self.syntax.name()));
+        self.line_ending = LineEnding::detect(mapped.bytes());
+        self.mapped = Some(mapped);
+        self.is_dirty = false;
+
+        Ok(())
+    }
+
+    /// Loads rows from the mapped file (if there is one) until there are at least `num_rows` of them, or the whole file is loaded.
+    pub fn load_rows(&mut self, num_rows: usize, config: &dyn RenderConfig) {
+        let mapped = match &mut self.mapped {
+            Some(mapped) => mapped,
+            None => return
+        };
+
+        self.hl_stale_from = cmp::min(self.hl_stale_from, self.rows.len());
+
+        while self.rows.len() < num_rows {
+            match mapped.line(self.rows.len()) {
+                Some(line) => self.rows.push(Row::from_chars(line, config)),
+                None => {
+                    // Every row is loaded, so the map isn't needed anymore
+                    self.mapped = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Loads any rows that haven't been loaded from the mapped file yet, or waits for the rest of the file to be read in the background.
+    pub fn load_all_rows(&mut self, config: &dyn RenderConfig) {
+        self.load_rows(usize::MAX, config);
+
+        while let Some(loader) = &mut self.loader {
+            let loaded = loader.next_timeout(Duration::MAX).unwrap_or(Loaded::Done);
+
+            if let Err(err) = self.add_loaded(loaded, config) {
+                log::warn(&format!("Stopped loading '{}':{err}", self.file_name));
+            }
+        }
+    }
+
+    /// Whether every row has been loaded, which is only not the case for large read-only files (see [`TextBuffer::load_rows`]), and files still being read in the background (see [`TextBuffer::poll_loader`]).
+    pub fn is_fully_loaded(&self) -> bool {
+        self.mapped.is_none() && self.loader.is_none() && !self.is_partial
+    }
+
+    /// Whether the file couldn't be read all the way, so that only the rows before the error are kept (see [`TextBuffer::poll_loader`]).
+    pub fn is_partial(&self) -> bool {
+        self.is_partial
+    }
+
+    /// Discards any unsaved changes by reading the file again. The history is cleared, as the edits in it no longer line up with the contents.
+    pub fn revert(&mut self, config: &dyn RenderConfig) -> error::Result<()> {
+        let mut saved = Self::new(self.saved_mode == Mode::View);
+        saved.open(&self.file_name, config)?;
+
+        self.rows = saved.rows;
+        self.invalidate_highlight_from(0);
+        self.mapped = saved.mapped;
+        self.loader = saved.loader;
+        self.is_partial = false;
+        self.line_ending = saved.line_ending;
+        self.modeline = saved.modeline;
+        self.syntax = saved.syntax;
+        self.is_dirty = false;
+        self.is_write_protected = saved.is_write_protected;
+        self.history = History::new();
+        self.diagnostics.clear();
+
+        if self.is_table_view {
+            self.is_table_view = false;
+            self.toggle_table_view(config);
+        }
+
+        Ok(())
+    }
+
+    /// Renders the rows again, for when the config they were rendered with has changed (eg. the tab stop).
+    pub fn rerender(&mut self, config: &dyn RenderConfig) {
+        // Table rows are rendered from the column widths instead, which don't depend on the config
+        if self.is_table_view {
+            return;
+        }
+
+        self.rows
+            .iter_mut()
+            .for_each(|r| r.update(config));
+        self.invalidate_highlight_from(0);
+    }
+
+    /// Renames the file of the current [`TextBuffer`]. If the buffer hasn't been saved to a file yet, only its name is changed.
+    pub fn rename(&mut self, path: &str) -> error::Result<()> {
+        let prev_syntax = self.detect_syntax();
+
+        if !self.file_name.is_empty() && Path::new(&self.file_name).exists() {
+            match fs::rename(&self.file_name, path) {
+                Ok(()) => (),
+                // Renaming can't move a file to another filesystem, so copy it there and delete the original instead
+                Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+                    fs::copy(&self.file_name, path).map_err(|_| Error::from(err))?;
+                    fs::remove_file(&self.file_name).map_err(Error::from)?;
+                }
+                Err(err) => return Err(Error::from(err))
+            }
+        }
+        self.file_name = path.to_owned();
+        
+        let syntax = self.detect_syntax();
+        if syntax != prev_syntax {
+            self.syntax = syntax.unwrap_or(Syntax::UNKNOWN);
+
+            self.rows
+            .iter_mut()
+            .for_each(Row::invalidate_highlight);
+            self.invalidate_highlight_from(0);
+        }
+
+        Ok(())
+    }
+
+    pub fn row_at(&self, idx: usize) -> &Row {
+        if idx >= self.num_rows() {
+            &self.rows[self.num_rows() - 1]
+        } else {
+            &self.rows[idx]
+        }
+    }
+
+    pub fn row_at_mut(&mut self, idx: usize) -> &mut Row {
+        self.invalidate_highlight_from(cmp::min(idx, self.num_rows().saturating_sub(1)));
+
+        if idx >= self.num_rows() {
+            let len = self.num_rows();
+            &mut self.rows[len - 1]
+        } else {
+            &mut self.rows[idx]
+        }
+    }
+
+    /// Appends a new row to the end of the [`TextBuffer`], given the characters that compose it.
+    pub fn append(&mut self, chars: String, config: &dyn RenderConfig) {        
+        self.push(Row::from_chars(chars, config))
+    }
+
+    /// Appends a new row to the end of the [`TextBuffer`].
+    pub fn append_row(&mut self, row: Row) {
+        self.push(row);
+    }
+
+    fn push(&mut self, row: Row) {
+        self.invalidate_highlight_from(self.rows.len());
+        self.rows.push(row);
+    }
+
+    pub fn rows_to_string(rows: &[Row]) -> String {
+        let mut s = String::new();
+
+        for row in rows {
+            s.push_str(&row.chars[..]);
+            s.push('\n');
+        }
+    
+        s
+    }
+
+    /// Gets the text to save to the file, which is every row ended with the buffer's line ending.
+    pub fn file_text(&self) -> String {
+        let mut s = String::new();
+
+        for row in &self.rows {
+            s.push_str(&row.chars);
+            s.push_str(self.line_ending.as_str());
+        }
+
+        s
+    }
+
+    /// Does the same as [`TextBuffer::insert_rows_no_diff`], but also records the action in the [`TextBuffer`]'s history, along with the `cursor` position before it.
+    pub fn insert_rows(&mut self, pos: Pos, rows: Vec<Row>, cursor: Pos, config: &dyn RenderConfig) -> Pos {        
+        let diff = Diff::Insert(pos, rows.iter()
+            .map(|r| r.chars().to_owned())
+            .collect::<Vec<_>>()
+        );
+
+        let end = self.insert_rows_no_diff(pos, rows, config);
+        self.history.perform(diff, cursor, end);
+
+        end
+    }
+
+    /// Inserts the given `rows` at the given `pos`. The first row will be appended to the row `pos` is at, and the last row will be prepended to the row after the given `pos`.
+    /// 
+    /// Returns position of end of newly inserted rows.
+    /// 
+    /// Assumes the given `pos` is a valid position in the text buffer. 
+    pub fn insert_rows_no_diff(&mut self, pos: Pos, rows: Vec<Row>, config: &dyn RenderConfig) -> Pos {
+        if rows.is_empty() {
+            return pos;
+        }
+
+        if !self.is_editable() {
+            return pos;
+        }
+
+        if self.rows.is_empty() {
+            self.append_row(Row::new());
+        }
+        
+        let num_inserted = rows.len();
+        let mut res_pos = pos;
+
+        // First row
+        let row = self.row_at_mut(pos.y());
+        
+        let remaining = row.chars[pos.x()..].to_owned();
+        row.chars.replace_range(pos.x().., &rows[0].chars);
+        row.update(config);
+        row.make_dirty();
+
+        if num_inserted > 1 {
+            res_pos = Pos(0, pos.y() + num_inserted - 1);
+
+            // Remaining rows -- moved in all at once, and only highlighted once they are shown
+            self.rows.splice(pos.y() + 1..pos.y() + 1, rows
+                .into_iter()
+                .skip(1)
+                .map(|mut r| { r.make_dirty(); r })
+            );
+        }
+
+        // Last row -- append remaining text from og first row
+        let last_row = &mut self.rows[res_pos.y()];
+        res_pos.set_x(last_row.size());
+        last_row.chars.push_str(&remaining);
+        last_row.update(config);
+
+        self.make_dirty();
+
+        res_pos
+    }
+
+    /// Does the same as [`TextBuffer::remove_rows_no_diff`], but also records the action in the [`TextBuffer`]'s history, along with the `cursor` position before it.
+    pub fn remove_rows(&mut self, from: Pos, rows: Vec<String>, cursor: Pos, config: &dyn RenderConfig) -> Pos {        
+        let end = self.remove_rows_no_diff(from, &rows, config);
+        self.history.perform(Diff::Remove(from, rows), cursor, end);
+
+        end
+    }
+
+    /// Replaces the `old` rows (eg. as created by [`TextBuffer::create_remove_msg_region`]) starting at `from` with the given `rows`, recording the removal and insertion in the [`TextBuffer`]'s history as a single edit (see [`History::begin_group`]), along with the `cursor` position before it.
+    /// 
+    /// Returns where the cursor should be afterwards, which is where it was, as long as that is still in the buffer.
+    pub fn replace_rows(&mut self, from: Pos, old: Vec<String>, rows: Vec<Row>, cursor: Pos, config: &dyn RenderConfig) -> Pos {
+        self.history.begin_group();
+        self.remove_rows(from, old, cursor, config);
+
+        let diff = Diff::Insert(from, rows.iter()
+            .map(|r| r.chars().to_owned())
+            .collect::<Vec<_>>()
+        );
+        self.insert_rows_no_diff(from, rows, config);
+
+        let end = self.clamp_pos(cursor);
+        self.history.perform(diff, cursor, end);
+        self.history.end_group();
+
+        end
+    }
+
+    /// Moves `pos` to the nearest position that is in the buffer, and not inside a char.
+    pub fn clamp_pos(&self, pos: Pos) -> Pos {
+        if self.rows.is_empty() {
+            return Pos(0, 0);
+        }
+
+        let y = cmp::min(pos.y(), self.rows.len() - 1);
+        let chars = self.rows[y].chars();
+
+        let mut x = cmp::min(pos.x(), chars.len());
+        while !chars.is_char_boundary(x) {
+            x -= 1;
+        }
+
+        Pos(x, y)
+    }
+
+    /// Removes the text & rows between the `from` and `to` positions.
+    /// 
+    /// Returns the position of the collapse point (end of removed rows).
+    /// 
+    /// Assumes positions are valid, and that `from < to`.
+    pub fn remove_rows_no_diff(&mut self, from: Pos, rows: &Vec<String>, config: &dyn RenderConfig) -> Pos {
+        let to = match (rows.len(), rows.last()) {
+            (0, _) => from,
+            (1, Some(row)) => from + Pos(row.len(), 0),
+            (n, Some(last)) => Pos(last.len(), from.y() + n - 1),
+            _ => unreachable!()
+        };
+        
+        if from == to {
+            return from;
+        }
+
+        if !self.is_editable() {
+            return from;
+        }
+
+        let from_cx = from.x();
+        let to_cx = to.x();
+
+        let lines_removed = to.y() - from.y();
+        self.invalidate_highlight_from(from.y());
+
+        if lines_removed == 0 {
+            self.rows[from.y()].chars.replace_range(from_cx..to_cx, "");
+        } else {
+            self.rows[from.y()].chars.replace_range(from_cx.., "");
+
+            self.rows.drain(from.y()+1..to.y());
+            
+            if from.y() + 1 < self.num_rows() { 
+                self.rows[from.y() + 1].chars.replace_range(..to_cx, "");
+                let chars = self.rows[from.y() + 1].chars.clone();
+                self.rows.remove(from.y() + 1);
+
+                let row = &mut self.rows[from.y()];
+                row.chars.push_str(&chars);
+            }
+        }
+
+        self.rows[from.y()].update(config);
+
+        self.make_dirty();
+
+        from
+    }
+
+    /// Creates the removal message for a given positional region.
+    pub fn create_remove_msg_region(&self, from: Pos, to: Pos) -> Vec<String> {
+        let from_cx = from.x();
+        let to_cx = to.x();
+        
+        let mut rows = Vec::with_capacity(to.y()-from.y()+1);
+
+        if from.y() == to.y() {
+            rows.push(self.row_at(from.y()).chars_at(from_cx..to_cx).to_owned());
+        } else {
+            rows.push(self.row_at(from.y()).chars_at(from_cx..).to_owned());
+
+            if to.y() - from.y() >= 1 {
+                for y in from.y()+1..to.y() {
+                    rows.push(self.row_at(y).chars.to_owned());
+                }
+
+                rows.push(self.row_at(to.y()).chars_at(..to_cx).to_owned());
+            }
+        }
+
+        rows
+    }
+
+    /// Undoes the last edit, returning where the cursor was before it was made.
+    pub fn undo(&mut self, config: &dyn RenderConfig) -> Option<Pos> {
+        let edit = self.history.current_edit()?.clone().inverse();
+
+        for diff in edit.diffs() {
+            self.apply_diff(diff, config);
+        }
+
+        self.history.undo()?;
+
+        Some(self.clamp_pos(edit.cursor_after()))
+    }
+
+    /// Redoes the last undone edit, returning where the cursor was after it was made.
+    pub fn redo(&mut self, config: &dyn RenderConfig) -> Option<Pos> {
+        self.history.redo()?;
+
+        let edit = self.history.current_edit()?.clone();
+
+        for diff in edit.diffs() {
+            self.apply_diff(diff, config);
+        }
+
+        Some(self.clamp_pos(edit.cursor_after()))
+    }
+
+    /// Makes the change described by `diff`, without recording it in the history.
+    fn apply_diff(&mut self, diff: &Diff, config: &dyn RenderConfig) {
+        match diff {
+            Diff::Insert(p, rows) => {
+                self.insert_rows_no_diff(*p, rows.iter().map(|chars| Row::from_chars(chars.to_owned(), config)).collect(), config);
+            }
+            Diff::Remove(p, rows) => {
+                self.remove_rows_no_diff(*p, rows, config);
+            }
+        }
+    }
+
+    pub fn rows(&self) -> &Vec<Row> {
+        &self.rows
+    }
+
+    pub fn rows_mut(&mut self) -> &mut Vec<Row> {
+        self.invalidate_highlight_from(0);
+        &mut self.rows
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_rows() == 0
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    /// Gets the name shown for the buffer, which is `[No Name]` if it doesn't have a file yet.
+    pub fn display_name(&self) -> &str {
+        if self.file_name.is_empty() {
+            "[No Name]"
+        } else {
+            &self.file_name
+        }
+    }
+
+    /// Gets the directory of the buffer's file, which relative paths entered while in the buffer are resolved against, and commands for it are run in. This is empty (ie. the current directory) if the buffer doesn't have a file yet, or its file is in the current directory.
+    pub fn dir(&self) -> &Path {
+        Path::new(&self.file_name)
+            .parent()
+            .unwrap_or(Path::new(""))
+    }
+
+    /// Gets the name of the buffer's file without its directory.
+    pub fn base_name(&self) -> &str {
+        Path::new(&self.file_name)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(&self.file_name)
+    }
+
+    /// Resolves `path` against the directory of the buffer's file (see [`TextBuffer::dir`]), with its separators normalized. Absolute paths are left as they are.
+    pub fn resolve_path(&self, path: &str) -> String {
+        util::normalize_separators(&self.dir().join(path).to_string_lossy())
+    }
+
+    /// Gets the syntax for the file from its name (eg. `COMMIT_EDITMSG`), or else its extension. Returns `None` if it has neither.
+    pub fn detect_syntax(&self) -> Option<&'static Syntax> {
+        let name = Path::new(&self.file_name)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+
+        Syntax::from_file_name(name).or_else(|| self.get_file_ext().map(Syntax::select_syntax))
+    }
+
+    pub fn get_file_ext(&self) -> Option<&str> {
+        if let Some('.') = self.file_name.chars().next() {
+            return Some(&self.file_name[1..]);
+        };
+        
+        std::path::Path::new(&self.file_name)
+            .extension()
+            .and_then(std::ffi::OsStr::to_str)
+    }
+
+    pub fn file_name_mut(&mut self) -> &mut String {
+        &mut self.file_name
+    }
+
+    /// Gets the id that tells the buffer apart from every other one, which stays the same for as long as it is open.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Gets how many times the rows have been changed. It only means something compared with an earlier count, as a different count means the rows may not be the same.
+    pub fn changes(&self) -> usize {
+        self.changes
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+
+    pub fn make_dirty(&mut self) {
+        self.rows
+            .iter_mut()
+            .for_each(Row::make_dirty);
+
+        self.is_dirty = true;
+    }
+
+    pub fn is_write_protected(&self) -> bool {
+        self.is_write_protected
+    }
+
+    pub fn set_write_protected(&mut self, is_write_protected: bool) {
+        self.is_write_protected = is_write_protected;
+    }
+
+    pub fn is_write_protect_warned(&self) -> bool {
+        self.is_write_protect_warned
+    }
+
+    pub fn set_write_protect_warned(&mut self) {
+        self.is_write_protect_warned = true;
+    }
+
+    pub fn make_clean(&mut self) {
+        self.rows
+            .iter_mut()
+            .for_each(Row::make_clean);
+
+        self.is_dirty = false;
+    }
+
+    pub fn set_is_dirty(&mut self, is_dirty: bool) {
+        self.is_dirty = is_dirty;
+    }
+
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Changes the line ending that the rows are saved with, which counts as an unsaved change if it's different.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        if line_ending != self.line_ending {
+            self.line_ending = line_ending;
+            self.is_dirty = true;
+        }
+    }
+
+    /// Gets the cursor position of the given line and column, which start at 1 and count chars, clamping them to the contents of the [`TextBuffer`].
+    pub fn pos_at_line_col(&self, line: usize, col: usize) -> Pos {
+        if self.rows.is_empty() {
+            return Pos(0, 0);
+        }
+
+        let y = cmp::min(line.saturating_sub(1), self.rows.len() - 1);
+        let row = &self.rows[y];
+        let x = row
+            .chars()
+            .char_indices()
+            .nth(col.saturating_sub(1))
+            .map_or(row.size(), |(i, _)| i);
+
+        Pos(x, y)
+    }
+
+    /// Finds the nearest brackets (eg. `()`, which can be on different rows) or quotes (eg. `"`, on the same row) around the text between `from` and `to`, returning the positions of the opening and closing ones. Which brackets and quotes there are depends on the language (see [`Syntax::bracket_pairs`] and [`Syntax::string_delims`]), and ones escaped with `\` are skipped.
+    pub fn enclosing_pair(&self, from: Pos, to: Pos) -> Option<(Pos, Pos)> {
+        let brackets = self.enclosing_brackets(from, to);
+        let quotes = self.enclosing_quotes(from, to);
+
+        // The pair that starts last is the innermost
+        match (brackets, quotes) {
+            (Some(b), Some(q)) => Some(cmp::max_by_key(b, q, |(open, _)| *open)),
+            (b, q) => b.or(q)
+        }
+    }
+
+    fn enclosing_brackets(&self, from: Pos, to: Pos) -> Option<(Pos, Pos)> {
+        let mut closers = vec![];
+
+        // Going back from `from`, each opening bracket that isn't closed before it is around it, from the nearest out
+        for (open, ch) in self.chars_before(from) {
+            if self.is_escaped(open) {
+                continue;
+            }
+
+            if self.syntax.is_closing_bracket(ch) {
+                closers.push(ch);
+            } else if let Some(closer) = self.syntax.closing_bracket(ch) {
+                if closers.pop().is_some() {
+                    continue;
+                }
+
+                match self.find_closing_bracket(open, closer) {
+                    Some(close) if close >= to => return Some((open, close)),
+                    // Closed before the end of the text, or never
+                    _ => continue
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Finds the `closer` that closes the bracket at `open`.
+    fn find_closing_bracket(&self, open: Pos, closer: char) -> Option<Pos> {
+        let mut openers = vec![];
+
+        for (pos, ch) in self.chars_from(open).skip(1) {
+            if self.is_escaped(pos) {
+                continue;
+            }
+
+            if self.syntax.closing_bracket(ch).is_some() {
+                openers.push(ch);
+            } else if self.syntax.is_closing_bracket(ch) && openers.pop().is_none() {
+                return (ch == closer).then_some(pos);
+            }
+        }
+
+        None
+    }
+
+    fn enclosing_quotes(&self, from: Pos, to: Pos) -> Option<(Pos, Pos)> {
+        if from.y() != to.y() {
+            return None;
+        }
+
+        let y = from.y();
+        let chars = self.rows.get(y)?.chars();
+
+        self.syntax
+            .string_delims()
+            .iter()
+            .copied()
+            .filter_map(|quote| {
+                let xs: Vec<usize> = chars
+                    .char_indices()
+                    .filter(|&(x, ch)| ch == quote && !self.is_escaped(Pos(x, y)))
+                    .map(|(x, _)| x)
+                    .collect();
+
+                // Quotes are paired up from the start of the row
+                xs.chunks_exact(2)
+                    .find(|pair| pair[0] < from.x() && pair[1] >= to.x())
+                    .map(|pair| (Pos(pair[0], y), Pos(pair[1], y)))
+            })
+            .max_by_key(|(open, _)| *open)
+    }
+
+    /// Gets whether the char at `pos` comes after an odd number of `\`s.
+    fn is_escaped(&self, pos: Pos) -> bool {
+        self.rows[pos.y()].chars()[..pos.x()]
+            .chars()
+            .rev()
+            .take_while(|&ch| ch == '\\')
+            .count() % 2 == 1
+    }
+
+    /// Gets the chars before `pos`, going back to the start of the buffer, along with their positions.
+    fn chars_before(&self, pos: Pos) -> impl Iterator<Item = (Pos, char)> + '_ {
+        let end_y = cmp::min(pos.y() + 1, self.rows.len());
+
+        self.rows[..end_y]
+            .iter()
+            .enumerate()
+            .rev()
+            .flat_map(move |(y, row)| {
+                let end = if y == pos.y() { pos.x() } else { row.size() };
+                row.chars()[..end]
+                    .char_indices()
+                    .rev()
+                    .map(move |(x, ch)| (Pos(x, y), ch))
+            })
+    }
+
+    /// Gets the chars from `pos` to the end of the buffer, along with their positions.
+    fn chars_from(&self, pos: Pos) -> impl Iterator<Item = (Pos, char)> + '_ {
+        self.rows
+            .iter()
+            .enumerate()
+            .skip(pos.y())
+            .flat_map(move |(y, row)| {
+                let start = if y == pos.y() { pos.x() } else { 0 };
+                row.chars()[start..]
+                    .char_indices()
+                    .map(move |(x, ch)| (Pos(start + x, y), ch))
+            })
+    }
+
+    /// Gets the columns of row `y` that are drawn over its syntax highlighting (see [`Row::write_hlchars_at`]): the `selection` and the `search_match`, each from its start to its end. These are worked out when drawing instead of being kept in each row's highlights, so that selecting (eg. the whole file) doesn't have to change every row, or change them back afterwards.
+    pub fn overlays_at(&self, y: usize, selection: Option<(Pos, Pos)>, search_match: Option<(Pos, Pos)>) -> Vec<(ops::Range<usize>, SelectHighlight)> {
+        let row = match self.rows.get(y) {
+            Some(row) => row,
+            None => return vec![]
+        };
+
+        [(selection, SelectHighlight::Select), (search_match, SelectHighlight::Search)]
+            .into_iter()
+            .filter_map(|(range, hl)| {
+                let (from, to) = range?;
+                if y < from.y() || y > to.y() {
+                    return None;
+                }
+
+                let start = if y == from.y() { row.cx_to_rx(from.x()) } else { 0 };
+                let end = if y == to.y() { row.cx_to_rx(to.x()) } else { row.rwidth() };
+
+                (start < end).then_some((start..end, hl))
+            })
+            .collect()
+    }
+
+    /// Gets the mode that the [`TextBuffer`]'s views are in when they aren't selecting: [`Mode::View`] if it can't be edited, and [`Mode::Insert`] otherwise. Whether a view is selecting is kept in the view, so that selecting in one tab doesn't select in the others of the same buffer.
+    pub fn mode(&self) -> Mode {
+        if self.is_editable() {
+            Mode::Insert
+        } else {
+            Mode::View
+        }
+    }
+
+    /// Whether the contents of the [`TextBuffer`] can currently be changed.
+    pub fn is_editable(&self) -> bool {
+        self.saved_mode != Mode::View && !self.is_table_view && !self.is_partial
+    }
+
+    pub fn is_table_view(&self) -> bool {
+        self.is_table_view
+    }
+
+    /// Toggles the aligned table view of a delimiter-separated file (eg. CSV), which pads the cells of each row so that the columns line up. The file contents are not changed, and the [`TextBuffer`] cannot be edited while in table view.
+    /// 
+    /// Returns whether the table view is now on, or `None` if the [`TextBuffer`]'s language cannot be shown as a table.
+    pub fn toggle_table_view(&mut self, config: &dyn RenderConfig) -> Option<bool> {
+        let delim = self.syntax.lang().table_delim()?;
+        self.load_all_rows(config);
+
+        self.is_table_view = !self.is_table_view;
+
+        if self.is_table_view {
+            let widths = table::column_widths(&self.rows, delim);
+
+            self.rows
+                .iter_mut()
+                .for_each(|r| r.update_table(&widths, delim));
+        } else {
+            self.rows
+                .iter_mut()
+                .for_each(|r| r.update(config));
+        }
+
+        self.invalidate_highlight_from(0);
+
+        Some(self.is_table_view)
+    }
+
+    /// Redoes the highlighting of any rows in `range` that are stale. Rows are only highlighted once they are about to be shown, so editing doesn't highlight rows needlessly.
+    /// 
+    /// Since a row's highlighting depends on the rows before it (eg. being inside a multi-line comment), stale rows above `range` are also highlighted, and rows whose starting state changed are redone. Only the rows from the first one that may be stale are gone through, rather than every row above `range`.
+    /// 
+    /// Only columns up to `cols_end` are shown, so long rows are only highlighted that far. If `is_rainbow` is set, brackets are colored by how deeply they are nested.
+    pub fn highlight_rows(&mut self, range: ops::Range<usize>, cols_end: usize, is_rainbow: bool) {
+        let syntax = self.syntax;
+        let end = cmp::min(range.end, self.rows.len());
+        let start = cmp::min(cmp::min(self.hl_stale_from, range.start), end);
+        let mut state = match start {
+            0 => HlState::default(),
+            _ => self.rows[start - 1].hl_out_state
+        };
+        self.hl_cache.set_syntax(syntax, is_rainbow);
+
+        for row in &mut self.rows[start..end] {
+            state = row.refresh_highlight(syntax, is_rainbow, state, cols_end, &mut self.hl_cache);
+        }
+
+        self.hl_stale_from = cmp::max(self.hl_stale_from, end);
+    }
+
+    /// Notes that the rows from `y` on have changed, so they may need to be highlighted again, as a row's highlighting depends on the rows before it.
+    fn invalidate_highlight_from(&mut self, y: usize) {
+        self.hl_stale_from = cmp::min(self.hl_stale_from, y);
+        self.changes = self.changes.wrapping_add(1);
+    }
+
+    pub fn syntax(&self) -> &'static Syntax {
+        self.syntax
+    }
+
+    pub fn syntax_mut(&mut self) -> &mut &'static Syntax{
+        &mut self.syntax
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn history_mut(&mut self) -> &mut History {
+        &mut self.history
+    }
+
+    pub fn current_diff(&self) -> Option<&Diff> {
+        self.history.current_edit()?.diffs().last()
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn set_diagnostics(&mut self, diagnostics: Vec<Diagnostic>) {
+        self.diagnostics = diagnostics;
+    }
+
+    /// Gets the most severe diagnostic for the given row, if there are any.
+    pub fn diagnostic_at(&self, row: usize) -> Option<&Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.row() == row)
+            .max_by_key(|d| d.severity())
+    }
+}
+
+/// Checks whether the given file contents look like binary data (ie. contain NUL bytes or invalid UTF-8).
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.contains(&0) || std::str::from_utf8(bytes).is_err()
+}
+
+/// Gets what is shown in place of the control char `ch`: caret notation for ASCII ones (eg. `^M` for `\r`), and the hex code for the rest (eg. `<85>`).
+fn control_placeholder(ch: char) -> String {
+    match ch as u32 {
+        n @ 0..=0x1f => format!("^{}", char::from(n as u8 + b'@')),
+        0x7f => "^?".to_owned(),
+        n => format!("<{n:02X}>")
+    }
+}
+
+/// What the end of each row is written as in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    /// `\n`, as on Linux and macOS
+    #[default]
+    Lf,
+    /// `\r\n`, as on Windows
+    Crlf
+}
+
+impl LineEnding {
+    /// How many bytes at the start of a file are looked at to detect its line ending.
+    const DETECT_LEN: u64 = 8192;
+
+    /// Detects the line ending used in `text` from the end of its first line, defaulting to [`LineEnding::Lf`] if there isn't one near the start.
+    pub fn detect(text: &[u8]) -> Self {
+        let start = &text[..cmp::min(text.len(), Self::DETECT_LEN as usize)];
+
+        match start.iter().position(|&b| b == b'\n') {
+            Some(i) if i > 0 && start[i - 1] == b'\r' => Self::Crlf,
+            _ => Self::Lf
+        }
+    }
+
+    /// Gets the line ending with the given name (ignoring case), as shown in the status bar.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_uppercase().as_str() {
+            "LF" => Some(Self::Lf),
+            "CRLF" => Some(Self::Crlf),
+            _ => None
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Lf => "LF",
+            Self::Crlf => "CRLF"
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n"
+        }
+    }
+}
+
+/// The mode that a view of a [`TextBuffer`] is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Insert,
+    Select,
+    View,
+}
+
+/// What is drawn on top of a row's syntax highlighting when it is written (see [`Row::write_hlchars_at`]).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Decorations<'a> {
+    /// The selection and search matches, as ranges of columns (see [`TextBuffer::overlays_at`])
+    pub overlays: &'a [(ops::Range<usize>, SelectHighlight)],
+    /// The columns that rulers are drawn in
+    pub rulers: &'a [usize],
+    /// The columns that indent guides are drawn in
+    pub guides: &'a [usize],
+    /// Whether whitespace at the end of the row is highlighted
+    pub show_trailing_ws: bool
+}
+
+/// Struct for holding information about a row in a [`TextBuffer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    chars: String,
+    render: String,
+    hl: Vec<Highlight>,
+	has_tabs: bool,
+    is_dirty: bool,
+    /// Whether `hl` is out of date with `render`
+    is_hl_stale: bool,
+    /// How many bytes of `render` have been highlighted, as long rows are only highlighted as far as they are shown
+    hl_end: usize,
+    /// The multi-line highlight states (eg. being in a comment) that the row starts and ends in
+    hl_in_state: HlState,
+    hl_out_state: HlState,
+    /// Maps each byte index of `chars` (and the end of the row) to the column it is drawn at in `render`, so the cursor can be moved between them without walking the row
+    col_map: Vec<usize>,
+    render_width: usize,
+    /// Whether `render` is laid out as part of a table (see [`Row::update_table`])
+    is_table: bool,
+    /// The byte ranges of `render` that show control chars (eg. `^M` for `\r`)
+    controls: Vec<ops::Range<usize>>,
+    /// The byte ranges of `render` that are URLs, which are underlined
+    urls: Vec<ops::Range<usize>>
+}
+
+/// Rows longer than this many bytes are only highlighted as far as they are shown, plus this much more, so that very long rows (eg. in minified files) stay quick to edit and draw.
+const LAZY_HL_LEN: usize = 16 * 1024;
+
+impl Row {
+    /// Create a new, empty [`Row`].
+    pub fn new() -> Self {
+        Self {
+            chars: String::new(),
+            render: String::new(),
+            hl: vec![],
+			has_tabs: false,
+            is_dirty: false,
+            is_hl_stale: false,
+            hl_end: 0,
+            hl_in_state: HlState::default(),
+            hl_out_state: HlState::default(),
+            col_map: vec![0],
+            render_width: 0,
+            is_table: false,
+            controls: vec![],
+            urls: vec![]
+        }
+    }
+
+    /// Creates a new [`Row`], given its contents, and a [`RenderConfig`] to determine details.
+    pub fn from_chars(chars: String, config: &dyn RenderConfig) -> Self {
+        let mut row = Row::new();
+        row.chars = chars;
+        row.update(config);
+
+        row
+    }
+
+    /// Gets the chars at the given `range` of `self.chars`. If any values of the range go out of bounds of the row's text, they are not used, so that it will not fail. If the range is entirely out of bounds, then all chars will not be used, returning an empty `&str`.
+    pub fn chars_at<R>(&self, range: R) -> &str        
+    where 
+        R: ops::RangeBounds<usize>
+    {
+        self.chars.get(Self::index_range(&self.chars, self.size(), range)).unwrap_or("")
+    }
+
+    /// Gets the chars at the given `range` of `self.render`. If any values of the range go out of bounds of the row's text, they are not used, so that it will not fail. If the range is entirely out of bounds, then all chars will not be used, returning an empty `&str`.
+    pub fn rchars_at<R>(&self, range: R) -> &str        
+    where 
+        R: ops::RangeBounds<usize>
+    {
+        self.render.get(Self::index_range(&self.render, self.rsize(), range)).unwrap_or("")
+    }
+
+    /// Writes the chars of `self.render` shown in the given range of terminal columns to `out`, applying any highlights according to `self.hl`. Wide chars (eg. CJK) that are cut off by either end of the range are written as spaces, so exactly `cols.len()` columns are written (unless the row is shorter).
+    /// 
+    /// The `decorations` are drawn on top: chars in the columns of its rulers are drawn on the theme's `superdim` color, unless they are selected or matched by a search, and spaces in the columns of its guides are drawn as indent guides. If it shows trailing whitespace, whitespace at the end of the row is drawn on the theme's `trailing_whitespace` color in the same way. A style is only written at the start of each run of chars with the same highlight, and `out` can be reused across rows to avoid allocating.
+    pub fn write_hlchars_at(&self, cols: ops::Range<usize>, decorations: &Decorations, theme: &Theme, out: &mut String) {
+        let Decorations { overlays, rulers, guides, show_trailing_ws } = *decorations;
+        let mut prev_style = Some(Highlight::NORMAL.to_style(theme));
+        // Tabs are rendered as spaces, so only spaces need to be trimmed. Tables are padded with spaces, which aren't in the file
+        let trailing_ws_start = if show_trailing_ws && !self.is_table { self.render.trim_end_matches(' ').len() } else { self.rsize() };
+
+        // Skip straight to the first char shown when possible, so long rows aren't walked from the start
+        let start = self.render_idx_at(cols.start).unwrap_or(0);
+        let mut col = self.cx_to_rx(start);
+
+        // The fonts that control chars and URLs add, for each byte from `start`, so the ranges aren't searched again for every char
+        let mut fonts = if self.controls.is_empty() && self.urls.is_empty() {
+            vec![]
+        } else {
+            vec![FontStyle::NONE; self.render.len() - start]
+        };
+        for (ranges, font) in [(&self.controls, FontStyle::REVERSE), (&self.urls, FontStyle::UNDERLINE)] {
+            for range in ranges.iter().filter(|range| range.end > start) {
+                for added in &mut fonts[range.start.saturating_sub(start)..range.end - start] {
+                    *added |= font;
+                }
+            }
+        }
+
+        for (i, ch) in self.render[start..].char_indices().map(|(i, ch)| (start + i, ch)) {
+            let width = ch.width().unwrap_or(0);
+            let (from, to) = (col, col + width);
+            col = to;
+
+            // Zero-width chars (eg. combining accents) go with the char before them
+            let is_visible = if width == 0 {
+                from > cols.start && from <= cols.end
+            } else {
+                from >= cols.start && to <= cols.end
+            };
+            let is_cut_off = !is_visible && width > 0 && from < cols.end && to > cols.start;
+
+            if !is_visible && !is_cut_off {
+                if from >= cols.end {
+                    break;
+                }
+                continue;
+            }
+
+            let mut hl = self.hl.get(i).copied().unwrap_or_default();
+            // The last overlay that covers the char is drawn on top
+            if let Some((_, select_hl)) = overlays.iter().rev().find(|(overlay, _)| overlay.contains(&from)) {
+                hl.set_select_hl(*select_hl);
+            }
+            let is_on_ruler = rulers.iter().any(|ruler| (from..cmp::max(to, from + 1)).contains(ruler));
+            let is_guide = ch == ' ' && guides.contains(&from);
+
+            let mut style = hl.to_style(theme);
+            if is_on_ruler && hl.select_hl() == SelectHighlight::Normal {
+                style.set_bg(*theme.superdim());
+            }
+            if i >= trailing_ws_start && hl.select_hl() == SelectHighlight::Normal {
+                style.set_bg(*theme.trailing_whitespace());
+            }
+            if is_guide {
+                style.set_fg(*theme.indent_guide());
+            }
+            if let Some(&added) = fonts.get(i - start) {
+                style.set_font(style.font() | added);
+            }
+
+            if prev_style != Some(style) {
+                let _ = write!(out, "{style}");
+                prev_style = Some(style);
+            }
+
+            if is_guide && is_visible {
+                out.push('│');
+            } else if is_visible {
+                out.push(ch);
+            } else {
+                for _ in cmp::max(from, cols.start)..cmp::min(to, cols.end) {
+                    out.push(' ');
+                }
+            }
+        }
+
+        let _ = write!(out, "{}", Style::default(theme));
+    }
+
+    /// Gets the chars at the given `range` of `str`. If any values of the range go out of bounds of the row's text, they are not used, so that it will not fail. If the range is entirely out of bounds, then all chars will not be used, returning an empty `&str`.
+    fn index_range<R>(str: &str, size: usize, range: R) -> ops::Range<usize>
+    where 
+        R: ops::RangeBounds<usize>
+    {
+        if str.is_empty() {
+            return 0..0;
+        }
+
+        let start = range.start_bound();
+        let end = range.end_bound();
+
+        let start_idx = match start {
+            ops::Bound::Unbounded => 0,
+            ops::Bound::Included(i) => if *i >= size {
+                size - 1
+            } else {
+                *i
+            },
+            ops::Bound::Excluded(i) => if *i >= size - 1 {
+                return 0..0;
+            } else {
+                *i+1
+            }
+        };
+
+        let end_idx = match end {
+            ops::Bound::Unbounded => size,
+            ops::Bound::Included(i) => if *i >= size {
+                size
+            } else {
+                *i+1
+            },
+            ops::Bound::Excluded(i) => if *i > size {
+                return 0..0;
+            } else if *i == 0 {
+                return 0..0;
+            } else {
+                *i
+            }
+        };
+
+        start_idx..end_idx
+    }
+
+    /// Updates the [`render`] and [`rsize`] properties to align with the [`chars`] property.
+    pub fn update(&mut self, config: &dyn RenderConfig) {
+        let mut render = String::with_capacity(self.size());
+        let mut map = Vec::with_capacity(self.size() + 1);
+        let mut col = 0;
+
+		self.has_tabs = false;
+        self.controls.clear();
+        for ch in self.chars.chars() {
+            map.extend(iter::repeat_n(col, ch.len_utf8()));
+
+            // Tabs are expanded to the next tab stop
+            if ch == '\t' {
+				self.has_tabs = true;
+                let width = config.tab_stop() - (col % config.tab_stop());
+                render.extend(iter::repeat_n(' ', width));
+                col += width;
+            } else if ch.is_control() {
+                // Other control chars would be read by the terminal, so they are shown as placeholders instead
+                let placeholder = control_placeholder(ch);
+                self.controls.push(render.len()..render.len() + placeholder.len());
+                render.push_str(&placeholder);
+                col += placeholder.len();
+            } else {
+                render.push(ch);
+                col += ch.width().unwrap_or(0);
+            }
+        }
+
+        map.push(col);
+
+        self.urls = url::find_urls(&render);
+        self.render = render;
+        self.col_map = map;
+        self.render_width = col;
+        self.is_table = false;
+
+        self.invalidate_highlight();
+    }
+
+    /// Updates the [`render`] property to show the row as part of an aligned table, padding each cell to the given column `widths`. The [`chars`] property is left untouched.
+    pub fn update_table(&mut self, widths: &[usize], delim: char) {
+        let mut render = String::with_capacity(self.size());
+        let mut render_width = 0;
+        let mut map = vec![0; self.size() + 1];
+
+        for (col, range) in table::cell_ranges(&self.chars, delim).into_iter().enumerate() {
+            if col > 0 {
+                render.push_str(" | ");
+                render_width += 3;
+            }
+
+            let mut width = 0;
+            for (i, ch) in self.chars[range.clone()].char_indices() {
+                for b in 0..ch.len_utf8() {
+                    map[range.start + i + b] = render_width;
+                }
+
+                let ch = if ch == '\t' { ' ' } else { ch };
+                render.push(ch);
+                width += ch.width().unwrap_or(0);
+            }
+            render_width += width;
+
+            // The delimiter (or end of row) sits right after the cell's text
+            for b in 0..delim.len_utf8() {
+                if range.end + b <= self.size() {
+                    map[range.end + b] = render_width;
+                }
+            }
+
+            for _ in width..widths[col] {
+                render.push(' ');
+                render_width += 1;
+            }
+        }
+
+        self.urls = url::find_urls(&render);
+        self.render = render;
+        self.col_map = map;
+        self.render_width = render_width;
+        self.is_table = true;
+
+        self.invalidate_highlight();
+    }
+
+    /// Marks the row's highlighting as stale, so that it is redone the next time the row is shown (see [`Row::refresh_highlight`]).
+    pub fn invalidate_highlight(&mut self) {
+        self.hl.clear();
+        self.hl.resize(self.rsize(), Highlight::default());
+        self.is_hl_stale = true;
+    }
+
+    /// Redoes the row's highlighting if it is stale, or if the row now starts in a different multi-line state (`in_state`), keeping any select or search highlights. Highlighting is reused from the `cache` when possible.
+    /// 
+    /// Returns the multi-line state that the row ends in, which the next row starts in.
+    /// 
+    /// Long rows are only highlighted up to a little past column `cols_end`, the furthest column shown, so their out state is only known that far.
+    pub fn refresh_highlight(&mut self, syntax: &'static Syntax, is_rainbow: bool, in_state: HlState, cols_end: usize, cache: &mut HighlightCache) -> HlState {
+        let end = self.hl_end_for(cols_end);
+
+        if !self.is_hl_stale && self.hl_in_state == in_state && self.hl_end >= end {
+            return self.hl_out_state;
+        }
+
+        self.hl_in_state = in_state;
+
+        if end < self.rsize() {
+            // Too long to be worth caching
+            self.update_highlight(syntax, is_rainbow, end);
+        } else if let Some((hl, out_state)) = cache.get(&self.render, in_state) {
+            self.hl = hl.to_vec();
+            self.hl_out_state = out_state;
+            self.hl_end = self.rsize();
+            self.is_hl_stale = false;
+        } else {
+            self.update_highlight(syntax, is_rainbow, end);
+            cache.insert(&self.render, in_state, self.hl.clone(), self.hl_out_state);
+        }
+
+        self.hl_out_state
+    }
+
+    // TODO: Create `Highlighter` iterator/struct and put this in that
+    /// Highlights the first `end` bytes of the row according to the given `syntax`, starting in the multi-line state that the previous row ended in (see [`Row::refresh_highlight`]). The rest of the row is left unhighlighted, and brackets in it aren't counted.
+    /// 
+    /// If `is_rainbow` is set, brackets outside of strings and comments are highlighted by how deeply they are nested.
+    pub fn update_highlight(&mut self, syntax: &'static Syntax, is_rainbow: bool, end: usize) {
+        self.is_hl_stale = false;
+        self.hl_end = end;
+
+        if let Language::Unknown = syntax.lang() {
+            self.hl = vec![Highlight::default(); self.rsize()];
+            self.hl_out_state = HlState::default();
+            self.hl_end = self.rsize();
+            return;
+        }
+
+        self.hl = Vec::with_capacity(self.rsize());
+        let mut is_prev_sep = true;
+        let mut quote: Option<char> = None;
+        let mut state = self.hl_in_state;
+        
+        // Use `chars.next()` to skip next item
+        let mut chars = self.render.char_indices();
+        let mut next = chars.next();
+        while let Some((i, ch)) = next {
+            if i >= end {
+                // Only multi-line comments matter for the rows after, so skim the rest for those
+                if quote.is_none() && state.raw_string.is_none() {
+                    state = Self::skim_comments(&self.render[i..], state, syntax);
+                }
+
+                break;
+            }
+
+            // Highlight Raw String, which has no escapes and may have started on a row before (see `RAW_MULTILINE`)
+            if let Some((delim, hashes)) = state.raw_string {
+                let hl = Highlight::from_syntax_hl(SyntaxHighlight::String);
+                Self::push_hl(&mut self.hl, hl, ch);
+                next = chars.next();
+
+                let closing = "#".repeat(hashes as usize);
+                if ch == '\\' && checkflags!(RAW_ESCAPED_QUOTES in syntax.flags()) && self.render[i+1..].starts_with(delim) {
+                    self.hl.push(hl);
+                    next = chars.next();
+                } else if ch == delim && self.render[i+1..].starts_with(&closing) {
+                    for _ in 0..closing.len() {
+                        self.hl.push(hl);
+                        next = chars.next();
+                    }
+                    state.raw_string = None;
+                }
+
+                is_prev_sep = true;
+                continue;
+            }
+
+            let prev_hl = if i > 0 { self.hl[i - 1] } else { Highlight::default() };
+            // Doc comments only start at the start of a row
+            let is_row_start = state.nested_comments == 0 && self.render[..i].trim_start().is_empty();
+
+            // Highlight Single-line Doc Comment
+            if quote.is_none() &&
+                is_row_start &&
+                syntax.doc_comments().iter().any(|start| Self::is_doc_start(&self.render[i..], start, None))
+            {
+                self.hl.append(&mut vec![Highlight::from_syntax_hl(SyntaxHighlight::DocComment); self.rsize() - self.hl.len()]);
+                break;
+            }
+
+            // Highlight Single-line Comment
+            if let Some(ln_comment) = syntax.ln_comment() {
+                if quote.is_none() &&
+                    state.nested_comments == 0 &&
+                    (i == 0 || checkflags!(LINE_START_COMMENTS !in syntax.flags())) &&
+                    ln_comment == self.rchars_at(i..i+ln_comment.len())
+                {
+                    self.hl.append(&mut vec![Highlight::from_syntax_hl(SyntaxHighlight::Comment); self.rsize() - self.hl.len()]);
+                    break;
+                }
+            }
+
+            // Highlight Multi-line Comment
+            if quote.is_none() {
+                let doc_comment = if is_row_start {
+                    syntax.multi_doc_comments()
+                        .iter()
+                        .find(|(start, end)| Self::is_doc_start(&self.render[i..], start, Some(end)))
+                } else {
+                    None
+                };
+                let mc_start = doc_comment
+                    .map(|(start, _)| *start)
+                    .or(syntax.multi_comment().map(|(start, _)| start))
+                    .filter(|start| self.render[i..].starts_with(start));
+
+                if let Some(mc_start) = mc_start {
+                    if state.nested_comments == 0 {
+                        state.doc_end = doc_comment.map(|(_, end)| *end);
+                    }
+
+                    let hl = Self::comment_hl(state);
+                    for _ in 0..mc_start.len() {
+                        self.hl.push(hl);
+                        next = chars.next();
+                    }
+
+                    state.nested_comments += 1;
+                    continue;
+                }
+
+                if state.nested_comments > 0 {
+                    let hl = Self::comment_hl(state);
+                    Self::push_hl(&mut self.hl, hl, ch);
+
+                    let mc_end = state.doc_end.or(syntax.multi_comment().map(|(_, end)| end));
+
+                    if let Some(mc_end) = mc_end.filter(|end| self.render[i..].starts_with(end)) {
+                        for _ in 0..mc_end.len()-1 {
+                            self.hl.push(hl);
+                            chars.next();
+                        }
+                        next = chars.next();
+
+                        if checkflags!(NESTED_COMMENTS in syntax.flags()) {
+                            state.nested_comments -= 1;
+                        } else {
+                            state.nested_comments = 0;
+                        }
+
+                        if state.nested_comments == 0 {
+                            state.doc_end = None;
+                        }
+                        
+                        is_prev_sep = true;
+                        continue;
+                    } else {
+                        next = chars.next();
+                        continue;
+                    }
+                }
+            }
+
+            // Highlight Keywords
+            if is_prev_sep {
+                if quote.is_none() {
+                    for keyword in syntax.keywords() {
+                        let len = keyword.len();
+                        if *keyword == self.rchars_at(i..i+len) &&
+                            (self.rsize() == i + len || 
+                            self.render[i+len..].starts_with(is_sep))
+                        {
+                            self.hl.append(&mut vec![Highlight::from_syntax_hl(SyntaxHighlight::Keyword); len]);
+
+                            for _ in 0..len {
+                                next = chars.next();
+                            }
+
+                            is_prev_sep = false;
+                            break;
+                        }
+                    }
+                }
+
+                if !is_prev_sep {
+                    if let Some((_, ch)) = next {
+                        if is_sep(ch) {
+                            is_prev_sep = true;
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            // Highlight Ctrl Flow Keywords
+            if is_prev_sep {
+                if quote.is_none() {
+                    for flowword in syntax.flowwords() {
+                        let len = flowword.len();
+                        if *flowword == self.rchars_at(i..i+len) &&
+                            (self.rsize() == i + len || 
+                            self.render[i+len..].starts_with(is_sep))
+                        {
+                            self.hl.append(&mut vec![Highlight::from_syntax_hl(SyntaxHighlight::Flowword); len]);
+
+                            for _ in 0..len {
+                                next = chars.next();
+                            }
+
+                            is_prev_sep = false;
+                            break;
+                        }
+                    }
+                }
+
+                if !is_prev_sep {
+                    if let Some((_, ch)) = next {
+                        if is_sep(ch) {
+                            is_prev_sep = true;
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            // Highlight Common Types
+            if is_prev_sep {
+                if quote.is_none() {
+                    for common_type in syntax.common_types() {
+                        let len = common_type.len();
+                        if *common_type == self.rchars_at(i..i+len) &&
+                            (self.rsize() == i + len || 
+                            self.render[i+len..].starts_with(is_sep))
+                        {
+                            self.hl.append(&mut vec![Highlight::from_syntax_hl(SyntaxHighlight::Type); len]);
+
+                            for _ in 0..len {
+                                next = chars.next();
+                            }
+
+                            is_prev_sep = false;
+                            break;
+                        }
+                    }
+                }
+
+                if !is_prev_sep {
+                    if let Some((_, ch)) = next {
+                        if is_sep(ch) {
+                            is_prev_sep = true;
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            // Highlight Metawords
+            if is_prev_sep {
+                if quote.is_none() {
+                    for metaword in syntax.metawords() {
+                        let len = metaword.len();
+                        if *metaword == self.rchars_at(i..i+len) &&
+                            (self.rsize() == i + len || 
+                            self.render[i+len..].starts_with(is_sep))
+                        {
+                            self.hl.append(&mut vec![Highlight::from_syntax_hl(SyntaxHighlight::Metaword); len]);
+
+                            for _ in 0..len {
+                                next = chars.next();
+                            }
+
+                            is_prev_sep = false;
+                            break;
+                        }
+                    }
+                }
+
+                if !is_prev_sep {
+                    if let Some((_, ch)) = next {
+                        if is_sep(ch) {
+                            is_prev_sep = true;
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            // Highlight Strings
+            if checkflags!(HIGHLIGHT_STRINGS in syntax.flags()) {
+                if let Some(delim) = quote {
+                    Self::push_hl(&mut self.hl, Highlight::from_syntax_hl(SyntaxHighlight::String), ch);
+
+                    // Escape character
+                    if ch == '\\' && i + 1 < self.rsize() {
+                        if let Some((_, escaped)) = chars.next() {
+                            Self::push_hl(&mut self.hl, Highlight::from_syntax_hl(SyntaxHighlight::String), escaped);
+                        }
+                        next = chars.next();
+                        continue;
+                    }
+
+                    if ch == delim {
+                        quote = None;
+                    }
+
+                    is_prev_sep = true;
+                    next = chars.next();
+                    continue;
+                }
+
+                let raw_start = if is_prev_sep && checkflags!(RAW_STRINGS in syntax.flags()) {
+                    Self::raw_string_start(&self.render[i..], syntax.string_delims())
+                } else {
+                    None
+                };
+
+                if let Some((len, delim, hashes)) = raw_start {
+                    self.hl.append(&mut vec![Highlight::from_syntax_hl(SyntaxHighlight::String); len]);
+
+                    next = chars.next();
+                    while next.is_some_and(|(j, _)| j < i + len) {
+                        next = chars.next();
+                    }
+
+                    state.raw_string = Some((delim, hashes));
+                    is_prev_sep = true;
+                    continue;
+                }
+
+                // The `b` of a byte string (eg. `b"..."`) is part of it
+                if is_prev_sep &&
+                    ch == 'b' &&
+                    checkflags!(BYTE_STRINGS in syntax.flags())
+                {
+                    if let Some(delim @ ('"' | '\'')) = self.render[i+1..].chars().next() {
+                        quote = Some(delim);
+                        self.hl.append(&mut vec![Highlight::from_syntax_hl(SyntaxHighlight::String); 2]);
+
+                        chars.next();
+                        next = chars.next();
+                        continue;
+                    }
+                }
+
+                let lifetime_len = if ch == '\'' && checkflags!(LIFETIMES in syntax.flags()) {
+                    Self::lifetime_len(&self.render[i..])
+                } else {
+                    None
+                };
+
+                if let Some(len) = lifetime_len {
+                    self.hl.append(&mut vec![Highlight::from_syntax_hl(SyntaxHighlight::Keyword); len]);
+
+                    next = chars.next();
+                    while next.is_some_and(|(j, _)| j < i + len) {
+                        next = chars.next();
+                    }
+
+                    is_prev_sep = false;
+                    continue;
+                }
+
+                if ch == '"' || ch == '\'' {
+                    quote = Some(ch);
+                    self.hl.push(Highlight::from_syntax_hl(SyntaxHighlight::String));
+                    next = chars.next();
+                    continue;
+                }
+            }
+                
+            // Highlight Number
+            if checkflags!(HIGHLIGHT_NUMBERS in syntax.flags()) &&
+                ch.is_ascii_digit() &&
+                is_prev_sep
+            {
+                let len = Self::number_len(&self.render[i..], syntax);
+                self.hl.append(&mut vec![Highlight::from_syntax_hl(SyntaxHighlight::Number); len]);
+
+                next = chars.next();
+                while next.is_some_and(|(j, _)| j < i + len) {
+                    next = chars.next();
+                }
+
+                is_prev_sep = false;
+                continue;
+            }
+
+            // Highlight Identifiers 
+            if checkflags!(HIGHLIGHT_IDENTS in syntax.flags()) &&
+                (is_prev_sep || prev_hl.syntax_hl() == SyntaxHighlight::Ident) && 
+                !is_sep(ch) 
+            {
+                // For highlighting the first letter of capitalized idents (eg. MyClass) as types
+                if checkflags!(CAPITAL_AS_TYPES in syntax.flags()) &&
+                    is_prev_sep &&
+                    ch.is_uppercase()
+                {
+                    Self::push_hl(&mut self.hl, Highlight::from_syntax_hl(SyntaxHighlight::Type), ch);
+                } else {
+                    Self::push_hl(&mut self.hl, Highlight::from_syntax_hl(SyntaxHighlight::Ident), ch);
+                }
+
+                is_prev_sep = false;
+                next = chars.next();
+                continue;
+            }
+
+            // Highlighting the rest of capitalized idents (eg. MyClass) as types
+            if checkflags!(CAPITAL_AS_TYPES in syntax.flags()) &&
+                prev_hl.syntax_hl() == SyntaxHighlight::Type &&
+                !is_sep(ch) 
+            {
+                Self::push_hl(&mut self.hl, Highlight::from_syntax_hl(SyntaxHighlight::Type), ch);
+
+                is_prev_sep = false;
+                next = chars.next();
+                continue;
+            }
+
+            // Highlight Function
+            if prev_hl.syntax_hl() == SyntaxHighlight::Ident {
+                if ch == '(' {
+                    let mut j = 1;
+                    while j <= i {
+                        let hl = &self.hl[i - j];
+
+                        if hl.syntax_hl() == SyntaxHighlight::Ident {
+                            
+                            self.hl[i - j] = Highlight::from_syntax_hl(SyntaxHighlight::Function);
+
+                            j += 1; 
+                            continue;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            // Highlighting idents prior to `::` or other equivalents
+            if prev_hl.syntax_hl() == SyntaxHighlight::Ident {
+                for path_delim in syntax.path_delims() {
+                    if path_delim == &self.rchars_at(i..i+path_delim.len()) {
+                        let mut j = 1;
+                        while j <= i {
+                            let hl = &self.hl[i - j];
+
+                            if hl.syntax_hl() == SyntaxHighlight::Ident {
+                                
+                                self.hl[i - j] = Highlight::from_syntax_hl(SyntaxHighlight::Path);
+
+                                j += 1; 
+                                continue;
+                            } else {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Highlight Rainbow Bracket
+            if is_rainbow && syntax.bracket_pairs().iter().any(|&(open, close)| ch == open || ch == close) {
+                let depth = if syntax.closing_bracket(ch).is_some() {
+                    state.bracket_depth += 1;
+                    state.bracket_depth - 1
+                } else {
+                    state.bracket_depth = state.bracket_depth.saturating_sub(1);
+                    state.bracket_depth
+                };
+
+                self.hl.push(Highlight::from_syntax_hl(SyntaxHighlight::Bracket(depth as u8)));
+                is_prev_sep = true;
+                next = chars.next();
+                continue;
+            }
+
+            Self::push_hl(&mut self.hl, Highlight::default(), ch);
+            is_prev_sep = is_sep(ch);
+            next = chars.next();
+        }
+
+        if checkflags!(RAW_MULTILINE !in syntax.flags()) {
+            state.raw_string = None;
+        }
+
+        self.hl.resize(self.rsize(), Highlight::default());
+        self.hl_out_state = state;
+    }
+
+    /// Gets the length in bytes of the start of the raw string (eg. `r#"`, `br"` or `Rb'`) that `text` starts with, along with the quote and the number of `#`s that close it. The quote is one of the language's `delims`. Returns `None` if `text` doesn't start with one.
+    fn raw_string_start(text: &str, delims: &[char]) -> Option<(usize, char, u32)> {
+        let prefix_len = ["br", "rb", "r"]
+            .into_iter()
+            .find(|prefix| text.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix)))?
+            .len();
+
+        let rest = &text[prefix_len..];
+        let hashes = rest.len() - rest.trim_start_matches('#').len();
+        let delim = rest[hashes..].chars().next().filter(|ch| delims.contains(ch))?;
+
+        Some((prefix_len + hashes + delim.len_utf8(), delim, hashes as u32))
+    }
+
+    /// Gets the length in bytes of the lifetime or label (eg. `'a`) that `text` starts with, or `None` if it starts a char literal (eg. `'a'` or `'\n'`) instead.
+    fn lifetime_len(text: &str) -> Option<usize> {
+        let mut chars = text[1..].chars();
+
+        match (chars.next(), chars.next()) {
+            (Some('\\'), _) | (Some(_), Some('\'')) => None,
+            _ => Some(text[1..].find(is_sep).map_or(text.len(), |end| end + 1))
+        }
+    }
+
+    /// Gets the length in bytes of the number that `text` starts with, which must start with a digit. This includes its base prefix (eg. `0x`), digit separators, fraction, exponent and type suffix (eg. `f32`), where `syntax` has them.
+    fn number_len(text: &str, syntax: &'static Syntax) -> usize {
+        let bytes = text.as_bytes();
+        let is_digit = |idx: usize, radix: u32| bytes.get(idx).is_some_and(|&b| (b as char).is_digit(radix));
+        let is_sep_at = |idx: usize| syntax.digit_sep().is_some_and(|sep| text[idx..].starts_with(sep));
+
+        let mut radix = 10;
+        let mut len = 0;
+
+        for prefix in syntax.number_prefixes() {
+            let prefix_radix = match prefix.as_bytes().last() {
+                Some(b'x') => 16,
+                Some(b'o') => 8,
+                Some(b'b') => 2,
+                _ => continue
+            };
+
+            if text.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix)) &&
+                is_digit(prefix.len(), prefix_radix)
+            {
+                radix = prefix_radix;
+                len = prefix.len();
+                break;
+            }
+        }
+
+        let skip_digits = |mut idx: usize| {
+            while is_digit(idx, radix) || (idx > 0 && is_digit(idx - 1, radix) && is_sep_at(idx)) {
+                idx += 1;
+            }
+            idx
+        };
+
+        len = skip_digits(len);
+
+        if radix == 10 {
+            // Fraction, which needs a digit after the `.` so that ranges (eg. `0..10`) and method calls aren't included
+            if bytes.get(len) == Some(&b'.') && is_digit(len + 1, 10) {
+                len = skip_digits(len + 1);
+            }
+
+            // Exponent (eg. `1e-9`)
+            if matches!(bytes.get(len), Some(b'e' | b'E')) {
+                let exp_start = if matches!(bytes.get(len + 1), Some(b'+' | b'-')) { len + 2 } else { len + 1 };
+
+                if is_digit(exp_start, 10) {
+                    len = skip_digits(exp_start);
+                }
+            }
+        }
+
+        let suffix = syntax.number_suffixes().iter().find(|suffix| {
+            text.get(len..len + suffix.len()).is_some_and(|s| s.eq_ignore_ascii_case(suffix)) &&
+                text[len + suffix.len()..].chars().next().is_none_or(is_sep)
+        });
+
+        len + suffix.map_or(0, |suffix| suffix.len())
+    }
+
+    /// Gets whether `text` starts with the doc comment that starts with `start` and ends with `end` (if it is multi-line). Repeating the last char of `start` (eg. `////` or `/***`) or closing it straight away (eg. `/**/`) makes a regular comment instead.
+    fn is_doc_start(text: &str, start: &str, end: Option<&str>) -> bool {
+        let Some(rest) = text.strip_prefix(start) else {
+            return false;
+        };
+        let last = &start[start.len() - 1..];
+
+        !rest.starts_with(last) && end.is_none_or(|end| !text[start.len() - 1..].starts_with(end))
+    }
+
+    /// Gets the highlight of the multi-line comment that `state` is in.
+    fn comment_hl(state: HlState) -> Highlight {
+        if state.doc_end.is_some() {
+            Highlight::from_syntax_hl(SyntaxHighlight::DocComment)
+        } else {
+            Highlight::from_syntax_hl(SyntaxHighlight::Comment)
+        }
+    }
+
+    /// Finds the multi-line comment state at the end of `text`, starting in `state`, by only looking for comment delimiters. Strings and single-line comments containing them aren't accounted for, which is good enough for the unhighlighted part of a long row.
+    fn skim_comments(mut text: &str, mut state: HlState, syntax: &'static Syntax) -> HlState {
+        // Doc comments only start at the start of a row, so only regular comments can start here
+        let mc_start = syntax.multi_comment().map(|(start, _)| start);
+        let is_nested = checkflags!(NESTED_COMMENTS in syntax.flags());
+
+        loop {
+            let mc_end = state.doc_end.or(syntax.multi_comment().map(|(_, end)| end));
+
+            let start = mc_start.and_then(|mc_start| Some((text.find(mc_start)?, mc_start.len())));
+            let end = if state.nested_comments > 0 {
+                mc_end.and_then(|mc_end| Some((text.find(mc_end)?, mc_end.len())))
+            } else {
+                None
+            };
+
+            match (start, end) {
+                (Some((s, len)), Some((e, _))) if s < e && is_nested => {
+                    state.nested_comments += 1;
+                    text = &text[s + len..];
+                }
+                (_, Some((e, len))) => {
+                    state.nested_comments = if is_nested { state.nested_comments - 1 } else { 0 };
+                    if state.nested_comments == 0 {
+                        state.doc_end = None;
+                    }
+
+                    text = &text[e + len..];
+                }
+                (Some((s, len)), None) if state.nested_comments == 0 || is_nested => {
+                    state.nested_comments += 1;
+                    text = &text[s + len..];
+                }
+                _ => return state
+            }
+        }
+    }
+
+    /// Gets how many bytes of `render` to highlight to show up to column `cols_end`. Short rows are always highlighted in full.
+    fn hl_end_for(&self, cols_end: usize) -> usize {
+        if self.rsize() <= LAZY_HL_LEN {
+            return self.rsize();
+        }
+
+        match self.render_idx_at(cols_end) {
+            Some(idx) => cmp::min(idx + LAZY_HL_LEN, self.rsize()),
+            None => self.rsize()
+        }
+    }
+
+    /// Gets the byte index in `render` of the char at column `col`. This can only be found quickly when `render` is laid out the same as `chars` (ie. without tabs, and not as a table).
+    fn render_idx_at(&self, col: usize) -> Option<usize> {
+        if self.has_tabs || self.is_table || !self.controls.is_empty() {
+            None
+        } else {
+            Some(self.rx_to_cx(col))
+        }
+    }
+
+    /// Pushes the highlight of `ch` once for each of its bytes, as `hl` is indexed by byte.
+    fn push_hl(hls: &mut Vec<Highlight>, hl: Highlight, ch: char) {
+        for _ in 0..ch.len_utf8() {
+            hls.push(hl);
+        }
+    }
+
+    pub fn cx_to_rx(&self, cx: usize) -> usize {
+        self.col_map[cmp::min(cx, self.size())]
+    }
+
+    /// Gets the index of the start of the grapheme cluster (ie. what is seen as a single char, such as an accented letter or an emoji sequence) before `cx`.
+    pub fn prev_grapheme(&self, cx: usize) -> usize {
+        self.chars_at(..cx)
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// Gets the index of the end of the grapheme cluster (ie. what is seen as a single char, such as an accented letter or an emoji sequence) at `cx`.
+    pub fn next_grapheme(&self, cx: usize) -> usize {
+        self.chars_at(cx..)
+            .graphemes(true)
+            .next()
+            .map_or(self.size(), |g| cx + g.len())
+    }
+
+    /// Gets the index of the start of the grapheme cluster that `cx` is in, so that the cursor never sits in the middle of one.
+    pub fn grapheme_start(&self, cx: usize) -> usize {
+        if cx >= self.size() {
+            return self.size();
+        }
+
+        self.chars
+            .grapheme_indices(true)
+            .map(|(i, _)| i)
+            .take_while(|&i| i <= cx)
+            .last()
+            .unwrap_or(0)
+    }
+
+    pub fn rx_to_cx(&self, rx: usize) -> usize {
+        // The first byte drawn after `rx`, so the char before it is the one at `rx`
+        let after = self.col_map.partition_point(|&col| col <= rx);
+        if after > self.size() {
+            return self.size();
+        }
+
+        let mut cx = after.saturating_sub(1);
+        while !self.chars.is_char_boundary(cx) {
+            cx -= 1;
+        }
+
+        cx
+    }
+
+    pub fn size(&self) -> usize {
+        self.chars.len()
+    }
+
+    pub fn rsize(&self) -> usize {
+        self.render.len()
+    }
+
+    /// Gets the terminal column that each line of the row starts at when it is wrapped to `width` columns. Wide chars are moved to the next line instead of being split.
+    pub fn wrap_starts(&self, width: usize) -> Vec<usize> {
+        let width = cmp::max(width, 1);
+        let mut starts = vec![0];
+        let mut col = 0;
+
+        for ch in self.render.chars() {
+            let ch_width = ch.width().unwrap_or(0);
+            let line_start = *starts.last().unwrap();
+
+            if col + ch_width - line_start > width && col > line_start {
+                starts.push(col);
+            }
+
+            col += ch_width;
+        }
+
+        starts
+    }
+
+    /// Gets the number of terminal columns that the rendered row takes up.
+    pub fn rwidth(&self) -> usize {
+        self.render_width
+    }
+
+    /// Gets the number of terminal columns taken up by the whitespace at the start of the row.
+    pub fn indent_width(&self) -> usize {
+        self.render.len() - self.render.trim_start_matches(' ').len()
+    }
+
+    pub fn chars(&self) -> &str {
+        &self.chars
+    }
+
+    pub fn chars_mut(&mut self) -> &mut String {
+        &mut self.chars
+    }
+
+    pub fn render(&self) -> &str {
+        &self.render
+    }
+
+    pub fn render_mut(&mut self) -> &mut String {
+        &mut self.render
+    }
+
+    pub fn hl(&self) -> &Vec<Highlight> {
+        &self.hl
+    }
+
+    pub fn hl_mut(&mut self) -> &mut Vec<Highlight> {
+        &mut self.hl
+    }
+
+	pub fn has_tabs(&self) -> bool {
+		self.has_tabs
+	}
+
+    pub fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+
+    pub fn make_clean(&mut self) {
+        self.is_dirty = false;
+    }
+
+    pub fn make_dirty(&mut self) {
+        self.is_dirty = true;
+    }
+}
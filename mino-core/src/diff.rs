@@ -0,0 +1,304 @@
+use std::ops::Range;
+
+use crate::util::Pos;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diff {
+    Insert(Pos, Vec<String>),   // Insert given rows at given `Pos`
+    Remove(Pos, Vec<String>)    // Remove given rows at given `Pos`
+}
+
+impl Diff {
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::Insert(pos, s) => Self::Remove(pos, s),
+            Self::Remove(pos, s) => Self::Insert(pos, s)
+        }
+    }
+
+    pub fn pos(&self) -> &Pos {
+        match self {
+            Self::Insert(p, _) => p,
+            Self::Remove(p, _) => p
+        }
+    }
+
+    /// Gets the rows that the `Diff` adds or removes.
+    pub fn rows(&self) -> &[String] {
+        match self {
+            Self::Insert(_, rows) => rows,
+            Self::Remove(_, rows) => rows
+        }
+    }
+}
+
+/// A run of lines that differ between two files, where the lines in `old` were replaced by the lines in `new`. Either may be empty, if lines were only added or removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    old: Range<usize>,
+    new: Range<usize>
+}
+
+impl Hunk {
+    pub fn old_lines(&self) -> &Range<usize> {
+        &self.old
+    }
+
+    pub fn new_lines(&self) -> &Range<usize> {
+        &self.new
+    }
+}
+
+/// How a line changed between two files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineChange {
+    Added,
+    Removed,
+    Changed
+}
+
+/// The most lines that [`diff_lines`] looks for ways to add or remove, as the time and memory that takes grows with the square of it. Files that differ by more than this are given as one hunk.
+const MAX_EDITS: isize = 1000;
+
+/// Finds the hunks of lines that differ between `old` and `new`, using Myers' algorithm so that as few lines as possible are marked as added or removed. If they differ by more than [`MAX_EDITS`] lines, the lines between the ones that are the same at the start and end are all given as one hunk.
+pub fn diff_lines<T: AsRef<str>>(old: &[T], new: &[T]) -> Vec<Hunk> {
+    // Lines that are the same at the start or end of both files can be skipped
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(a, b)| a.as_ref() == b.as_ref())
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a.as_ref() == b.as_ref())
+        .count();
+
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = n + m;
+
+    // `v[k]` is the furthest x reached on diagonal k (where k = x - y). A copy of the part of `v` that each step uses is kept, to backtrack through.
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    let idx = |k: isize| (k + max + 1) as usize;
+    let mut trace = vec![];
+
+    'search: for d in 0..=max {
+        if d > MAX_EDITS {
+            return vec![Hunk { old: prefix..prefix + a.len(), new: prefix..prefix + b.len() }];
+        }
+
+        trace.push(v[idx(-d - 1)..=idx(d + 1)].to_vec());
+
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[idx(k - 1)] < v[idx(k + 1)]) {
+                v[idx(k + 1)]
+            } else {
+                v[idx(k - 1)] + 1
+            };
+            let mut y = x - k;
+
+            while x < n && y < m && a[x as usize].as_ref() == b[y as usize].as_ref() {
+                x += 1;
+                y += 1;
+            }
+            v[idx(k)] = x;
+
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // Backtracks to find which lines of both files matched
+    let mut matches = vec![];
+    let (mut x, mut y) = (n, m);
+
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let at = |k: isize| v[(k + d + 1) as usize];
+
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            matches.push((x as usize, y as usize));
+        }
+
+        x = prev_x;
+        y = prev_y;
+    }
+
+    // The lines between each pair of matching lines are the hunks
+    let mut hunks = vec![];
+    let (mut old_at, mut new_at) = (0, 0);
+
+    for (x, y) in matches.into_iter().rev().chain([(n as usize, m as usize)]) {
+        if x > old_at || y > new_at {
+            hunks.push(Hunk {
+                old: prefix + old_at..prefix + x,
+                new: prefix + new_at..prefix + y
+            });
+        }
+
+        (old_at, new_at) = (x + 1, y + 1);
+    }
+
+    hunks
+}
+
+/// Gets how each line of a file with `num_lines` lines changed, given the `hunks` between it and another file. If `is_old` is true, the file is the old side of the hunks.
+pub fn line_changes(hunks: &[Hunk], num_lines: usize, is_old: bool) -> Vec<Option<LineChange>> {
+    let mut changes = vec![None; num_lines];
+
+    for hunk in hunks {
+        let (lines, other) = if is_old {
+            (&hunk.old, &hunk.new)
+        } else {
+            (&hunk.new, &hunk.old)
+        };
+
+        let change = if other.is_empty() {
+            if is_old { LineChange::Removed } else { LineChange::Added }
+        } else {
+            LineChange::Changed
+        };
+
+        for line in lines.clone() {
+            if let Some(c) = changes.get_mut(line) {
+                *c = Some(change);
+            }
+        }
+    }
+
+    changes
+}
+
+/// Compares two open buffers, keeping track of how each of their lines changed. The buffers are told apart by their ids (see [`TextBuffer::id`]), as two files can have the same name.
+/// 
+/// [`TextBuffer::id`]: crate::buffer::TextBuffer::id
+#[derive(Debug, Clone)]
+pub struct DiffView {
+    old_id: usize,
+    new_id: usize,
+    hunks: Vec<Hunk>,
+    old_changes: Vec<Option<LineChange>>,
+    new_changes: Vec<Option<LineChange>>
+}
+
+impl DiffView {
+    pub fn new(old_id: usize, new_id: usize) -> Self {
+        Self {
+            old_id,
+            new_id,
+            hunks: vec![],
+            old_changes: vec![],
+            new_changes: vec![]
+        }
+    }
+
+    pub fn old_id(&self) -> usize {
+        self.old_id
+    }
+
+    pub fn new_id(&self) -> usize {
+        self.new_id
+    }
+
+    pub fn hunks(&self) -> &Vec<Hunk> {
+        &self.hunks
+    }
+
+    /// Diffs the current lines of both files.
+    pub fn update<T: AsRef<str>>(&mut self, old: &[T], new: &[T]) {
+        self.hunks = diff_lines(old, new);
+        self.old_changes = line_changes(&self.hunks, old.len(), true);
+        self.new_changes = line_changes(&self.hunks, new.len(), false);
+    }
+
+    /// Gets how the given line of the buffer changed, if the buffer is one of the two being compared.
+    pub fn change_at(&self, id: usize, row: usize) -> Option<LineChange> {
+        let changes = if id == self.old_id {
+            &self.old_changes
+        } else if id == self.new_id {
+            &self.new_changes
+        } else {
+            return None;
+        };
+
+        changes.get(row).copied().flatten()
+    }
+
+    /// Gets the id of the buffer that the given buffer is being compared with, if it is one of the two.
+    pub fn other_id(&self, id: usize) -> Option<usize> {
+        if id == self.old_id {
+            Some(self.new_id)
+        } else if id == self.new_id {
+            Some(self.old_id)
+        } else {
+            None
+        }
+    }
+
+    /// Gets the ranges of lines of each hunk in the given buffer and in the other one, in that order.
+    fn sides(&self, id: usize) -> Option<Vec<(&Range<usize>, &Range<usize>)>> {
+        if id == self.old_id {
+            Some(self.hunks.iter().map(|h| (&h.old, &h.new)).collect())
+        } else if id == self.new_id {
+            Some(self.hunks.iter().map(|h| (&h.new, &h.old)).collect())
+        } else {
+            None
+        }
+    }
+
+    /// Gets the line of the other buffer that lines up with the given line of the buffer, so that the two can be shown side by side. Lines that were replaced line up with the lines that replaced them, in order, and there is `None` for lines that have nothing to line up with (eg. added lines).
+    pub fn counterpart(&self, id: usize, row: usize) -> Option<usize> {
+        // How many more lines the other file has before this point
+        let mut offset = 0isize;
+
+        for (this, other) in self.sides(id)? {
+            if row < this.start {
+                break;
+            }
+
+            if row < this.end {
+                let i = row - this.start;
+                return (i < other.len()).then_some(other.start + i);
+            }
+
+            offset = other.end as isize - this.end as isize;
+        }
+
+        row.checked_add_signed(offset)
+    }
+
+    /// Gets the number of lines of the other buffer just before the counterpart of the given line (see [`DiffView::counterpart`]) that have nothing to line up with, so are skipped when the two are shown side by side.
+    pub fn skipped_before(&self, id: usize, row: usize) -> usize {
+        self.sides(id)
+            .and_then(|sides| sides
+                .into_iter()
+                .find(|(this, _)| this.end == row)
+                .map(|(this, other)| other.len().saturating_sub(this.len()))
+            )
+            .unwrap_or(0)
+    }
+
+    /// Gets the line that each hunk starts at in the given buffer, or `None` if the buffer isn't one of the two being compared.
+    pub fn hunk_starts(&self, id: usize) -> Option<Vec<usize>> {
+        self.sides(id).map(|sides| sides
+            .into_iter()
+            .map(|(this, _)| this.start)
+            .collect()
+        )
+    }
+}
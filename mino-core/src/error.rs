@@ -3,7 +3,9 @@ use std::fmt;
 
 #[derive(Debug)]
 pub enum Error {
-    Io(io::ErrorKind)
+    Io(io::ErrorKind),
+    Binary,
+    Command(String)
 }
 
 impl From<io::Error> for Error {
@@ -20,10 +22,12 @@ impl fmt::Display for Error {
                 io::ErrorKind::PermissionDenied => "Permission denied",
                 io::ErrorKind::AlreadyExists    => "File already exists",
                 _                               => &format!("{}", err)
-            } 
+            },
+            Self::Binary => "Cannot open binary file",
+            Self::Command(msg) => msg
         };
 
-        write!(f, " \x1b[31mError:\x1b[31m {}", err_msg)
+        write!(f, "{}", err_msg)
     }
 }
 
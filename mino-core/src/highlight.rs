@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ptr;
+
+use crate::{lang::Syntax, style::{Style}, theme::Theme};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Highlight {
+    syntax: SyntaxHighlight,
+    select: SelectHighlight
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntaxHighlight {
+    Normal,
+    Number,
+    String,
+    Comment,
+    DocComment,
+    Keyword,
+    Flowword,
+    Type,
+    Metaword,
+    Ident,
+    Function,
+    Path,
+    /// A bracket, with how deeply it is nested (for rainbow brackets)
+    Bracket(u8)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectHighlight {
+    Normal,
+    Search,
+    Select
+}
+
+impl Highlight {
+    pub const NORMAL: Self = Self {
+        syntax: SyntaxHighlight::Normal,
+        select: SelectHighlight::Normal
+    };
+    
+    pub fn to_style(&self, theme: &Theme) -> Style {
+        let mut style = match self.syntax {
+            SyntaxHighlight::Normal     => *theme.normal(),
+            SyntaxHighlight::Number     => *theme.number(),
+            SyntaxHighlight::String     => *theme.string(),
+            SyntaxHighlight::Comment    => *theme.comment(),
+            SyntaxHighlight::DocComment => *theme.doc_comment(),
+            SyntaxHighlight::Keyword    => *theme.keyword(),
+            SyntaxHighlight::Flowword   => *theme.flowword(),
+            SyntaxHighlight::Type       => *theme.common_type(),
+            SyntaxHighlight::Metaword   => *theme.metaword(),
+            SyntaxHighlight::Ident      => *theme.ident(),
+            SyntaxHighlight::Function   => *theme.function(),
+            SyntaxHighlight::Path       => *theme.path(),
+            SyntaxHighlight::Bracket(depth) => {
+                let colors = theme.brackets();
+                let mut style = *theme.normal();
+                style.set_fg(colors[depth as usize % colors.len()]);
+
+                style
+            }
+        };
+
+        match self.select {
+            SelectHighlight::Normal => (),
+            SelectHighlight::Search => style.set_bg(*theme.search()),
+            SelectHighlight::Select => style.set_bg(*theme.select())
+        }
+
+        // A highlight in the theme's text color (eg. the monochrome theme's search) shows its text in the background color, like reverse video
+        if style.bg() == theme.fg() {
+            style.set_fg(*theme.bg());
+        }
+
+        style
+    }
+
+    pub fn from_syntax_hl(syntax: SyntaxHighlight) -> Self {
+        Self {
+            syntax,
+            select: SelectHighlight::default()
+        }
+    }
+
+    pub fn from_select_hl(select: SelectHighlight) -> Self {
+        Self {
+            syntax: SyntaxHighlight::default(),
+            select
+        }
+    }
+
+    pub fn new(syntax: SyntaxHighlight, select: SelectHighlight) -> Self {
+        Self {
+            syntax,
+            select
+        }
+    }
+
+    pub fn syntax_hl(&self) -> SyntaxHighlight {
+        self.syntax
+    }
+
+    pub fn set_syntax_hl(&mut self, syntax: SyntaxHighlight) {
+        self.syntax = syntax
+    }
+
+    pub fn set_select_hl(&mut self, select: SelectHighlight) {
+        self.select = select;
+    }
+
+    pub fn select_hl(&self) -> SelectHighlight {
+        self.select
+    }
+}
+
+impl Default for Highlight {
+    fn default() -> Self {
+        Highlight {
+            syntax: SyntaxHighlight::default(),
+            select: SelectHighlight::default()
+        }
+    }
+}
+
+impl Default for SyntaxHighlight {
+    fn default() -> Self {
+        SyntaxHighlight::Normal
+    }
+}
+
+impl Default for SelectHighlight {
+    fn default() -> Self {
+        SelectHighlight::Normal
+    }
+}
+
+/// The multi-line state that a row's highlighting starts or ends in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct HlState {
+    /// The number of nested multi-line comments that are open
+    pub nested_comments: u32,
+    /// What ends the outermost open comment, if it is a doc comment (eg. `*/` for `/**`)
+    pub doc_end: Option<&'static str>,
+    /// The number of brackets that are open, which is only counted for rainbow brackets
+    pub bracket_depth: u32,
+    /// The quote and the number of `#`s after it that close the open raw string (eg. `"` and 1 for `r#"`), if there is one
+    pub raw_string: Option<(char, u32)>
+}
+
+/// Caches the highlighting of rows by the hash of their rendered text and the [`HlState`] they start in, so that rows with the same contents (eg. after an undo, or blank lines) aren't highlighted again. The text is kept too, so that rows whose hashes collide aren't given each other's highlighting.
+#[derive(Debug, Clone, Default)]
+pub struct HighlightCache {
+    syntax: Option<&'static Syntax>,
+    is_rainbow: bool,
+    entries: HashMap<(u64, HlState), (String, Vec<Highlight>, HlState)>
+}
+
+impl HighlightCache {
+    /// The maximum number of rows to cache; the cache is cleared once it gets this big.
+    const MAX_ENTRIES: usize = 4096;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the syntax that the cached highlights are for, and whether they have rainbow brackets, clearing the cache if either changed.
+    pub fn set_syntax(&mut self, syntax: &'static Syntax, is_rainbow: bool) {
+        if !self.syntax.is_some_and(|s| ptr::eq(s, syntax)) || self.is_rainbow != is_rainbow {
+            self.entries.clear();
+            self.syntax = Some(syntax);
+            self.is_rainbow = is_rainbow;
+        }
+    }
+
+    /// Gets the highlighting of a row with the given `render` text that starts in `in_state`, along with the state it ends in.
+    pub fn get(&self, render: &str, in_state: HlState) -> Option<(&[Highlight], HlState)> {
+        self.entries
+            .get(&Self::key(render, in_state))
+            .filter(|(text, ..)| text == render)
+            .map(|(_, hl, out_state)| (&hl[..], *out_state))
+    }
+
+    pub fn insert(&mut self, render: &str, in_state: HlState, hl: Vec<Highlight>, out_state: HlState) {
+        if self.entries.len() >= Self::MAX_ENTRIES {
+            self.entries.clear();
+        }
+
+        self.entries.insert(Self::key(render, in_state), (render.to_owned(), hl, out_state));
+    }
+
+    fn key(render: &str, in_state: HlState) -> (u64, HlState) {
+        let mut hasher = DefaultHasher::new();
+        render.hash(&mut hasher);
+
+        (hasher.finish(), in_state)
+    }
+}
@@ -0,0 +1,182 @@
+use circular_buffer::CircularBuffer;
+
+use crate::diff::Diff;
+use crate::util::Pos;
+
+const DEPTH: usize = 50;
+
+/// An entry in the history, made of the [`Diff`]s that are undone and redone together, along with where the cursor was before and after them, so that undoing or redoing them can put the cursor back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    diffs: Vec<Diff>,
+    cursor_before: Pos,
+    cursor_after: Pos
+}
+
+impl Edit {
+    pub fn new(diff: Diff, cursor_before: Pos, cursor_after: Pos) -> Self {
+        Self {
+            diffs: vec![diff],
+            cursor_before,
+            cursor_after
+        }
+    }
+
+    /// Gets the edit that undoes this one: each diff is inverted, in reverse order, and the cursor positions are swapped.
+    pub fn inverse(self) -> Self {
+        Self {
+            diffs: self.diffs
+                .into_iter()
+                .rev()
+                .map(Diff::inverse)
+                .collect(),
+            cursor_before: self.cursor_after,
+            cursor_after: self.cursor_before
+        }
+    }
+
+    /// Gets the diffs, in the order they were made.
+    pub fn diffs(&self) -> &[Diff] {
+        &self.diffs
+    }
+
+    pub fn cursor_after(&self) -> Pos {
+        self.cursor_after
+    }
+
+    /// Merges `next` into this edit, if both only add or only remove text in the same row, and `next` carries on from where this one left off (eg. typing, or holding backspace).
+    fn merge(&mut self, next: &Edit) -> bool {
+        let merged = match (&self.diffs[..], &next.diffs[..]) {
+            ([Diff::Insert(p1, a)], [Diff::Insert(p2, b)]) 
+                if a.len() == 1 && b.len() == 1 && *p2 == *p1 + Pos(a[0].len(), 0) => {
+                Diff::Insert(*p1, vec![a[0].clone() + &b[0]])
+            }
+            // Backspace
+            ([Diff::Remove(p1, a)], [Diff::Remove(p2, b)]) 
+                if a.len() == 1 && b.len() == 1 && *p2 + Pos(b[0].len(), 0) == *p1 => {
+                Diff::Remove(*p2, vec![b[0].clone() + &a[0]])
+            }
+            // Delete
+            ([Diff::Remove(p1, a)], [Diff::Remove(p2, b)]) 
+                if a.len() == 1 && b.len() == 1 && p2 == p1 => {
+                Diff::Remove(*p1, vec![a[0].clone() + &b[0]])
+            }
+            _ => return false
+        };
+
+        self.diffs = vec![merged];
+        self.cursor_after = next.cursor_after;
+
+        true
+    }
+}
+
+/// A struct that holds the edit history of a [`TextBuffer`](crate::buffer::TextBuffer).
+#[derive(Debug)]
+pub struct History {
+    redo: Box<CircularBuffer<DEPTH, Edit>>,
+    undo: Vec<Edit>,
+    /// How many groups are open (they can be nested)
+    group_depth: usize,
+    /// Whether the open group has an entry yet, which diffs are added to
+    is_group_started: bool
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            redo: CircularBuffer::boxed(),
+            undo: Vec::with_capacity(DEPTH),
+            group_depth: 0,
+            is_group_started: false
+        }
+    }
+
+    /// Records the `diff`, which moved the cursor from `cursor_before` to `cursor_after`. If a group is open, it is added to the group's entry.
+    pub fn perform(&mut self, diff: Diff, cursor_before: Pos, cursor_after: Pos) {
+        match self.redo.back_mut() {
+            Some(edit) if self.is_group_started => {
+                edit.diffs.push(diff);
+                edit.cursor_after = cursor_after;
+            }
+            _ => {
+                if self.redo.is_full() {
+                    self.compact();
+                }
+
+                self.redo.push_back(Edit::new(diff, cursor_before, cursor_after));
+                self.is_group_started = self.group_depth > 0;
+            }
+        }
+
+        self.undo.clear();
+    }
+
+    /// Merges runs of small edits that carry on from each other (such as typing a word one character at a time) into single edits, to make room for more. The oldest edit is only lost if nothing can be merged.
+    fn compact(&mut self) {
+        let mut edits: Vec<Edit> = Vec::with_capacity(DEPTH);
+
+        while let Some(edit) = self.redo.pop_front() {
+            let is_merged = edits
+                .last_mut()
+                .is_some_and(|last| last.merge(&edit));
+
+            if !is_merged {
+                edits.push(edit);
+            }
+        }
+
+        for edit in edits {
+            self.redo.push_back(edit);
+        }
+    }
+
+    /// Starts a group, so that every diff performed until the matching [`History::end_group`] is undone and redone as one.
+    pub fn begin_group(&mut self) {
+        if self.group_depth == 0 {
+            self.is_group_started = false;
+        }
+
+        self.group_depth += 1;
+    }
+
+    /// Ends the group started by the matching [`History::begin_group`].
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+
+        if self.group_depth == 0 {
+            self.is_group_started = false;
+        }
+    }
+
+    pub fn redo(&mut self) -> Option<()> {
+        if self.undo.is_empty() {
+            return None;
+        }
+
+        self.redo.push_back(self.undo.pop().unwrap().inverse());
+
+        Some(())
+    }
+
+    pub fn undo(&mut self) -> Option<()> {
+        if self.redo.is_empty() {
+            return None;
+        }
+
+        self.undo.push(self.redo.pop_back().unwrap().inverse());
+
+        Some(())
+    }
+
+    pub fn current_edit(&self) -> Option<&Edit> {
+        self.redo.back()
+    }
+
+    /// Changes where the cursor goes when the last edit is redone, for when the cursor is moved somewhere other than where the edit left it.
+    pub fn set_cursor_after(&mut self, cursor: Pos) {
+        if let Some(edit) = self.redo.back_mut() {
+            edit.cursor_after = cursor;
+        }
+    }
+}
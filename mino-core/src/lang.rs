@@ -0,0 +1,502 @@
+use bitflags::bitflags;
+
+use crate::bitexpr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Text,
+    C,
+    Cpp,
+    Rust,
+    Python,
+    Js,
+    Ts,
+    Csv,
+    Tsv,
+    Markdown,
+    GitCommit,
+    GitRebase,
+    Unknown
+}
+
+impl Language {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Text      => "Text",
+            Self::C         => "C",
+            Self::Cpp       => "Cpp",
+            Self::Rust      => "Rust",
+            Self::Python    => "Python",
+            Self::Js        => "Js",
+            Self::Ts        => "Ts",
+            Self::Csv       => "Csv",
+            Self::Tsv       => "Tsv",
+            Self::Markdown  => "Markdown",
+            Self::GitCommit => "GitCommit",
+            Self::GitRebase => "GitRebase",
+            Self::Unknown   => "?"
+        }
+    }
+
+    pub const fn ext(&self) -> &'static [&'static str] {
+        match self {
+            Self::Text      => &["txt"],
+            Self::C         => &["c", "h"],
+            Self::Cpp       => &["cc", "cpp", "c++", "hh", "hpp", "h++"],
+            Self::Rust      => &["rs"],
+            Self::Python    => &["py"],
+            Self::Js        => &["js", "jsx"],
+            Self::Ts        => &["ts", "d.ts", "tsx"],
+            Self::Csv       => &["csv"],
+            Self::Tsv       => &["tsv"],
+            Self::Markdown  => &["md", "markdown"],
+            _               => &[]
+        }
+    }
+
+    /// The names of the files (without their directories) that are in this language, for languages that are known by the file's name instead of its extension.
+    pub const fn file_names(&self) -> &'static [&'static str] {
+        match self {
+            Self::GitCommit => &["COMMIT_EDITMSG", "MERGE_MSG", "TAG_EDITMSG", "SQUASH_MSG", "EDIT_DESCRIPTION"],
+            Self::GitRebase => &["git-rebase-todo"],
+            _               => &[]
+        }
+    }
+
+    /// The columns (starting at 1) that rulers are drawn at in this language, in place of the ones in the config. Commit messages have them just past the 50 chars that the subject line should fit in, and the 72 chars that the body is wrapped at.
+    pub const fn rulers(&self) -> &'static [usize] {
+        match self {
+            Self::GitCommit => &[51, 73],
+            _               => &[]
+        }
+    }
+
+    /// The delimiter separating the cells of a row, for languages that can be shown as a table.
+    pub const fn table_delim(&self) -> Option<char> {
+        match self {
+            Self::Csv       => Some(','),
+            Self::Tsv       => Some('\t'),
+            _               => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Syntax {
+    lang: &'static Language,
+    keywords: &'static [&'static str],
+    /// Keywords used for contol flow: if empty they're part of the keywords attribute instead
+    flow_keywords: &'static [&'static str],
+    /// Common types (basically keywords but a different color)
+    common_types: &'static [&'static str],
+    /// Keywords used for metaprogramming (eg. macros, '#include')
+    meta_keywords: &'static [&'static str],
+    /// Paths used for accessing or modules (eg. `std::`), styles the ident prior
+    path_access_delims: &'static [&'static str],
+    ln_comment: Option<&'static str>,
+    /// Format: Option<(Start, End)>
+    multi_comment: Option<(&'static str, &'static str)>,
+    /// Single-line comments that are documentation, which only count at the start of a row
+    doc_comments: &'static [&'static str],
+    /// Multi-line comments (or strings, eg. Python docstrings) that are documentation, which only count at the start of a row. Format: &[(Start, End)]
+    multi_doc_comments: &'static [(&'static str, &'static str)],
+    /// Prefixes of numbers in other bases, matched in any case (eg. `0x` for hex). Each must end in `x`, `o` or `b`
+    number_prefixes: &'static [&'static str],
+    /// Suffixes that can end a number, matched in any case (eg. `u8`, `f32`)
+    number_suffixes: &'static [&'static str],
+    /// Char that can separate the digits of a number (eg. `_` in `1_000_000`)
+    digit_sep: Option<char>,
+    /// Brackets that open and close a nested part, which are paired up when typed, matched, and colored by how deeply they are nested. Format: &[(Open, Close)]
+    bracket_pairs: &'static [(char, char)],
+    /// Chars that start and end strings on a single row, which are paired up when typed
+    string_delims: &'static [char],
+    /// Text that the next row is indented one more level after, when it ends a row (eg. `{`, or `:` in Python)
+    indent_triggers: &'static [&'static str],
+    flags: u16
+}
+
+bitflags! {
+    /// Struct that holds flags/modifiers for the language's syntax
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct SyntaxFlags: u16 {
+        const HIGHLIGHT_NUMBERS    = 0b0000_0000_0000_0001;  // Whether to highlight numbers
+        const HIGHLIGHT_STRINGS    = 0b0000_0000_0000_0010;  // Whether to highlight strings
+        const HIGHLIGHT_IDENTS     = 0b0000_0000_0000_0100;  // Whether to highlight identifiers
+        const NESTED_COMMENTS      = 0b0000_0000_0000_1000;  // Whether to allow nested multiline comments
+        const CAPITAL_AS_TYPES     = 0b0000_0000_0001_0000;  // Whether to treat words starting with capitals as types
+        const RAW_STRINGS          = 0b0000_0000_0010_0000;  // Whether there are raw strings (eg. `r#"..."#`), which have no escapes
+        const HASH_COMMENTS_ONLY   = 0b0000_0000_0100_0000;  // Whether `#` starts the only kind of comment
+        const INDENT_SIGNIFICANT   = 0b0000_0000_1000_0000;  // Whether indentation is part of the syntax (eg. it makes blocks)
+        const TRIPLE_QUOTE_STRINGS = 0b0000_0001_0000_0000;  // Whether there are strings in triple quotes, which can span lines
+        const BYTE_STRINGS         = 0b0000_0010_0000_0000;  // Whether strings and chars can have a `b` prefix (eg. `b"..."`)
+        const LIFETIMES            = 0b0000_0100_0000_0000;  // Whether `'` also starts lifetimes and labels (eg. `'a`) when it isn't a char literal
+        const LINE_START_COMMENTS  = 0b0000_1000_0000_0000;  // Whether single-line comments only start at the very start of a row
+        const RAW_ESCAPED_QUOTES   = 0b0001_0000_0000_0000;  // Whether a quote after a `\` doesn't end a raw string, though the `\` is kept (eg. `r"\""` in Python)
+        const RAW_MULTILINE        = 0b0010_0000_0000_0000;  // Whether raw strings can go on over several rows, instead of ending with the row
+        const NONE                 = 0b0000_0000_0000_0000;
+    }
+}
+
+impl Syntax {
+    pub const SYNTAX_SET: [&'static Syntax; 12] = [Self::TEXT, Self::C, Self::CPP, Self::RUST, Self::PYTHON, Self::JS, Self::Ts, Self::CSV, Self::TSV, Self::MARKDOWN, Self::GIT_COMMIT, Self::GIT_REBASE];
+
+    /// The brackets used by most languages.
+    const BRACKETS: &'static [(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}')];
+
+    pub const TEXT: &'static Syntax = &Syntax {
+        lang: &Language::Text,
+        keywords: &[],
+        flow_keywords: &[],
+        common_types: &[],
+        meta_keywords: &[],
+        path_access_delims: &[],
+        ln_comment: None,
+        multi_comment: None,
+        doc_comments: &[],
+        multi_doc_comments: &[],
+        number_prefixes: &[],
+        number_suffixes: &[],
+        digit_sep: None,
+        bracket_pairs: Self::BRACKETS,
+        string_delims: &['"'],
+        indent_triggers: &[],
+        flags: bitexpr!(SyntaxFlags: NONE)
+    };
+    
+    pub const C: &'static Self = &Self {
+        lang: &Language::C,
+        keywords: &["struct", "union", "typedef", "const", "static", "enum", "restrict", "__LINE__", "__FILE__", "__DATE__", "__TIME__", "__STDC__", "__STDC_HOSTED__", "__STDC_VERSION__"],
+        flow_keywords: &["switch", "if", "while", "for", "break", "continue", "return", "else", "case"],
+        common_types: &["int", "long", "double", "float", "char", "unsigned", "signed", "void", "size_t"],
+        meta_keywords: &["#define", "#include", "#undef", "#ifdef", "#ifndef", "#if", "#elif", "#else", "#endif", "#line", "#error", "#warning", "region", "endregion", "#pragma"],
+        path_access_delims: &[],
+        ln_comment: Some("//"),
+        multi_comment: Some(("/*", "*/")),
+        doc_comments: &["///", "//!"],
+        multi_doc_comments: &[("/**", "*/"), ("/*!", "*/")],
+        number_prefixes: &["0x", "0b"],
+        number_suffixes: &["u", "l", "ul", "lu", "ll", "ull", "llu", "f"],
+        digit_sep: None,
+        bracket_pairs: Self::BRACKETS,
+        string_delims: &['"', '\''],
+        indent_triggers: &["{", "(", "["],
+        flags: bitexpr! {
+            SyntaxFlags :
+            HIGHLIGHT_NUMBERS | 
+            HIGHLIGHT_STRINGS |
+            HIGHLIGHT_IDENTS
+        }
+    };
+
+    pub const CPP: &'static Self = &Self {
+        lang: &Language::Cpp,
+        keywords: &["alignas", "alignof", "asm", "__asm", "__asm__", "class", "concept", "consteval", "constexpr", "constinit", "const_case", "decltype", "dynamic_cast", "enum", "explicit", "export", "extern", "false", "float", "friend", "inline", "mutable", "namespace", "noexcept", "nullptr", "private", "protected", "public", "register", "reinterpret_cast", "requires", "sizeof", "static", "static_assert", "static_cast", "struct", "template", "this", "thread_local", "typedef", "typeid", "typename", "union", "virtual", "volatile", "true", "__LINE__", "__FILE__", "__DATE__", "__TIME__", "__STDC__", "__STDC_HOSTED__", "__STDC_VERSION__", "__STDC_MB_MIGHT_NEW_WC__", "__STDC_ISO_10646__", "__STDCPP_STRICT_POINTER_SAFETY__", "__STD_CPP_THREADS__", "__cplusplus"],
+        flow_keywords: &["break", "case", "catch", "continue", "co_await", "co_return", "co_yield", "default", "delete", "do", "esle", "for", "goto", "if", "new", "return", "switch", "throw", "try", "using", "while"],
+        common_types: &["int", "float", "char", "double", "void", "bool", "auto", "long", "signed", "unsigned", "size_t", "short", "wchar_t", "char8_t", "char16_t", "char32_t"],
+        meta_keywords: &["#define", "#include", "#undef", "#ifdef", "#ifndef", "#if", "#elif", "#else", "#endif", "#line", "#error", "#warning", "region", "endregion", "#pragma"],
+        path_access_delims: &["::"],
+        ln_comment: Some("//"),
+        multi_comment: Some(("/*", "*/")),
+        doc_comments: &["///", "//!"],
+        multi_doc_comments: &[("/**", "*/"), ("/*!", "*/")],
+        number_prefixes: &["0x", "0b"],
+        number_suffixes: &["u", "l", "ul", "lu", "ll", "ull", "llu", "f", "z", "uz"],
+        digit_sep: Some('\''),
+        bracket_pairs: Self::BRACKETS,
+        string_delims: &['"', '\''],
+        indent_triggers: &["{", "(", "["],
+        flags: bitexpr! {
+            SyntaxFlags :
+            HIGHLIGHT_NUMBERS |
+            HIGHLIGHT_STRINGS |
+            HIGHLIGHT_IDENTS
+        }
+    };
+
+    pub const RUST: &'static Self = &Self {
+        lang: &Language::Rust,
+        keywords: &["as", "const", "crate", "enum" , "extern", "false", "fn", "impl", "let", "mod", "move", "mut", "pub", "ref", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "Some", "None", "Err", "Ok", "'static", "'_"], 
+        flow_keywords: &["break", "continue", "else", "for", "if", "in", "loop", "match", "return", "while"],
+        common_types: &["u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "usize", "isize", "str", "bool", "String", "Vec"],
+        meta_keywords: &["print!", "println!", "eprint!", "eprintln!", "env!", "macro_rules!", "vec!"], // not all, just some common ones
+        path_access_delims: &["::"],
+        ln_comment: Some("//"),
+        multi_comment: Some(("/*", "*/")),
+        doc_comments: &["///", "//!"],
+        multi_doc_comments: &[("/**", "*/"), ("/*!", "*/")],
+        number_prefixes: &["0x", "0o", "0b"],
+        number_suffixes: &["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64"],
+        digit_sep: Some('_'),
+        bracket_pairs: Self::BRACKETS,
+        // `'` also starts lifetimes, which aren't closed
+        string_delims: &['"'],
+        indent_triggers: &["{", "(", "["],
+        flags: bitexpr! { 
+            SyntaxFlags :
+            HIGHLIGHT_NUMBERS | 
+            HIGHLIGHT_STRINGS |
+            HIGHLIGHT_IDENTS  |
+            NESTED_COMMENTS   |
+            CAPITAL_AS_TYPES  |
+            RAW_STRINGS       |
+            RAW_MULTILINE     |
+            BYTE_STRINGS      |
+            LIFETIMES
+        }
+    };
+
+    pub const PYTHON: &'static Self = &Self {
+        lang: &Language::Python,
+        keywords: &[],
+        flow_keywords: &[],
+        common_types: &[],
+        meta_keywords: &[],
+        path_access_delims: &[],
+        ln_comment: Some("#"),
+        multi_comment: None,
+        doc_comments: &[],
+        multi_doc_comments: &[("\"\"\"", "\"\"\""), ("'''", "'''")],
+        number_prefixes: &["0x", "0o", "0b"],
+        number_suffixes: &["j"],
+        digit_sep: Some('_'),
+        bracket_pairs: Self::BRACKETS,
+        string_delims: &['"', '\''],
+        indent_triggers: &[":", "{", "(", "["],
+        flags: bitexpr! {
+            SyntaxFlags :
+            HIGHLIGHT_NUMBERS  |
+            HIGHLIGHT_STRINGS  |
+            HIGHLIGHT_IDENTS   |
+            RAW_STRINGS        |
+            RAW_ESCAPED_QUOTES |
+            BYTE_STRINGS       |
+            HASH_COMMENTS_ONLY |
+            INDENT_SIGNIFICANT |
+            TRIPLE_QUOTE_STRINGS
+        }
+    };
+
+    pub const JS: &'static Self = &Self {
+        lang: &Language::Js,
+        keywords: &["class", "const", "debugger", "delete", "export", "extends", "function", "in", "instanceof", "null", "undefined", "super", "this", "true", "false", "typeof", "var", "void", "let", "static", "async"],
+        flow_keywords: &["break", "case", "catch", "continue", "default", "do", "else", "finally", "for", "if", "import", "as", "new", "return", "switch", "throw", "try", "while", "with", "yield", "await"],
+        common_types: &[],
+        meta_keywords: &[],
+        path_access_delims: &[],
+        ln_comment: Some("//"),
+        multi_comment: Some(("/*", "*/")),
+        doc_comments: &[],
+        multi_doc_comments: &[("/**", "*/")],
+        number_prefixes: &["0x", "0o", "0b"],
+        number_suffixes: &["n"],
+        digit_sep: Some('_'),
+        bracket_pairs: Self::BRACKETS,
+        string_delims: &['"', '\'', '`'],
+        indent_triggers: &["{", "(", "["],
+        flags: bitexpr! {
+            SyntaxFlags :
+            HIGHLIGHT_NUMBERS |
+            HIGHLIGHT_STRINGS |
+            HIGHLIGHT_IDENTS  |
+            CAPITAL_AS_TYPES
+        }
+    };
+
+    pub const Ts: &'static Self = &Self {
+        lang: &Language::Ts,
+        keywords: &["class", "const", "debugger", "delete", "export", "extends", "function", "in", "instanceof", "null", "undefined", "super", "this", "true", "false", "typeof", "var", "void", "let", "static", "async", "enum", "implements", "interface", "package", "protected", "private", "public", "constructor", "declare", "get", "module", "require", "set", "type"],
+        flow_keywords: &["break", "case", "catch", "continue", "default", "do", "else", "finally", "for", "if", "import", "as", "new", "return", "switch", "throw", "try", "while", "with", "yield", "await", "of"],
+        common_types: &["any", "boolean", "number", "string", "symbol"],
+        meta_keywords: &[],
+        path_access_delims: &[],
+        ln_comment: Some("//"),
+        multi_comment: Some(("/*", "*/")),
+        doc_comments: &[],
+        multi_doc_comments: &[("/**", "*/")],
+        number_prefixes: &["0x", "0o", "0b"],
+        number_suffixes: &["n"],
+        digit_sep: Some('_'),
+        bracket_pairs: Self::BRACKETS,
+        string_delims: &['"', '\'', '`'],
+        indent_triggers: &["{", "(", "["],
+        flags: bitexpr! {
+            SyntaxFlags :
+            HIGHLIGHT_NUMBERS |
+            HIGHLIGHT_STRINGS |
+            HIGHLIGHT_IDENTS  |
+            CAPITAL_AS_TYPES
+        }
+    };
+
+    pub const CSV: &'static Self = &Self {
+        lang: &Language::Csv,
+        ..*Self::TEXT
+    };
+
+    pub const TSV: &'static Self = &Self {
+        lang: &Language::Tsv,
+        ..*Self::TEXT
+    };
+
+    pub const MARKDOWN: &'static Self = &Self {
+        lang: &Language::Markdown,
+        ..*Self::TEXT
+    };
+
+    /// Commit messages, where git leaves out the lines starting with `#`.
+    pub const GIT_COMMIT: &'static Self = &Self {
+        lang: &Language::GitCommit,
+        ln_comment: Some("#"),
+        flags: bitexpr!(SyntaxFlags: LINE_START_COMMENTS),
+        ..*Self::TEXT
+    };
+
+    /// The list of commits that `git rebase -i` asks what to do with, one command on each line, which doesn't have the rulers of a commit message.
+    pub const GIT_REBASE: &'static Self = &Self {
+        lang: &Language::GitRebase,
+        ..*Self::GIT_COMMIT
+    };
+
+    pub const UNKNOWN: &'static Self = &Self {
+        lang: &Language::Unknown,
+        ..*Self::TEXT
+    };
+
+    pub const fn name(&self) -> &'static str {
+        self.lang.name()
+    }
+
+    pub const fn ext(&self) -> &'static [&'static str] {
+        self.lang.ext()
+    }
+
+    pub fn select_syntax(ext: &str) -> &'static Syntax {
+        for syntax in Self::SYNTAX_SET {
+            if syntax.ext().contains(&ext) {
+                return syntax;
+            }
+        }
+
+        Self::UNKNOWN
+    }
+
+    /// Finds the syntax for a file called `name` (without its directories), for languages that are known by the file's name (see [`Language::file_names`]).
+    pub fn from_file_name(name: &str) -> Option<&'static Syntax> {
+        Self::SYNTAX_SET
+            .into_iter()
+            .find(|syntax| syntax.lang().file_names().contains(&name))
+    }
+
+    /// Finds the syntax with the given name (eg. from a modeline), which can also be one of its extensions, or its full name in Vim and Emacs (eg. `javascript`). Case is ignored.
+    pub fn from_name(name: &str) -> Option<&'static Syntax> {
+        let name = name.to_ascii_lowercase();
+        let name = match name.as_str() {
+            "javascript"    => "js",
+            "typescript"    => "ts",
+            "text"          => "txt",
+            name            => name
+        };
+
+        Self::SYNTAX_SET
+            .into_iter()
+            .find(|syntax| syntax.name().eq_ignore_ascii_case(name) || syntax.ext().contains(&name))
+    }
+
+    pub fn lang(&self) -> &'static Language {
+        self.lang
+    }
+
+    pub fn keywords(&self) -> &'static [&'static str] {
+        self.keywords
+    }
+
+    pub fn flowwords(&self) -> &'static [&'static str] {
+        self.flow_keywords
+    }
+
+    pub fn common_types(&self) -> &'static [&'static str] {
+        self.common_types
+    }
+    
+    pub fn metawords(&self) -> &'static [&'static str] {
+        self.meta_keywords
+    }
+
+    pub fn path_delims(&self) -> &'static [&'static str] {
+        self.path_access_delims
+    }
+
+    pub fn ln_comment(&self) -> Option<&'static str> {
+        self.ln_comment
+    }
+
+    pub fn multi_comment(&self) -> Option<(&'static str, &'static str)> {
+        self.multi_comment
+    }
+
+    pub fn doc_comments(&self) -> &'static [&'static str] {
+        self.doc_comments
+    }
+
+    pub fn multi_doc_comments(&self) -> &'static [(&'static str, &'static str)] {
+        self.multi_doc_comments
+    }
+
+    pub fn number_prefixes(&self) -> &'static [&'static str] {
+        self.number_prefixes
+    }
+
+    pub fn number_suffixes(&self) -> &'static [&'static str] {
+        self.number_suffixes
+    }
+
+    pub fn digit_sep(&self) -> Option<char> {
+        self.digit_sep
+    }
+
+    pub fn bracket_pairs(&self) -> &'static [(char, char)] {
+        self.bracket_pairs
+    }
+
+    /// Gets the bracket that closes `open`, if it opens one of the language's bracket pairs.
+    pub fn closing_bracket(&self, open: char) -> Option<char> {
+        self.bracket_pairs
+            .iter()
+            .find(|(o, _)| *o == open)
+            .map(|(_, close)| *close)
+    }
+
+    /// Gets whether `ch` closes one of the language's bracket pairs.
+    pub fn is_closing_bracket(&self, ch: char) -> bool {
+        self.bracket_pairs
+            .iter()
+            .any(|(_, close)| *close == ch)
+    }
+
+    pub fn string_delims(&self) -> &'static [char] {
+        self.string_delims
+    }
+
+    pub fn indent_triggers(&self) -> &'static [&'static str] {
+        self.indent_triggers
+    }
+
+    /// Gets whether the row after `text` should be indented one more level than it.
+    pub fn triggers_indent(&self, text: &str) -> bool {
+        let text = text.trim_end();
+
+        self.indent_triggers
+            .iter()
+            .any(|trigger| text.ends_with(trigger))
+    }
+
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+}
+
+pub fn is_sep(ch: char) -> bool {
+    ch.is_ascii_whitespace() || 
+    ch == '\0' ||
+    ch.is_ascii_punctuation() && ch != '_'
+}
@@ -0,0 +1,26 @@
+//! The editing engine of mino, kept apart from the terminal so that it can be embedded elsewhere and tested on its own.
+//!
+//! The main types are:
+//! - [`TextBuffer`](buffer::TextBuffer), the contents of a file, split into [`Row`](buffer::Row)s, with the edits made to it recorded in its [`History`](history::History) as [`Diff`](diff::Diff)s
+//! - [`Syntax`](lang::Syntax), the rules for highlighting a language, which the rows are highlighted with (see [`Highlight`](highlight::Highlight))
+//! - [`Theme`](theme::Theme), the colors and font styles that highlights are drawn in
+//!
+//! Rows are rendered with the settings of a [`RenderConfig`](buffer::RenderConfig), which the editor implements for its config.
+
+pub mod buffer;
+pub mod diff;
+pub mod error;
+pub mod highlight;
+pub mod history;
+pub mod lang;
+pub mod lint;
+pub mod loader;
+pub mod log;
+pub mod mapped;
+pub mod modeline;
+pub mod paths;
+pub mod style;
+pub mod table;
+pub mod theme;
+pub mod url;
+pub mod util;
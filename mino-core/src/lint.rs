@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use crate::error::{self, Error};
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error
+}
+
+/// A problem reported by a linter for a specific row of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    row: usize,
+    col: usize,
+    msg: String,
+    severity: Severity
+}
+
+impl Diagnostic {
+    pub fn new(row: usize, col: usize, msg: String, severity: Severity) -> Self {
+        Self { row, col, msg, severity }
+    }
+
+    /// The row the diagnostic is for (starting at 0).
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// The column the diagnostic is for (starting at 0).
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+}
+
+/// Runs the given lint `command` on the file at `file_name`, and parses its output into diagnostics for that file.
+/// 
+/// The command is split on whitespace, and any `{file}` in it is replaced with `file_name`. It is run in `dir`, or the current directory if that is empty.
+pub fn run(command: &str, dir: &Path, file_name: &str) -> error::Result<Vec<Diagnostic>> {
+    let mut parts = command
+        .split_whitespace()
+        .map(|part| part.replace("{file}", file_name));
+
+    let program = match parts.next() {
+        Some(program) => program,
+        None => return Err(Error::Command("Lint command is empty".to_owned()))
+    };
+
+    // Linters usually exit unsuccessfully when they find problems, so the exit status is ignored
+    let mut cmd = Command::new(&program);
+    if !dir.as_os_str().is_empty() {
+        cmd.current_dir(dir);
+    }
+
+    let output = cmd
+        .args(parts)
+        .output()
+        .map_err(|err| Error::Command(format!("Could not run '{program}': {err}")))?;
+
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+
+    Ok(parse(&text, file_name))
+}
+
+/// Runs a lint command on another thread, so that a slow linter doesn't freeze the editor (eg. when linting on save).
+#[derive(Debug)]
+pub struct Linting {
+    rx: Receiver<error::Result<Vec<Diagnostic>>>
+}
+
+impl Linting {
+    /// Starts running the lint `command` on the file at `file_name`, as [`run`] does.
+    pub fn spawn(command: String, dir: PathBuf, file_name: String) -> Self {
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            // The receiver is gone if the editor stopped waiting for the result
+            let _ = tx.send(run(&command, &dir, &file_name));
+        });
+
+        Self { rx }
+    }
+
+    /// Gets the diagnostics if the linter has finished, without waiting.
+    pub fn try_result(&self) -> Option<error::Result<Vec<Diagnostic>>> {
+        match self.rx.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(Error::Command("Linter stopped unexpectedly".to_owned())))
+        }
+    }
+}
+
+/// Parses linter output made up of `file:line:message` or `file:line:col:message` lines, keeping only the diagnostics for `file_name`.
+pub fn parse(output: &str, file_name: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = output
+        .lines()
+        .filter_map(parse_line)
+        .filter(|(file, ..)| is_same_file(file, file_name))
+        .map(|(_, line, col, msg)| {
+            let severity = if msg.to_lowercase().contains("error") {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+
+            Diagnostic::new(line.saturating_sub(1), col.saturating_sub(1), msg.to_owned(), severity)
+        })
+        .collect();
+
+    diagnostics.sort_by_key(|d| (d.row, d.col));
+    diagnostics
+}
+
+/// Splits a `file:line[:col]:message` line into its parts.
+fn parse_line(line: &str) -> Option<(&str, usize, usize, &str)> {
+    // The file name may itself contain `:` (eg. `C:\`), so look for the first `:<number>:`
+    for (i, _) in line.match_indices(':') {
+        let rest = &line[i + 1..];
+        let (num, after) = rest.split_once(':')?;
+
+        let line_num = match num.trim().parse::<usize>() {
+            Ok(n) => n,
+            Err(_) => continue
+        };
+
+        let (col, msg) = match after.split_once(':') {
+            Some((col, msg)) if col.trim().parse::<usize>().is_ok() => (col.trim().parse().unwrap(), msg),
+            _ => (1, after)
+        };
+
+        return Some((&line[..i], line_num, col, msg.trim()));
+    }
+
+    None
+}
+
+fn is_same_file(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => Path::new(a).file_name() == Path::new(b).file_name()
+    }
+}
@@ -0,0 +1,142 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crate::buffer;
+use crate::error::Error;
+
+/// How many bytes are read at a time.
+const CHUNK_LEN: usize = 256 * 1024;
+
+/// What the reading thread sends back.
+#[derive(Debug)]
+pub enum Loaded {
+    /// The next lines of the file, and how many bytes of it have been read so far
+    Lines(Vec<String>, u64),
+    Done,
+    Failed(Error)
+}
+
+/// Reads a file on another thread, sending its lines back in batches, so that large files can be shown (and moved around in) while they are still being read.
+#[derive(Debug)]
+pub struct Loader {
+    rx: Receiver<Loaded>,
+    len: u64,
+    bytes_read: u64
+}
+
+impl Loader {
+    /// Starts reading the file at `path`. The file is opened first, so that errors like it not existing are returned straight away.
+    pub fn spawn(path: &str) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || read_lines(file, &tx));
+
+        Ok(Self {
+            rx,
+            len,
+            bytes_read: 0
+        })
+    }
+
+    /// Gets how much of the file has been read, as a percentage.
+    pub fn progress(&self) -> u64 {
+        (self.bytes_read * 100).checked_div(self.len).unwrap_or(100)
+    }
+
+    /// Gets the next batch if there is one, without waiting.
+    pub fn try_next(&mut self) -> Option<Loaded> {
+        match self.rx.try_recv() {
+            Ok(loaded) => Some(self.track(loaded)),
+            Err(TryRecvError::Empty) => None,
+            // The thread only stops after sending `Done` or `Failed`
+            Err(TryRecvError::Disconnected) => Some(Loaded::Done)
+        }
+    }
+
+    /// Waits up to `timeout` for the next batch.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<Loaded> {
+        match self.rx.recv_timeout(timeout) {
+            Ok(loaded) => Some(self.track(loaded)),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => Some(Loaded::Done)
+        }
+    }
+
+    fn track(&mut self, loaded: Loaded) -> Loaded {
+        if let Loaded::Lines(_, bytes_read) = &loaded {
+            self.bytes_read = *bytes_read;
+        }
+
+        loaded
+    }
+}
+
+/// Reads `file` in chunks, sending the complete lines in each one. Stops early if the file turns out to be binary, or the receiver is gone.
+fn read_lines(mut file: File, tx: &Sender<Loaded>) {
+    let mut buf = vec![0; CHUNK_LEN];
+    // The bytes after the last newline read, which are the start of the next line
+    let mut pending = Vec::new();
+    let mut bytes_read = 0;
+
+    loop {
+        let n = match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => {
+                let _ = tx.send(Loaded::Failed(Error::from(err)));
+                return;
+            }
+        };
+
+        bytes_read += n as u64;
+        pending.extend_from_slice(&buf[..n]);
+
+        let end = match pending.iter().rposition(|&b| b == b'\n') {
+            Some(i) => i + 1,
+            None => continue
+        };
+
+        let lines = match split_lines(&pending[..end]) {
+            Ok(lines) => lines,
+            Err(err) => {
+                let _ = tx.send(Loaded::Failed(err));
+                return;
+            }
+        };
+
+        pending.drain(..end);
+
+        if tx.send(Loaded::Lines(lines, bytes_read)).is_err() {
+            return;
+        }
+    }
+
+    match split_lines(&pending) {
+        Ok(lines) => {
+            let _ = tx.send(Loaded::Lines(lines, bytes_read));
+            let _ = tx.send(Loaded::Done);
+        }
+        Err(err) => {
+            let _ = tx.send(Loaded::Failed(err));
+        }
+    }
+}
+
+/// Splits `bytes` into lines, failing if they aren't text.
+fn split_lines(bytes: &[u8]) -> Result<Vec<String>, Error> {
+    if buffer::is_binary(bytes) {
+        return Err(Error::Binary);
+    }
+
+    Ok(String::from_utf8_lossy(bytes)
+        .lines()
+        .map(str::to_owned)
+        .collect()
+    )
+}
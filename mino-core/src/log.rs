@@ -0,0 +1,75 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::paths;
+use crate::util;
+
+/// The log file, if logging was enabled.
+static LOG_FILE: Mutex<Option<File>> = Mutex::new(None);
+
+/// How important a logged message is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Error,
+    Warn,
+    Debug
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warn  => "WARN",
+            Self::Debug => "DEBUG"
+        }
+    }
+}
+
+/// Opens the log file for appending.
+///
+/// Until this is called, logging does nothing.
+pub fn init() -> io::Result<PathBuf> {
+    let path = paths::log_file().ok_or(io::ErrorKind::NotFound)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    *LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+
+    debug(&format!("Started mino {}", env!("CARGO_PKG_VERSION")));
+
+    Ok(path)
+}
+
+/// Appends `msg` to the log file (if logging is enabled), with any escape sequences removed.
+pub fn write(level: Level, msg: &str) {
+    let mut log_file = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let Some(file) = log_file.as_mut() {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        // Nothing useful can be done if writing to the log fails
+        let _ = writeln!(file, "[{secs}] {:<5} {}", level.name(), util::strip_escapes(msg).trim());
+    }
+}
+
+pub fn error(msg: &str) {
+    write(Level::Error, msg);
+}
+
+pub fn warn(msg: &str) {
+    write(Level::Warn, msg);
+}
+
+pub fn debug(msg: &str) {
+    write(Level::Debug, msg);
+}
@@ -0,0 +1,95 @@
+use std::fs::File;
+use std::io;
+
+use memmap2::Mmap;
+
+/// A file that is mapped into memory rather than read in, so that very large files can be opened for viewing instantly. Its lines are only found as they are needed.
+#[derive(Debug)]
+pub struct MappedFile {
+    map: Mmap,
+    /// The byte index that each line found so far starts at
+    line_starts: Vec<usize>,
+    /// Whether every line has been found
+    is_indexed: bool
+}
+
+impl MappedFile {
+    pub fn open(path: &str) -> io::Result<Self> {
+        let file = File::open(path)?;
+
+        // Safety: the map is only read from. If the file is changed by another program while it is open, the rows read after that may be garbled, as in any other viewer that maps files
+        let map = unsafe { Mmap::map(&file)? };
+        let is_indexed = map.is_empty();
+
+        Ok(Self {
+            map,
+            line_starts: if is_indexed { vec![] } else { vec![0] },
+            is_indexed
+        })
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// Gets the text of line `idx` without its line ending, or `None` if the file doesn't have that many lines. Invalid UTF-8 is replaced.
+    pub fn line(&mut self, idx: usize) -> Option<String> {
+        // The start of the next line is where this one ends
+        self.index_to(idx + 2);
+
+        let start = *self.line_starts.get(idx)?;
+        let end = match self.line_starts.get(idx + 1) {
+            Some(next) => next - 1,
+            None => self.map.len()
+        };
+
+        let mut line = &self.map[start..end];
+        if line.last() == Some(&b'\n') {
+            line = &line[..line.len() - 1];
+        }
+        if line.last() == Some(&b'\r') {
+            line = &line[..line.len() - 1];
+        }
+
+        Some(String::from_utf8_lossy(line).into_owned())
+    }
+
+    /// Gets the text of the last `n` lines (or all of them, if there are fewer), without finding where the lines before them start. Like [`str::lines`], a final line ending doesn't start another line.
+    pub fn last_lines(&self, n: usize) -> Vec<String> {
+        let mut lines = vec![];
+        let mut end = self.map.len();
+        if self.map.last() == Some(&b'\n') {
+            end -= 1;
+        }
+
+        while lines.len() < n && end > 0 {
+            let start = self.map[..end].iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+
+            let mut line = &self.map[start..end];
+            if line.last() == Some(&b'\r') {
+                line = &line[..line.len() - 1];
+            }
+            lines.push(String::from_utf8_lossy(line).into_owned());
+
+            if start == 0 {
+                break;
+            }
+            end = start - 1;
+        }
+
+        lines.reverse();
+        lines
+    }
+
+    /// Finds where the lines start, until `num_lines` lines have been found or the end of the file is reached. Like [`str::lines`], a final line ending doesn't start another line.
+    fn index_to(&mut self, num_lines: usize) {
+        while !self.is_indexed && self.line_starts.len() < num_lines {
+            let from = *self.line_starts.last().unwrap_or(&0);
+
+            match self.map[from..].iter().position(|&b| b == b'\n') {
+                Some(i) if from + i + 1 < self.map.len() => self.line_starts.push(from + i + 1),
+                _ => self.is_indexed = true
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::lang::Syntax;
+
+/// How many lines at the start and at the end of a file are looked in for modelines, as in Vim.
+pub const MODELINE_LINES: usize = 5;
+
+/// The widest tab stop a modeline can set. Wider ones are ignored, as the file can't be trusted to ask for something reasonable (eg. `ts=99999999999` would make every tab that many spaces).
+pub const MAX_TAB_STOP: usize = 32;
+
+/// Settings for a single file, given in the file itself by a Vim modeline (eg. `# vim: set ts=2 ft=python:`) or an Emacs one (eg. `-*- mode: rust; tab-width: 4 -*-`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modeline {
+    syntax: Option<&'static Syntax>,
+    tab_stop: Option<usize>
+}
+
+impl Modeline {
+    /// Reads the modelines in `lines`, which should be the first and last [`MODELINE_LINES`] lines of the file. Settings in later lines override those in earlier ones, and settings that the editor doesn't have (eg. `shiftwidth`) are ignored.
+    pub fn find<'a>(lines: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut modeline = Self::default();
+
+        for line in lines {
+            for found in [Self::parse_vim(line), Self::parse_emacs(line)].into_iter().flatten() {
+                modeline.syntax = found.syntax.or(modeline.syntax);
+                modeline.tab_stop = found.tab_stop.or(modeline.tab_stop);
+            }
+        }
+
+        modeline
+    }
+
+    /// Parses a Vim modeline, which is either `vim: ts=2 ft=python` (with options separated by spaces or `:`), or `vim: set ts=2 ft=python:` (ending at the `:`). `vi:` and `ex:` work too.
+    fn parse_vim(line: &str) -> Option<Self> {
+        // The marker must start the line or come after whitespace, so that words like `envi:` aren't taken for one
+        let start = ["vim:", "vi:", "ex:"]
+            .iter()
+            .filter_map(|marker| {
+                line.match_indices(marker)
+                    .find(|(idx, _)| *idx == 0 || line[..*idx].ends_with(char::is_whitespace))
+                    .map(|(idx, marker)| idx + marker.len())
+            })
+            .min()?;
+
+        let rest = line[start..].trim_start();
+        let options: Vec<_> = match rest.strip_prefix("set ").or_else(|| rest.strip_prefix("se ")) {
+            Some(set) => set
+                .split(':')
+                .next()
+                .unwrap_or_default()
+                .split_whitespace()
+                .collect(),
+            None => rest
+                .split(|ch: char| ch == ':' || ch.is_whitespace())
+                .filter(|option| !option.is_empty())
+                .collect()
+        };
+
+        let mut modeline = Self::default();
+        for option in options {
+            match option.split_once('=') {
+                Some(("ts" | "tabstop", value)) => modeline.tab_stop = Self::parse_tab_stop(value),
+                Some(("ft" | "filetype" | "syn" | "syntax", value)) => modeline.syntax = Syntax::from_name(value),
+                _ => ()
+            }
+        }
+
+        Some(modeline)
+    }
+
+    /// Parses an Emacs modeline, which is either `-*- mode: python; tab-width: 4 -*-`, or just the mode, like `-*- python -*-`.
+    fn parse_emacs(line: &str) -> Option<Self> {
+        let start = line.find("-*-")? + 3;
+        let end = start + line[start..].find("-*-")?;
+        let vars = line[start..end].trim();
+
+        let mut modeline = Self::default();
+        if !vars.contains(':') {
+            modeline.syntax = Syntax::from_name(vars);
+            return Some(modeline);
+        }
+
+        for var in vars.split(';') {
+            match var.split_once(':').map(|(name, value)| (name.trim(), value.trim())) {
+                Some(("mode", value)) => modeline.syntax = Syntax::from_name(value),
+                Some(("tab-width", value)) => modeline.tab_stop = Self::parse_tab_stop(value),
+                _ => ()
+            }
+        }
+
+        Some(modeline)
+    }
+
+    fn parse_tab_stop(value: &str) -> Option<usize> {
+        value.parse().ok().filter(|tab_stop| (1..=MAX_TAB_STOP).contains(tab_stop))
+    }
+
+    /// Gets the syntax the file is set to, which is used instead of the one for its extension.
+    pub fn syntax(&self) -> Option<&'static Syntax> {
+        self.syntax
+    }
+
+    /// Gets the tab stop the file is set to, which is used instead of the config's.
+    pub fn tab_stop(&self) -> Option<usize> {
+        self.tab_stop
+    }
+}
@@ -0,0 +1,98 @@
+use std::env;
+use std::path::PathBuf;
+
+/// The directory that the editor's files are kept in, inside each of the platform's locations.
+const APP_DIR: &str = "mino";
+
+/// A kind of platform location that the editor keeps files in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Location {
+    /// For files the user writes, like the config file and themes
+    Config,
+    /// For files the editor writes, like the log
+    Data
+}
+
+/// Gets the platform's directory for the `location`, which is where the XDG variable for it points (if it's set), then `~/.config` or `~/.local/share` on Linux and other Unixes, and `~/Library/Application Support` on macOS.
+#[cfg(not(windows))]
+fn base_dir(location: Location) -> Option<PathBuf> {
+    let (var, fallback) = match location {
+        Location::Config => ("XDG_CONFIG_HOME", ".config"),
+        Location::Data => ("XDG_DATA_HOME", ".local/share")
+    };
+
+    if let Some(dir) = env::var_os(var).filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+
+    let home = PathBuf::from(env::var_os("HOME")?);
+    if cfg!(target_os = "macos") {
+        Some(home.join("Library/Application Support"))
+    } else {
+        Some(home.join(fallback))
+    }
+}
+
+/// Gets the platform's directory for the `location`, which is `%APPDATA%` for config, which roams between machines, and `%LOCALAPPDATA%` for data, which doesn't.
+#[cfg(windows)]
+fn base_dir(location: Location) -> Option<PathBuf> {
+    let var = match location {
+        Location::Config => "APPDATA",
+        Location::Data => "LOCALAPPDATA"
+    };
+
+    env::var_os(var)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Gets the directory that the config file and themes are in (eg. `~/.config/mino`).
+/// 
+/// On macOS, `~/.config/mino` is still used if it exists and the one in `~/Library/Application Support` doesn't, as that is where the config used to be kept.
+pub fn config_dir() -> Option<PathBuf> {
+    let dir = base_dir(Location::Config)?.join(APP_DIR);
+
+    if cfg!(target_os = "macos") && !dir.exists() {
+        let old_dir = env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join(APP_DIR));
+
+        if let Some(old_dir) = old_dir.filter(|old_dir| old_dir.is_dir()) {
+            return Some(old_dir);
+        }
+    }
+
+    Some(dir)
+}
+
+/// Gets the directory that the editor writes its own files to (eg. `~/.local/share/mino`).
+pub fn data_dir() -> Option<PathBuf> {
+    base_dir(Location::Data).map(|dir| dir.join(APP_DIR))
+}
+
+/// Gets the path of the config file.
+pub fn config_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Gets the directory that the user's own themes are in, each in a `<name>.toml` file.
+pub fn themes_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("themes"))
+}
+
+/// Gets the path of the file for the user's theme called `name`.
+pub fn theme_file(name: &str) -> Option<PathBuf> {
+    themes_dir().map(|dir| dir.join(format!("{name}.toml")))
+}
+
+/// Gets the path of the log file.
+pub fn log_file() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("mino.log"))
+}
+
+/// Gets the path of the socket that a running editor listens on for files to open, which is in `$XDG_RUNTIME_DIR` if it's set, as that is only for the user and is cleared when they log out.
+pub fn socket_file() -> Option<PathBuf> {
+    env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join(APP_DIR))
+        .or_else(data_dir)
+        .map(|dir| dir.join("mino.sock"))
+}
@@ -0,0 +1,222 @@
+use std::fmt;
+use bitflags::bitflags;
+
+use crate::theme::Theme;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    fg: Rgb,    // If none, then use the defaults according to the theme fg and bg values
+    bg: Rgb, 
+    font: FontStyle,
+}
+
+impl Style {
+    pub const RESET: &'static str       = "\x1b[0m";
+    pub const FG_RESET: &'static str    = "\x1b[39m";
+    pub const BG_RESET: &'static str    = "\x1b[49m";
+
+    pub fn new(fg: Rgb, bg: Rgb, font: FontStyle) -> Self {
+        Self { fg, bg, font }
+    }
+
+    pub fn from_fg(fg: Rgb, theme: &Theme) -> Self {        
+        Self {
+            fg: fg,
+            bg: *theme.bg(),
+            font: FontStyle::default()
+        }
+    }
+
+    pub fn from_bg(bg: Rgb, theme: &Theme) -> Self {
+        Self {
+            fg: *theme.fg(),
+            bg: bg,
+            font: FontStyle::default(),
+        }
+    }
+
+    pub fn from_font(font: FontStyle, theme: &Theme) -> Self {
+        Self {
+            fg: *theme.fg(),
+            bg: *theme.bg(),
+            font
+        }
+    }
+
+    pub fn default(theme: &Theme) -> Self {
+        Self {
+            fg: *theme.fg(),
+            bg: *theme.bg(),
+            font: FontStyle::default()
+        }
+    }
+
+    pub fn fg(&self) -> &Rgb {
+        &self.fg
+    }
+
+    pub fn set_fg(&mut self, fg: Rgb) {
+        self.fg = fg;
+    }
+
+    pub fn bg(&self) -> &Rgb {
+        &self.bg
+    }
+
+    pub fn set_bg(&mut self, bg: Rgb) {
+        self.bg = bg;
+    }
+
+    pub fn font(&self) -> FontStyle {
+        self.font
+    }
+
+    pub fn set_font(&mut self, font: FontStyle) {
+        self.font = font;
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\x1b[{}48;2;{};38;2;{}m", &self.font, &self.bg, &self.fg)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Parses a color in the form `#rrggbb`.
+    pub fn from_hex(s: &str) -> Option<Self> {
+        let hex = s.strip_prefix('#')?;
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+        Some(Self(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Formats the color in the form `#rrggbb`.
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+
+    /// The levels of each channel in the 6x6x6 color cube of the 256-color palette.
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    /// The colors of the 16 basic ANSI colors, as xterm shows them.
+    const ANSI_16: [Rgb; 16] = [
+        Rgb(0, 0, 0), Rgb(205, 0, 0), Rgb(0, 205, 0), Rgb(205, 205, 0), 
+        Rgb(0, 0, 238), Rgb(205, 0, 205), Rgb(0, 205, 205), Rgb(229, 229, 229),
+        Rgb(127, 127, 127), Rgb(255, 0, 0), Rgb(0, 255, 0), Rgb(255, 255, 0), 
+        Rgb(92, 92, 255), Rgb(255, 0, 255), Rgb(0, 255, 255), Rgb(255, 255, 255)
+    ];
+
+    /// Gets the index of the closest color in the 256-color palette, out of the color cube (16-231) and the grayscale ramp (232-255). The first 16 colors are skipped, as terminals often change them.
+    pub fn to_ansi256(self) -> u8 {
+        let nearest_level = |c: u8| Self::CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|&(_, &level)| level.abs_diff(c))
+            .map_or(0, |(i, _)| i);
+
+        let (r, g, b) = (nearest_level(self.0), nearest_level(self.1), nearest_level(self.2));
+        let cube = Rgb(Self::CUBE_LEVELS[r], Self::CUBE_LEVELS[g], Self::CUBE_LEVELS[b]);
+        let cube_idx = 16 + 36 * r + 6 * g + b;
+
+        // The grays go from 8 to 238 in steps of 10
+        let avg = (self.0 as usize + self.1 as usize + self.2 as usize) / 3;
+        let gray_step = (avg.saturating_sub(3) / 10).min(23);
+        let gray_level = (8 + gray_step * 10) as u8;
+        let gray = Rgb(gray_level, gray_level, gray_level);
+
+        if self.distance(gray) < self.distance(cube) {
+            (232 + gray_step) as u8
+        } else {
+            cube_idx as u8
+        }
+    }
+
+    /// Gets the index of the closest of the 16 basic ANSI colors.
+    pub fn to_ansi16(self) -> u8 {
+        (0..16u8)
+            .min_by_key(|&i| self.distance(Self::ANSI_16[i as usize]))
+            .unwrap_or(0)
+    }
+
+    /// Gets the squared distance between two colors, weighted by how sensitive eyes are to each channel.
+    fn distance(self, other: Rgb) -> u32 {
+        let d = |a: u8, b: u8| a.abs_diff(b) as u32 * a.abs_diff(b) as u32;
+
+        2 * d(self.0, other.0) + 4 * d(self.1, other.1) + 3 * d(self.2, other.2)
+    }
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{};{};{}", self.0, self.1, self.2)
+    }
+}
+
+bitflags! {
+    /// Represents the set of possible font styles
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct FontStyle: u8 {
+        const BOLD          = 0b0000_0001;
+        const ITALIC        = 0b0000_0010;
+        const UNDERLINE     = 0b0000_0100;
+        const STRIKETHRU    = 0b0000_1000;
+        const DIM           = 0b0001_0000;
+        const REVERSE       = 0b0010_0000;
+        const NONE          = 0b0000_0000; // Beware, `contains(Self::NONE)` will always be true, even if another is set.
+    }
+}
+
+impl FontStyle {
+    pub const RESET: &'static str = "\x1b[m";
+
+    /// Parses the font style with the given lowercase name (eg. `bold`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "bold"          => Some(Self::BOLD),
+            "italic"        => Some(Self::ITALIC),
+            "underline"     => Some(Self::UNDERLINE),
+            "strikethrough" => Some(Self::STRIKETHRU),
+            "dim"           => Some(Self::DIM),
+            "reverse"       => Some(Self::REVERSE),
+            "none"          => Some(Self::NONE),
+            _               => None
+        }
+    }
+}
+
+impl Default for FontStyle {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+impl fmt::Display for FontStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str(";");
+        }
+
+        for (style, code) in [
+            (Self::BOLD, "1;"), 
+            (Self::ITALIC, "3;"), 
+            (Self::UNDERLINE, "4;"), 
+            (Self::STRIKETHRU, "9;"), 
+            (Self::DIM, "2;"),
+            (Self::REVERSE, "7;")
+        ] {
+            if self.contains(style) {
+                f.write_str(code)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use std::ops;
+use unicode_width::UnicodeWidthStr;
+
+use crate::buffer::Row;
+
+/// Gets the byte ranges of each cell in a delimiter-separated `line`.
+/// 
+/// For comma-separated lines, delimiters inside double quotes are treated as part of the cell.
+pub fn cell_ranges(line: &str, delim: char) -> Vec<ops::Range<usize>> {
+    let mut ranges = vec![];
+    let mut start = 0;
+    let mut in_quotes = false;
+
+    for (i, ch) in line.char_indices() {
+        if ch == '"' && delim == ',' {
+            in_quotes = !in_quotes;
+        } else if ch == delim && !in_quotes {
+            ranges.push(start..i);
+            start = i + ch.len_utf8();
+        }
+    }
+
+    ranges.push(start..line.len());
+
+    ranges
+}
+
+/// Computes the width of each column (ie. the width of its widest cell) across all `rows`.
+pub fn column_widths(rows: &[Row], delim: char) -> Vec<usize> {
+    let mut widths: Vec<usize> = vec![];
+
+    for row in rows {
+        for (col, range) in cell_ranges(row.chars(), delim).into_iter().enumerate() {
+            let width = row.chars()[range].width();
+
+            if col >= widths.len() {
+                widths.push(width);
+            } else if widths[col] < width {
+                widths[col] = width;
+            }
+        }
+    }
+
+    widths
+}
@@ -0,0 +1,498 @@
+use crate::style::{FontStyle, Rgb, Style};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Themes {
+    VsCode,     // Darks
+    Campbell,
+    BusyBee,
+    GithubLight, // Lights
+    HighContrast, // Accessible
+    Monochrome
+}
+
+impl Themes {
+    /// Every theme, in the order they are listed in.
+    pub const AVAILABLE: [Themes; 6] = [Self::VsCode, Self::Campbell, Self::BusyBee, Self::GithubLight, Self::HighContrast, Self::Monochrome];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::VsCode        => "vscode",
+            Self::Campbell      => "campbell",
+            Self::BusyBee       => "busy-bee",
+            Self::GithubLight   => "github-light",
+            Self::HighContrast  => "high-contrast",
+            Self::Monochrome    => "monochrome"
+        }
+    }
+
+    /// Gets the available theme with the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::AVAILABLE
+            .into_iter()
+            .find(|theme| theme.name() == name)
+    }
+
+    /// Whether the theme has a light background.
+    pub const fn is_light(self) -> bool {
+        matches!(self, Self::GithubLight)
+    }
+
+    pub fn theme(self) -> Theme {
+        match self {
+            Self::VsCode        => {
+                let bg = Rgb(31, 31, 31);
+                let fg = Rgb(204, 204, 204);
+
+                Theme {
+                    bg: bg,
+                    fg: fg,
+                    dimmed: Rgb(138, 138, 138),
+                    superdim: Rgb(81, 81, 81),
+                    current_line: Rgb(208, 208, 208),
+                    title: Style::new(fg, bg, FontStyle::default()),
+                    cursor: CursorStyle::Regular,
+                    normal: Style::new(fg, bg, FontStyle::default()),
+                    number: Style::new(Rgb(181, 206, 168), bg, FontStyle::default()),
+                    string: Style::new(Rgb(206, 145, 120), bg, FontStyle::default()),
+                    comment: Style::new(Rgb(106, 153, 85), bg, FontStyle::default()),
+                    doc_comment: Style::new(Rgb(106, 153, 85), bg, FontStyle::ITALIC),
+                    keyword: Style::new(Rgb(86, 156, 214), bg, FontStyle::default()),
+                    flowword: Style::new(Rgb(197, 134, 192), bg, FontStyle::default()),
+                    common_type: Style::new(Rgb(78, 201, 176), bg, FontStyle::default()),
+                    metaword: Style::new(Rgb(86, 156, 214), bg, FontStyle::default()),
+                    ident: Style::new(Rgb(156, 220, 254), bg, FontStyle::default()),
+                    function: Style::new(Rgb(220, 220, 170), bg, FontStyle::default()),
+                    path: Style::new(Rgb(78, 201, 176), bg, FontStyle::default()),
+                    search: Rgb(158, 106, 3),
+                    select: Rgb(38, 79, 120),
+                    error: Rgb(244, 71, 71),
+                    warning: Rgb(204, 167, 0),
+                    added: Rgb(72, 145, 67),
+                    indent_guide: Rgb(64, 64, 64),
+                    trailing_whitespace: Rgb(92, 76, 0),
+                    brackets: [Rgb(255, 215, 0), Rgb(218, 112, 214), Rgb(23, 159, 255)]
+                }
+            }
+            Self::Campbell      => {
+                let bg = Rgb(12, 12, 12);
+                let fg = Rgb(204, 204, 204);
+
+                Theme {
+                    bg: bg,
+                    fg: fg,
+                    dimmed: Rgb(138, 138, 138),
+                    superdim: Rgb(52, 52, 52),
+                    current_line: Rgb(208, 208, 208),
+                    title: Style::new(fg, bg, FontStyle::default()),
+                    cursor: CursorStyle::Regular,
+                    normal: Style::new(fg, bg, FontStyle::default()),
+                    number: Style::new(Rgb(181, 206, 168), bg, FontStyle::default()),
+                    string: Style::new(Rgb(206, 145, 120), bg, FontStyle::default()),
+                    comment: Style::new(Rgb(106, 153, 85), bg, FontStyle::default()),
+                    doc_comment: Style::new(Rgb(106, 153, 85), bg, FontStyle::ITALIC),
+                    keyword: Style::new(Rgb(86, 156, 214), bg, FontStyle::default()),
+                    flowword: Style::new(Rgb(197, 134, 192), bg, FontStyle::default()),
+                    common_type: Style::new(Rgb(78, 201, 176), bg, FontStyle::default()),
+                    metaword: Style::new(Rgb(86, 156, 214), bg, FontStyle::default()),
+                    ident: Style::new(Rgb(156, 220, 254), bg, FontStyle::default()),
+                    function: Style::new(Rgb(220, 220, 170), bg, FontStyle::default()),
+                    path: Style::new(Rgb(78, 201, 176), bg, FontStyle::default()),
+                    search: Rgb(0, 0, 250),
+                    select: Rgb(38, 79, 120),
+                    error: Rgb(231, 72, 86),
+                    warning: Rgb(249, 241, 165),
+                    added: Rgb(22, 198, 12),
+                    indent_guide: Rgb(40, 40, 40),
+                    trailing_whitespace: Rgb(96, 88, 30),
+                    brackets: [Rgb(249, 241, 165), Rgb(180, 0, 158), Rgb(59, 120, 255)]
+                }
+            }
+            Self::BusyBee       => {
+                let bg = Rgb(15, 15, 15);
+                let fg = Rgb(192, 192, 192);
+                let normal = Style::new(fg, bg, FontStyle::default());
+
+                Theme {
+                    bg,
+                    fg,
+                    dimmed: Rgb(86, 86, 86),
+                    superdim: Rgb(46, 48, 44),
+                    current_line: Rgb(224, 227, 96),
+                    title: Style::new(fg, bg, FontStyle::default()),
+                    cursor: CursorStyle::Regular, // if I can find a way to change cursor color, then use BigBar
+                    normal: normal,
+                    number: normal,
+                    string: Style::new(Rgb(118, 148, 109), bg, FontStyle::default()),
+                    comment: Style::new(Rgb(69, 69, 69), bg, FontStyle::ITALIC),
+                    doc_comment: Style::new(Rgb(112, 112, 112), bg, FontStyle::ITALIC),
+                    keyword: Style::new(Rgb(224, 227, 96), bg, FontStyle::BOLD),
+                    flowword: normal,
+                    common_type: Style::new(Rgb(129, 129, 124), bg, FontStyle::BOLD),
+                    metaword: normal,
+                    ident: normal,
+                    function: normal,
+                    path: normal,
+                    search: Rgb(0, 0, 250),
+                    select: Rgb(116, 118, 34),
+                    error: Rgb(225, 80, 80),
+                    warning: Rgb(224, 227, 96),
+                    added: Rgb(118, 148, 109),
+                    indent_guide: Rgb(36, 38, 34),
+                    trailing_whitespace: Rgb(82, 83, 35),
+                    brackets: [Rgb(224, 227, 96), Rgb(118, 148, 109), Rgb(129, 129, 124)]
+                }
+            }
+            Self::GithubLight   => {
+                let bg = Rgb(255, 255, 255);
+                let fg = Rgb(31, 35, 40);
+                let normal = Style::new(fg, bg, FontStyle::default());
+
+                Theme {
+                    bg,
+                    fg,
+                    dimmed: Rgb(99, 109, 120),
+                    superdim: Rgb(205, 205, 205),
+                    current_line: Rgb(16, 16, 16),
+                    title: Style::new(fg, bg, FontStyle::default()),
+                    cursor: CursorStyle::Regular,
+                    normal,
+                    number: Style::new(Rgb(5, 80, 174), bg, FontStyle::default()),
+                    string: Style::new(Rgb(10, 48, 105), bg, FontStyle::default()),
+                    comment: Style::new(Rgb(90, 108, 119), bg, FontStyle::default()),
+                    doc_comment: Style::new(Rgb(90, 108, 119), bg, FontStyle::ITALIC),
+                    keyword: Style::new(Rgb(207, 34, 46), bg, FontStyle::default()),
+                    flowword: Style::new(Rgb(207, 34, 46), bg, FontStyle::default()),
+                    common_type: normal,
+                    metaword: Style::new(Rgb(207, 34, 46), bg, FontStyle::default()),
+                    ident: normal,
+                    function: Style::new(Rgb(102, 57, 186), bg, FontStyle::default()),
+                    path: normal,
+                    search: Rgb(255, 150, 50),
+                    select: Rgb(206, 225, 248),
+                    error: Rgb(207, 34, 46),
+                    warning: Rgb(154, 103, 0),
+                    added: Rgb(26, 127, 55),
+                    indent_guide: Rgb(225, 228, 232),
+                    trailing_whitespace: Rgb(255, 235, 160),
+                    brackets: [Rgb(4, 49, 250), Rgb(49, 147, 49), Rgb(123, 56, 20)]
+                }
+            }
+            Self::HighContrast  => {
+                let bg = Rgb(0, 0, 0);
+                let fg = Rgb(255, 255, 255);
+                let normal = Style::new(fg, bg, FontStyle::default());
+
+                Theme {
+                    bg,
+                    fg,
+                    dimmed: Rgb(192, 192, 192),
+                    superdim: Rgb(112, 112, 112),
+                    current_line: Rgb(255, 255, 0),
+                    title: Style::new(fg, bg, FontStyle::BOLD),
+                    cursor: CursorStyle::Regular,
+                    normal,
+                    number: Style::new(Rgb(255, 175, 95), bg, FontStyle::default()),
+                    string: Style::new(Rgb(255, 255, 0), bg, FontStyle::default()),
+                    comment: Style::new(Rgb(135, 255, 135), bg, FontStyle::ITALIC),
+                    doc_comment: Style::new(Rgb(135, 255, 135), bg, FontStyle::ITALIC | FontStyle::BOLD),
+                    keyword: Style::new(Rgb(0, 255, 255), bg, FontStyle::BOLD),
+                    flowword: Style::new(Rgb(255, 135, 255), bg, FontStyle::BOLD),
+                    common_type: Style::new(Rgb(0, 255, 175), bg, FontStyle::default()),
+                    metaword: Style::new(Rgb(0, 255, 255), bg, FontStyle::BOLD),
+                    ident: normal,
+                    function: Style::new(Rgb(255, 255, 175), bg, FontStyle::default()),
+                    path: Style::new(Rgb(0, 255, 175), bg, FontStyle::default()),
+                    search: fg,
+                    select: Rgb(0, 0, 175),
+                    error: Rgb(255, 95, 95),
+                    warning: Rgb(255, 255, 0),
+                    added: Rgb(0, 255, 0),
+                    indent_guide: Rgb(88, 88, 88),
+                    trailing_whitespace: Rgb(175, 0, 0),
+                    brackets: [Rgb(255, 255, 0), Rgb(255, 135, 255), Rgb(0, 255, 255)]
+                }
+            }
+            // Only black, white and grays, with font styles telling the parts of the code apart
+            Self::Monochrome    => {
+                let bg = Rgb(0, 0, 0);
+                let fg = Rgb(255, 255, 255);
+                let dimmed = Rgb(160, 160, 160);
+                let normal = Style::new(fg, bg, FontStyle::default());
+
+                Theme {
+                    bg,
+                    fg,
+                    dimmed,
+                    superdim: Rgb(88, 88, 88),
+                    current_line: fg,
+                    title: Style::new(fg, bg, FontStyle::BOLD),
+                    cursor: CursorStyle::Regular,
+                    normal,
+                    number: normal,
+                    string: Style::new(fg, bg, FontStyle::UNDERLINE),
+                    comment: Style::new(dimmed, bg, FontStyle::ITALIC),
+                    doc_comment: Style::new(fg, bg, FontStyle::ITALIC),
+                    keyword: Style::new(fg, bg, FontStyle::BOLD),
+                    flowword: Style::new(fg, bg, FontStyle::BOLD),
+                    common_type: Style::new(fg, bg, FontStyle::BOLD | FontStyle::ITALIC),
+                    metaword: Style::new(fg, bg, FontStyle::BOLD),
+                    ident: normal,
+                    function: normal,
+                    path: normal,
+                    search: fg,
+                    select: Rgb(96, 96, 96),
+                    error: fg,
+                    warning: dimmed,
+                    added: fg,
+                    indent_guide: Rgb(48, 48, 48),
+                    trailing_whitespace: Rgb(96, 96, 96),
+                    brackets: [fg, dimmed, fg]
+                }
+            }
+        }.to_owned()
+    }
+}
+
+impl Themes {
+    /// The theme used by default on terminals with a light background.
+    pub const LIGHT_DEFAULT: Themes = Themes::GithubLight;
+}
+
+impl Default for Themes {
+    fn default() -> Self {
+        Themes::Campbell
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    bg: Rgb,            // Default bg color
+    fg: Rgb,            // Default text color
+    dimmed: Rgb,        // Dimmed text color (ie. for line # and ~)
+    superdim: Rgb,      // Extremely dimmed text color (ie. for ---s in Keybinds Help)
+    current_line: Rgb,  // Current line number text color
+    title: Style,       // Style for the welcome screen title
+    cursor: CursorStyle,// Default cursor style (cursor for main text buffer)
+    normal: Style,
+    number: Style,
+    string: Style,
+    comment: Style,
+    doc_comment: Style,
+    keyword: Style,
+    flowword: Style,
+    common_type: Style,
+    metaword: Style,
+    ident: Style,
+    function: Style,
+    path: Style,
+    search: Rgb,        // Default search highlight color
+    select: Rgb,        // Default select highlight color
+    error: Rgb,         // Error color (ie. for lint markers)
+    warning: Rgb,       // Warning color (ie. for lint markers)
+    added: Rgb,         // Added line color (ie. for diff markers)
+    indent_guide: Rgb,  // Indent guide color
+    trailing_whitespace: Rgb, // Background color of whitespace at the end of rows
+    brackets: [Rgb; 3]  // Colors of brackets by how deeply they are nested, repeating (ie. for rainbow brackets)
+}
+
+impl Theme {
+    /// Overrides the field named `field` with `value`. Color fields take a color like `#1f1f1f`, while style fields take a color and/or font styles, like `#6a9955 italic`, keeping the parts that aren't given.
+    /// 
+    /// Changing `bg` also changes the background of the styles that used it.
+    pub fn set(&mut self, field: &str, value: &str) -> Result<(), String> {
+        let color = |value: &str| Rgb::from_hex(value).ok_or(format!("Invalid color '{value}' (expected '#rrggbb')"));
+
+        let rgb = match field {
+            "bg" => {
+                let (old_bg, bg) = (self.bg, color(value)?);
+                for style in self.styles_mut() {
+                    if *style.bg() == old_bg {
+                        style.set_bg(bg);
+                    }
+                }
+
+                self.bg = bg;
+                return Ok(());
+            }
+            "fg"            => &mut self.fg,
+            "dimmed"        => &mut self.dimmed,
+            "superdim"      => &mut self.superdim,
+            "current_line"  => &mut self.current_line,
+            "search"        => &mut self.search,
+            "select"        => &mut self.select,
+            "error"         => &mut self.error,
+            "warning"       => &mut self.warning,
+            "added"         => &mut self.added,
+            "indent_guide"  => &mut self.indent_guide,
+            "trailing_whitespace" => &mut self.trailing_whitespace,
+            "bracket_1"     => &mut self.brackets[0],
+            "bracket_2"     => &mut self.brackets[1],
+            "bracket_3"     => &mut self.brackets[2],
+            _ => {
+                let style = self.style_mut(field).ok_or(format!("Unknown theme field '{field}'"))?;
+                let mut font = None;
+
+                for word in value.split_whitespace() {
+                    if word.starts_with('#') {
+                        style.set_fg(color(word)?);
+                    } else {
+                        let f = FontStyle::parse(word).ok_or(format!("Unknown font style '{word}'"))?;
+                        font = Some(font.unwrap_or(FontStyle::NONE) | f);
+                    }
+                }
+
+                if let Some(font) = font {
+                    style.set_font(font);
+                }
+
+                return Ok(());
+            }
+        };
+
+        *rgb = color(value)?;
+
+        Ok(())
+    }
+
+    fn style_mut(&mut self, field: &str) -> Option<&mut Style> {
+        Some(match field {
+            "title"         => &mut self.title,
+            "normal"        => &mut self.normal,
+            "number"        => &mut self.number,
+            "string"        => &mut self.string,
+            "comment"       => &mut self.comment,
+            "doc_comment"   => &mut self.doc_comment,
+            "keyword"       => &mut self.keyword,
+            "flowword"      => &mut self.flowword,
+            "common_type"   => &mut self.common_type,
+            "metaword"      => &mut self.metaword,
+            "ident"         => &mut self.ident,
+            "function"      => &mut self.function,
+            "path"          => &mut self.path,
+            _               => return None
+        })
+    }
+
+    fn styles_mut(&mut self) -> [&mut Style; 13] {
+        [
+            &mut self.title, &mut self.normal, &mut self.number, &mut self.string, 
+            &mut self.comment, &mut self.doc_comment, &mut self.keyword, &mut self.flowword, 
+            &mut self.common_type, &mut self.metaword, &mut self.ident, &mut self.function, 
+            &mut self.path
+        ]
+    }
+
+    pub fn bg(&self) -> &Rgb {
+        &self.bg
+    }
+
+    pub fn fg(&self) -> &Rgb {
+        &self.fg
+    }
+
+    pub fn dimmed(&self) -> &Rgb {
+        &self.dimmed
+    }
+
+    pub fn superdim(&self) -> &Rgb {
+        &self.superdim
+    }
+
+    pub fn current_line(&self) -> &Rgb {
+        &self.current_line
+    }
+
+    pub fn title(&self) -> &Style {
+        &self.title
+    }
+
+    pub fn cursor(&self) -> &CursorStyle {
+        &self.cursor
+    }
+
+    pub fn normal(&self) -> &Style {
+        &self.normal
+    }
+
+    pub fn number(&self) -> &Style {
+        &self.number
+    }
+
+    pub fn string(&self) -> &Style {
+        &self.string
+    }
+
+    pub fn comment(&self) -> &Style {
+        &self.comment
+    }
+
+    pub fn doc_comment(&self) -> &Style {
+        &self.doc_comment
+    }
+
+    pub fn keyword(&self) -> &Style {
+        &self.keyword
+    }
+
+    pub fn flowword(&self) -> &Style {
+        &self.flowword
+    }
+
+    pub fn common_type(&self) -> &Style {
+        &self.common_type
+    }
+
+    pub fn metaword(&self) -> &Style {
+        &self.metaword
+    }
+
+    pub fn ident(&self) -> &Style {
+        &self.ident
+    }
+
+    pub fn function(&self) -> &Style {
+        &self.function
+    }
+
+    pub fn path(&self) -> &Style {
+        &self.path
+    }
+
+    pub fn search(&self) -> &Rgb {
+        &self.search
+    }
+
+    pub fn select(&self) -> &Rgb {
+        &self.select
+    }
+
+    pub fn error(&self) -> &Rgb {
+        &self.error
+    }
+
+    pub fn warning(&self) -> &Rgb {
+        &self.warning
+    }
+
+    pub fn added(&self) -> &Rgb {
+        &self.added
+    }
+
+    pub fn indent_guide(&self) -> &Rgb {
+        &self.indent_guide
+    }
+
+    pub fn trailing_whitespace(&self) -> &Rgb {
+        &self.trailing_whitespace
+    }
+
+    pub fn brackets(&self) -> &[Rgb] {
+        &self.brackets
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorStyle {
+    Regular,
+    BigBar
+}
@@ -0,0 +1,80 @@
+use std::io;
+use std::ops::Range;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// What a URL can start with to be found.
+const SCHEMES: [&str; 4] = ["https://", "http://", "ftp://", "file://"];
+
+/// Finds the byte ranges of the URLs in `text`. A URL starts with one of the [`SCHEMES`] and runs until a char that can't be in one (eg. whitespace or `"`), leaving off any punctuation at its end, such as the `.` ending a sentence or the `)` around a Markdown link.
+pub fn find_urls(text: &str) -> Vec<Range<usize>> {
+    let mut urls = vec![];
+    let mut from = 0;
+
+    // Most rows have no URLs, so they are ruled out in a single pass
+    if !text.contains("://") {
+        return urls;
+    }
+
+    while let Some((start, scheme)) = SCHEMES
+        .iter()
+        .filter_map(|scheme| text[from..].find(scheme).map(|i| (from + i, scheme)))
+        .min_by_key(|(i, _)| *i)
+    {
+        let min_end = start + scheme.len();
+        let mut end = text[min_end..]
+            .find(|ch: char| !is_url_char(ch))
+            .map_or(text.len(), |i| min_end + i);
+
+        while end > min_end && is_trailing_punct(&text[start..end]) {
+            end -= 1;
+        }
+
+        if end > min_end {
+            urls.push(start..end);
+        }
+        from = end;
+    }
+
+    urls
+}
+
+fn is_url_char(ch: char) -> bool {
+    ch.is_alphanumeric() || "-._~:/?#[]@!$&'()*+,;=%".contains(ch)
+}
+
+/// Gets whether the last char of `url` is punctuation around or after it rather than part of it. Closing brackets are part of it if they close one in it (eg. `https://en.wikipedia.org/wiki/Rust_(programming_language)`).
+fn is_trailing_punct(url: &str) -> bool {
+    match url.chars().next_back() {
+        Some('.' | ',' | ':' | ';' | '!' | '?' | '\'') => true,
+        Some(')') => url.matches(')').count() > url.matches('(').count(),
+        Some(']') => url.matches(']').count() > url.matches('[').count(),
+        _ => false
+    }
+}
+
+/// Opens `url` in the system's browser, without waiting for it to open.
+pub fn open(url: &str) -> io::Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut cmd = Command::new("open");
+
+    #[cfg(windows)]
+    let mut cmd = Command::new("rundll32");
+    #[cfg(windows)]
+    cmd.arg("url.dll,FileProtocolHandler");
+
+    #[cfg(not(any(target_os = "macos", windows)))]
+    let mut cmd = Command::new("xdg-open");
+
+    let mut child = cmd
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // Waited for on another thread so that it doesn't linger as a zombie process
+    thread::spawn(move || child.wait());
+
+    Ok(())
+}
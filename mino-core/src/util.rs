@@ -1,4 +1,5 @@
-use std::{cmp, ops::Add};
+use std::{cmp, ops::Add, path::Path};
+use unicode_width::UnicodeWidthChar;
 
 /// Trait to easily convert to u16.
 pub trait AsU16 {
@@ -28,6 +29,41 @@ impl IntLen for usize {
     }
 }
 
+/// Truncates `s` so that it takes up at most `width` terminal columns, without splitting any chars.
+pub fn truncate_to_width(s: &mut String, width: usize) {
+    let mut cur_width = 0;
+
+    for (i, ch) in s.char_indices() {
+        cur_width += ch.width().unwrap_or(0);
+
+        if cur_width > width {
+            s.truncate(i);
+            return;
+        }
+    }
+}
+
+/// Removes any escape sequences (eg. colors) from `s`, leaving only the text that would be shown.
+pub fn strip_escapes(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            res.push(ch);
+        } else if let Some('[') = chars.next() {
+            // Skip the parameters, up to and including the final byte
+            for c in chars.by_ref() {
+                if ('\x40'..='\x7e').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+
+    res
+}
+
 /// Struct to easily represent the cursor position (as (x, y))
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Pos(pub usize, pub usize);
@@ -103,7 +139,7 @@ macro_rules! pos {
 
 /// Computes a bit expression given `SyntaxFlag` flags and either all `|`, `&`, or `^`.
 /// 
-/// Returns `u8`.
+/// Returns `u16`.
 #[macro_export]
 macro_rules! bitexpr {
     ( $path:ident : $flag:ident ) => {
@@ -161,19 +197,22 @@ macro_rules! checkflags {
     };    
 }
 
-pub fn prepend_prefix<'a>(paths: &'a Vec<String>, prefix: &'a Option<String>) -> Vec<String> {
-    if prefix.is_none() {
-        paths.clone()
+/// Joins each of `paths` onto the `prefix` directory, if there is one, with their separators normalized (see [`normalize_separators`]). Absolute paths are left as they are.
+pub fn prepend_prefix(paths: &[String], prefix: Option<&Path>) -> Vec<String> {
+    paths
+        .iter()
+        .map(|path| match prefix {
+            Some(prefix) => normalize_separators(&prefix.join(path).to_string_lossy()),
+            None => normalize_separators(path)
+        })
+        .collect()
+}
+
+/// Makes every separator in `path` the platform's main one, so that paths typed with a mix of them are shown consistently. Only Windows has more than one (`\` and `/`), so elsewhere `path` is left as it is, as `\` can be part of a file name.
+pub fn normalize_separators(path: &str) -> String {
+    if cfg!(windows) {
+        path.replace('/', "\\")
     } else {
-        let prefix = prefix.as_ref().unwrap();
-
-        paths
-            .iter()
-            .map(|p| {
-                let mut path = p.clone();
-                path.insert_str(0, prefix);
-                path
-            })
-            .collect()
+        path.to_owned()
     }
 }
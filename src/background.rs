@@ -0,0 +1,128 @@
+use std::env;
+#[cfg(unix)]
+use std::fs::OpenOptions;
+#[cfg(unix)]
+use std::io::{Read, Write};
+#[cfg(unix)]
+use std::os::fd::AsRawFd;
+use std::sync::OnceLock;
+use std::time::Duration;
+#[cfg(unix)]
+use std::time::Instant;
+
+use crate::style::Rgb;
+use crate::theme::Themes;
+
+/// Whether the terminal has a light background, once it has been detected.
+static IS_LIGHT: OnceLock<bool> = OnceLock::new();
+
+/// How long to wait for the terminal to answer the background color query.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Detects whether the terminal has a light background, using `COLORFGBG` if it is set, or else by asking the terminal for its background color. If neither works, the background is assumed to be dark.
+///
+/// This must be called in raw mode, before any events are read, so that the terminal's answer isn't echoed or read as key presses. The result is remembered, and can be got later with [`is_light`].
+pub fn detect() -> bool {
+    *IS_LIGHT.get_or_init(|| {
+        from_colorfgbg()
+            .or_else(|| query_background().map(|bg| luminance(bg) > 128.0))
+            .unwrap_or(false)
+    })
+}
+
+/// Gets whether the terminal was detected to have a light background, or `false` if it hasn't been detected.
+pub fn is_light() -> bool {
+    IS_LIGHT.get().copied().unwrap_or(false)
+}
+
+/// Gets the default theme for the terminal's background, if it was detected to be light.
+pub fn default_theme() -> Themes {
+    if is_light() {
+        Themes::LIGHT_DEFAULT
+    } else {
+        Themes::default()
+    }
+}
+
+/// Reads `COLORFGBG` (eg. `15;0`), which some terminals set to the ANSI colors of the text and background.
+fn from_colorfgbg() -> Option<bool> {
+    let colors = env::var("COLORFGBG").ok()?;
+    let bg = colors.rsplit(';').next()?.parse::<u8>().ok()?;
+
+    // White, light gray and the bright colors (except bright black) are light
+    Some(bg == 7 || (9..=15).contains(&bg))
+}
+
+/// Asks the terminal for its background color with OSC 11.
+///
+/// A device attributes query is sent after it, which every terminal answers, so that terminals that don't support OSC 11 are known to be done answering without having to wait. The answer is read a byte at a time, waiting at most [`QUERY_TIMEOUT`] in all, so that nothing typed after it is read.
+#[cfg(unix)]
+fn query_background() -> Option<Rgb> {
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+
+    tty.write_all(b"\x1b]11;?\x1b\\\x1b[c").ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    let mut answer = Vec::new();
+    let mut byte = [0];
+
+    loop {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let mut poll_fd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0
+        };
+
+        // Safety: `poll_fd` is a valid `pollfd` for the whole call, and 1 is the number of them
+        let num_ready = unsafe { libc::poll(&mut poll_fd, 1, timeout.as_millis() as libc::c_int) };
+        if num_ready <= 0 || tty.read_exact(&mut byte).is_err() {
+            break;
+        }
+        answer.push(byte[0]);
+
+        // The device attributes answer (`ESC [ ? ... c`) comes last
+        if byte[0] == b'c' && answer.windows(3).any(|w| w == b"\x1b[?") {
+            break;
+        }
+    }
+
+    parse_osc11(&String::from_utf8_lossy(&answer))
+}
+
+#[cfg(not(unix))]
+fn query_background() -> Option<Rgb> {
+    None
+}
+
+/// Parses the color out of an OSC 11 answer, such as `ESC ] 11 ; rgb:1e1e/1e1e/1e1e ESC \`.
+fn parse_osc11(answer: &str) -> Option<Rgb> {
+    let start = answer.find("rgb:")? + 4;
+    let end = answer[start..]
+        .find(|ch: char| ch != '/' && !ch.is_ascii_hexdigit())
+        .map_or(answer.len(), |i| start + i);
+
+    // Each channel has 1 to 4 hex digits, so scale them down to 8 bits
+    let mut channels = answer[start..end].split('/').map(|hex| {
+        if hex.is_empty() || hex.len() > 4 {
+            return None;
+        }
+
+        let max = 16u32.pow(hex.len() as u32) - 1;
+        let value = u32::from_str_radix(hex, 16).ok()?;
+
+        Some((value * 255 / max) as u8)
+    });
+
+    Some(Rgb(channels.next()??, channels.next()??, channels.next()??))
+}
+
+/// Gets how bright a color looks, from 0 to 255.
+fn luminance(Rgb(r, g, b): Rgb) -> f32 {
+    0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32
+}
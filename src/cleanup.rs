@@ -1,4 +1,9 @@
-use crossterm::terminal::disable_raw_mode;
+use std::io;
+use crossterm::{
+    event::{DisableBracketedPaste, EnableBracketedPaste}, 
+    terminal::{disable_raw_mode, enable_raw_mode}, 
+    ExecutableCommand
+};
 
 /// Used to clean up when project exits. 
 /// 
@@ -8,7 +13,25 @@ pub struct CleanUp;
 
 impl Drop for CleanUp {
     fn drop(&mut self) {
-        print!("\x1b[0 q");
-        disable_raw_mode().expect("Couldn't disable raw mode.");
+        restore_terminal();
     }
 }
+
+/// Sets up the terminal for the editor, by entering raw mode and enabling bracketed paste.
+pub fn set_up_terminal() {
+    enable_raw_mode().expect("An error occurred when trying to setup the program.");
+
+    // Pasted text (including text committed by an IME in some terminals) is sent as a single event, rather than as key presses
+    let _ = io::stdout().execute(EnableBracketedPaste);
+
+    // Saves the terminal title, as it is changed to show the current file
+    print!("\x1b[22;0t");
+}
+
+/// Undoes [`set_up_terminal`], leaving the terminal as it was before the editor started.
+pub fn restore_terminal() {
+    // Restores the cursor style, un-inverts the screen if an alert was showing, and restores the terminal title saved in `set_up_terminal`
+    print!("\x1b[0 q\x1b[?5l\x1b[23;0t");
+    let _ = io::stdout().execute(DisableBracketedPaste);
+    disable_raw_mode().expect("Couldn't disable raw mode.");
+}
@@ -1,4 +1,10 @@
-use clap::{builder::styling::{Effects, Styles}, Parser};
+use std::cmp;
+use std::ops;
+use std::path::{Path, PathBuf};
+use clap::{builder::styling::{Effects, Styles}, error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+
+use crate::theme::Themes;
 
 const MINO_EXAMPLES_SECTION: &'static str = "\
 \x1b[1mExamples:\x1b[m
@@ -14,11 +20,47 @@ const MINO_EXAMPLES_SECTION: &'static str = "\
   mino -r a.txt
           Opens 'a.txt' in readonly mode; can only view
 
-  mino docs.txt data.csv -p program/
+  mino docs.txt data.csv -p program
           Opens 'program/docs.txt' and 'program/data.csv' for editing
 
   mino a.txt -t ../
           Opens 'a.txt' and a file tree from the parent directory   
+
+  mino src/main.rs:120:8
+          Opens 'src/main.rs' with the cursor at line 120, column 8
+
+  git diff | mino -
+          Opens what is piped in as an unnamed file
+
+  mino --open-dir src --ext rs,toml
+          Opens each Rust and TOML file under 'src' in its own tab, leaving out the ones in .gitignore
+
+  mino --remote open src/main.rs:120
+          Opens 'src/main.rs' at line 120 in the mino that is already running, if there is one
+
+  EDITOR='mino --remote wait' git commit
+          Writes the commit message in the mino that is already running, carrying on once its tab is closed
+
+  mino --diff old.txt new.txt
+          Opens 'old.txt' and 'new.txt', marking the lines that differ between them
+
+  mino Makefile --tab-stop 8
+          Opens 'Makefile', showing tabs as 8 columns wide
+
+  mino --theme high-contrast a.txt
+          Opens 'a.txt' with the high contrast theme (see --list-themes for the others)
+
+  mino main.rs --export-html main.html
+          Writes 'main.rs', highlighted with the theme's colors, to 'main.html'
+
+  mino --print server.log --lines 100-200
+          Prints lines 100 to 200 of 'server.log' (highlighted, if printed to a terminal) without opening the editor
+
+  mino todo.txt --script edits.toml
+          Makes the edits in 'edits.toml' (eg. goto = 3, insert = \"- [ ] \", save = true) to 'todo.txt' without opening the editor
+
+  mino +45 notes.txt
+          Opens 'notes.txt' with the cursor at line 45 (or use +/pattern to go to the first match of 'pattern')
 ";
 
 const MINO_HELP_TEMPLATE: &'static str = "\
@@ -28,24 +70,115 @@ const MINO_HELP_TEMPLATE: &'static str = "\
 
 {all-args}{after-help}";
 
-#[derive(Parser)]
+#[derive(Debug, Clone, Parser)]
 #[command(name = "mino")]
 #[command(author, version, about)]
 #[command(help_template=MINO_HELP_TEMPLATE)]
 #[command(after_long_help=MINO_EXAMPLES_SECTION)]
 #[command(styles(Styles::styled().header(Effects::BOLD.into()).usage(Effects::BOLD.into())))]
 pub struct Cli {
-    /// List of files to open, when none are provided, a new editor will open
+    /// List of files to open, when none are provided, a new editor will open. Can include +N or +/pattern to start at line N, or at the first match of pattern, and - to read from stdin
     files: Vec<String>,
 
-    /// A prefix to insert before the given paths of each file
-    #[arg(short, long)]
-    prefix: Option<String>,
+    /// Whether to open stdin as a buffer, taken out of the files list
+    #[arg(skip)]
+    read_stdin: bool,
+
+    /// Where to start in the first file, taken out of the files list
+    #[arg(skip)]
+    start: Option<Start>,
+
+    /// A directory that the given paths of each file are relative to
+    #[arg(short, long, value_name = "DIR")]
+    prefix: Option<PathBuf>,
+
+    /// A directory to open all of the files under, in tabs, leaving out ignored and binary files
+    #[arg(long, value_name = "DIR")]
+    open_dir: Option<PathBuf>,
+
+    /// The extensions of the files to open with --open-dir, separated by commas (eg. rs,toml)
+    #[arg(long, value_name = "EXTS", value_delimiter = ',', requires = "open_dir")]
+    ext: Vec<String>,
+
+    /// The size of the largest file to open with --open-dir, in KB
+    #[arg(long, value_name = "KB", default_value_t = 1024, requires = "open_dir")]
+    max_size: u64,
+
+    /// Send the files to the mino that is already running (eg. from its own terminal) instead of opening them here. If there isn't one, they are opened here
+    #[arg(long, value_name = "CMD", requires = "files")]
+    remote: Option<RemoteCommand>,
 
     /// Whether to open in readonly mode
     #[arg(short, long)]
     readonly: bool,
 
+    /// The number of columns between tab stops
+    #[arg(long, value_name = "WIDTH", value_parser = clap::value_parser!(u8).range(1..))]
+    tab_stop: Option<u8>,
+
+    /// Whether to hide line numbers
+    #[arg(long)]
+    no_line_numbers: bool,
+
+    /// Whether to wrap rows that are too long for the screen onto the next line, instead of scrolling sideways
+    #[arg(long)]
+    soft_wrap: bool,
+
+    /// Whether to hide the welcome screen shown when there are no files open
+    #[arg(long)]
+    no_welcome: bool,
+
+    /// The theme to use instead of the config's
+    #[arg(long, value_name = "NAME", value_parser = Themes::AVAILABLE.map(Themes::name))]
+    theme: Option<String>,
+
+    /// Whether to draw without any colors, showing selections and search matches in reverse video instead. Also set by the NO_COLOR environment variable
+    #[arg(long)]
+    no_color: bool,
+
+    /// Whether to draw for terminal screen readers, without the welcome screen, the ~s on empty lines, alerts or anything else that only adds noise
+    #[arg(long)]
+    screen_reader: bool,
+
+    /// Whether to show how long drawing each frame takes, averaged over the last few frames
+    #[arg(long)]
+    profile: bool,
+
+    /// Whether to compare two files, marking the lines that were added, removed, or changed
+    #[arg(long)]
+    diff: bool,
+
+    /// Write the file, highlighted with the theme's colors, to an HTML file at OUT, then exit
+    #[arg(long, value_name = "OUT")]
+    export_html: Option<String>,
+
+    /// Make the edits in SCRIPT (or stdin, if it is -) to the file, then exit. Each line is an edit, like goto = "3:5", insert = "text", delete = 4, replace = "old" then with = "new", and save = true. If nothing is saved, the edited text is printed
+    #[arg(long, value_name = "SCRIPT", conflicts_with = "export_html")]
+    script: Option<String>,
+
+    /// Print the file (or stdin, if it is -) to stdout, then exit. It is highlighted with the theme's colors if stdout is a terminal (unless --no-color is given)
+    #[arg(long, conflicts_with_all = ["export_html", "script"])]
+    print: bool,
+
+    /// The lines (starting at 1) to print with --print, like 100-200, 100- (to the end of the file), -200 or 100
+    #[arg(long, value_name = "RANGE", requires = "print", allow_hyphen_values = true, value_parser = LineRange::parse)]
+    lines: Option<LineRange>,
+
+    /// Print the supported languages and their file extensions, then exit
+    #[arg(long)]
+    list_languages: bool,
+
+    /// Print the available themes, then exit
+    #[arg(long)]
+    list_themes: bool,
+
+    /// Whether to append errors and debug info to a log file, at mino/mino.log in the platform's data directory (eg. ~/.local/share)
+    #[arg(long)]
+    log: bool,
+
+    #[command(subcommand)]
+    subcommand: Option<Command>,
+
     // Todo: Use "default_missing_value" and set it to the current directory turned to a static string using this crate: https://docs.rs/static_str_ops/latest/static_str_ops/.
     /// Whether to open a file tree
     #[arg(short, long, value_name = "ROOT")]
@@ -53,21 +186,317 @@ pub struct Cli {
 }
 
 impl Cli {
+    /// Parses the command line arguments, taking any `+N`, `+/pattern` or `-` argument out of the files.
+    pub fn parse_args() -> Self {
+        let mut cli = Self::parse();
+
+        let mut i = 0;
+        while i < cli.files.len() {
+            if cli.files[i] == "-" {
+                cli.read_stdin = true;
+                cli.files.remove(i);
+            } else if let Some(start) = Start::parse(&cli.files[i]) {
+                cli.start = Some(start);
+                cli.files.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+
+        if cli.export_html.is_some() && cli.files.len() != 1 {
+            Self::command()
+                .error(ErrorKind::WrongNumberOfValues, "--export-html needs exactly 1 file to export")
+                .exit();
+        }
+
+        if cli.script.is_some() && cli.files.len() != 1 {
+            Self::command()
+                .error(ErrorKind::WrongNumberOfValues, "--script needs exactly 1 file to edit")
+                .exit();
+        }
+
+        if cli.print && cli.files.len() + cli.read_stdin as usize != 1 {
+            Self::command()
+                .error(ErrorKind::WrongNumberOfValues, "--print needs exactly 1 file (or -, for stdin) to print")
+                .exit();
+        }
+
+        if cli.diff && cli.files.len() != 2 {
+            Self::command()
+                .error(ErrorKind::WrongNumberOfValues, "--diff needs exactly 2 files to compare")
+                .exit();
+        }
+
+        cli
+    }
+
     pub fn files(&self) -> &Vec<String> {
         &self.files
     }
 
+    pub fn open_dir(&self) -> Option<&Path> {
+        self.open_dir.as_deref()
+    }
+
+    /// Gets the extensions of the files to open with `--open-dir`, without any `.` they were given with.
+    pub fn exts(&self) -> Vec<String> {
+        self.ext
+            .iter()
+            .map(|ext| ext.trim().trim_start_matches('.').to_owned())
+            .filter(|ext| !ext.is_empty())
+            .collect()
+    }
+
+    /// Gets the size in bytes of the largest file to open with `--open-dir`.
+    pub fn max_size(&self) -> u64 {
+        self.max_size.saturating_mul(1024)
+    }
+
+    pub fn remote(&self) -> Option<RemoteCommand> {
+        self.remote
+    }
+
     pub fn readonly(&self) -> bool {
         self.readonly
     }
 
+    pub fn tab_stop(&self) -> Option<usize> {
+        self.tab_stop.map(usize::from)
+    }
+
+    pub fn no_line_numbers(&self) -> bool {
+        self.no_line_numbers
+    }
+
+    pub fn soft_wrap(&self) -> bool {
+        self.soft_wrap
+    }
+
+    pub fn no_welcome(&self) -> bool {
+        self.no_welcome
+    }
+
+    pub fn theme(&self) -> Option<Themes> {
+        self.theme.as_deref().and_then(Themes::from_name)
+    }
+
+    pub fn no_color(&self) -> bool {
+        self.no_color
+    }
+
+    pub fn screen_reader(&self) -> bool {
+        self.screen_reader
+    }
+
+    pub fn profile(&self) -> bool {
+        self.profile
+    }
+
+    pub fn diff(&self) -> bool {
+        self.diff
+    }
+
+    pub fn export_html(&self) -> Option<&str> {
+        self.export_html.as_deref()
+    }
+
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    pub fn print(&self) -> bool {
+        self.print
+    }
+
+    pub fn lines(&self) -> Option<LineRange> {
+        self.lines
+    }
+
+    pub fn list_languages(&self) -> bool {
+        self.list_languages
+    }
+
+    pub fn list_themes(&self) -> bool {
+        self.list_themes
+    }
+
+    pub fn log(&self) -> bool {
+        self.log
+    }
+
+    pub fn read_stdin(&self) -> bool {
+        self.read_stdin
+    }
+
+    pub fn start(&self) -> &Option<Start> {
+        &self.start
+    }
+
+    pub fn subcommand(&self) -> &Option<Command> {
+        &self.subcommand
+    }
+
     pub fn tree(&self) -> &Option<String> {
         &self.tree
     }
 
-    pub fn prefix(&self) -> &Option<String> {
-        &self.prefix
+    pub fn prefix(&self) -> Option<&Path> {
+        self.prefix.as_deref()
+    }
+}
+
+/// Commands that are run instead of opening the editor.
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Print a completion script for the given shell
+    #[command(hide = true)]
+    Completions {
+        shell: Shell
+    }
+}
+
+/// What to ask the running editor to do with `--remote`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RemoteCommand {
+    /// Open the files in new tabs
+    Open,
+    /// Open the files in new tabs, and wait until they are all closed (eg. for use as `$EDITOR`)
+    Wait
+}
+
+/// A file given on the command line, along with the line and column to open it at, if they were given (eg. `src/main.rs:120:8`).
+#[derive(Debug, Clone)]
+pub struct FileArg {
+    path: String,
+    line: Option<usize>,
+    col: Option<usize>
+}
+
+impl FileArg {
+    /// Parses a trailing `:line` or `:line:col` off of `arg`, unless `arg` is the path of a file that exists. A trailing `:` is ignored, as in the output of `grep -n`.
+    pub fn parse(arg: &str) -> Self {
+        let no_pos = Self {
+            path: arg.to_owned(),
+            line: None,
+            col: None
+        };
+
+        if Path::new(arg).exists() {
+            return no_pos;
+        }
+
+        let parse_num = |s: &str| if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+            s.parse::<usize>().ok()
+        } else {
+            None
+        };
+
+        let arg = arg.strip_suffix(':').unwrap_or(arg);
+        let (rest, last) = match arg.rsplit_once(':') {
+            Some((rest, last)) => match parse_num(last) {
+                Some(n) => (rest, n),
+                None => return no_pos
+            },
+            None => return no_pos
+        };
+
+        let (path, line, col) = match rest.rsplit_once(':') {
+            Some((path, line)) => match parse_num(line) {
+                Some(line) => (path, line, Some(last)),
+                None => (rest, last, None)
+            },
+            None => (rest, last, None)
+        };
+
+        Self {
+            path: path.to_owned(),
+            line: Some(line),
+            col
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Gets the line to open the file at, starting at 1.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Gets the column to open the file at, starting at 1.
+    pub fn col(&self) -> Option<usize> {
+        self.col
     }
 }
 
+/// The lines to print with `--print`, starting at 1 and including both ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRange {
+    first: usize,
+    /// The last line, or `None` for the end of the file
+    last: Option<usize>
+}
+
+impl LineRange {
+    /// Parses `FIRST-LAST`, `FIRST-`, `-LAST`, or a single line.
+    fn parse(arg: &str) -> Result<Self, String> {
+        let parse_num = |s: &str| match s.trim().parse::<usize>() {
+            Ok(0) => Err("lines start at 1".to_owned()),
+            Ok(n) => Ok(n),
+            Err(_) => Err(format!("expected a line range like 100-200, not '{arg}'"))
+        };
+
+        let (first, last) = match arg.split_once('-') {
+            Some((first, last)) => (
+                if first.trim().is_empty() { 1 } else { parse_num(first)? },
+                if last.trim().is_empty() { None } else { Some(parse_num(last)?) }
+            ),
+            None => {
+                let line = parse_num(arg)?;
+                (line, Some(line))
+            }
+        };
+
+        match last {
+            Some(last) if last < first => Err(format!("the range ends before it starts ({first} is after {last})")),
+            _ => Ok(Self { first, last })
+        }
+    }
+
+    /// Gets the last line, or `None` if the range goes to the end of the file.
+    pub fn last(&self) -> Option<usize> {
+        self.last
+    }
+
+    /// Gets the indices (starting at 0) of the rows in the range, in a file with `num_rows` rows.
+    pub fn rows(&self, num_rows: usize) -> ops::Range<usize> {
+        let end = self.last.map_or(num_rows, |last| cmp::min(last, num_rows));
 
+        cmp::min(self.first - 1, end)..end
+    }
+}
+
+/// Where to move the cursor to after opening, like the `+N` and `+/pattern` arguments of vi.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Start {
+    /// A line number, starting at 1
+    Line(usize),
+    /// The first match of the pattern
+    Search(String)
+}
+
+impl Start {
+    /// Parses a `+N`, `+/pattern`, or `+` (the last line) argument.
+    pub fn parse(arg: &str) -> Option<Self> {
+        let arg = arg.strip_prefix('+')?;
+
+        if arg.is_empty() {
+            Some(Self::Line(usize::MAX))
+        } else if let Some(pattern) = arg.strip_prefix('/') {
+            Some(Self::Search(pattern.to_owned()))
+        } else {
+            arg.parse().ok().map(Self::Line)
+        }
+    }
+}
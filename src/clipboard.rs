@@ -27,6 +27,15 @@ impl Clipboard {
         if let Err(_) = cli_clipboard::set_contents(acc) {
             self.rows = context.to_owned();
         }
+
+        #[cfg(target_os = "linux")]
+        primary::set_contents(&context.join("\n"));
+    }
+
+    /// Returns the contents of the primary selection (ie. what was last selected in any program, which is pasted with a middle click), if it can be read.
+    #[cfg(target_os = "linux")]
+    pub fn load_primary(&self) -> Option<Vec<String>> {
+        primary::get_contents().map(|text| text.lines().map(str::to_owned).collect())
     }
 
     /// Returns the context from the system's clipboard, or if that failed, from the internal `Clipboard`.
@@ -50,4 +59,80 @@ impl Clipboard {
     pub fn clear_context(&mut self) {
         self.rows = vec![];
     }
+}
+
+/// The primary selection on Linux, which is set and read with whichever of `wl-copy`/`wl-paste` (on Wayland), `xclip` or `xsel` (on X11) is installed.
+#[cfg(target_os = "linux")]
+mod primary {
+    use std::env;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    /// Gets the commands that set the primary selection from stdin, in the order they are tried.
+    fn set_commands() -> Vec<(&'static str, &'static [&'static str])> {
+        let mut commands: Vec<(&str, &[&str])> = vec![];
+
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-copy", &["--primary"]));
+        }
+        if env::var_os("DISPLAY").is_some() {
+            commands.push(("xclip", &["-selection", "primary"]));
+            commands.push(("xsel", &["--primary", "--input"]));
+        }
+
+        commands
+    }
+
+    /// Gets the commands that write the primary selection to stdout, in the order they are tried.
+    fn get_commands() -> Vec<(&'static str, &'static [&'static str])> {
+        let mut commands: Vec<(&str, &[&str])> = vec![];
+
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-paste", &["--primary", "--no-newline"]));
+        }
+        if env::var_os("DISPLAY").is_some() {
+            commands.push(("xclip", &["-selection", "primary", "-o"]));
+            commands.push(("xsel", &["--primary", "--output"]));
+        }
+
+        commands
+    }
+
+    /// Sets the primary selection to `text`, returning whether it could be set.
+    pub fn set_contents(text: &str) -> bool {
+        set_commands().into_iter().any(|(program, args)| {
+            // Its output isn't read, as these programs stay in the background to serve the selection
+            let child = Command::new(program)
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+
+            let mut child = match child {
+                Ok(child) => child,
+                Err(_) => return false
+            };
+
+            let is_written = child.stdin
+                .take()
+                .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+
+            child.wait().is_ok_and(|status| status.success()) && is_written
+        })
+    }
+
+    /// Gets the contents of the primary selection, if any of the programs could read it.
+    pub fn get_contents() -> Option<String> {
+        get_commands().into_iter().find_map(|(program, args)| {
+            let output = Command::new(program)
+                .args(args)
+                .stdin(Stdio::null())
+                .stderr(Stdio::null())
+                .output()
+                .ok()?;
+
+            output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+    }
 }
\ No newline at end of file
@@ -1,7 +1,16 @@
-use std::time::Duration;
+use std::env;
+use std::time::{Duration, SystemTime};
 use supports_color::Stream;
 
-use crate::theme::{Theme, Themes};
+use crate::cli::Cli;
+use crate::config_file::{self, ConfigError, ConfigFile, Entry};
+use crate::lang::{is_sep, Language, Syntax};
+use crate::paths;
+use crate::project::{Project, PROJECT_FILE};
+use crate::background;
+use crate::buffer::RenderConfig;
+use crate::theme::{CursorStyle, Theme, Themes};
+use crate::welcome::Welcome;
 
 /// Holds configuration information that the user can change.
 /// 
@@ -9,34 +18,271 @@ use crate::theme::{Theme, Themes};
 #[derive(Debug, Clone)]
 pub struct Config {
     readonly: bool,
+    profile: bool,
     tab_stop: usize,
-    quit_times: u32,
+    line_numbers: bool,
+    /// The fewest columns that line numbers take up, so that the text doesn't move as the file grows
+    line_number_min_width: usize,
+    /// Drawn between the line numbers (and the markers after them) and the text
+    line_number_separator: String,
+    /// Whether lines are drawn down the indentation, at every tab stop
+    indent_guides: bool,
+    /// The columns (starting at 1) that vertical rulers are drawn at
+    rulers: Vec<usize>,
+    /// Whether spaces and tabs at the end of rows are drawn on the theme's `trailing_whitespace` color
+    highlight_trailing_whitespace: bool,
+    /// Whether brackets are colored by how deeply they are nested, using the theme's bracket colors
+    rainbow_brackets: bool,
+    /// Patterns of paths (in the `.gitignore` format) to leave out when opening or listing a directory, on top of the ones in `.gitignore` files
+    ignore: Vec<String>,
+    /// Whether rows that are too long for the screen are continued on the next line, instead of scrolling sideways
+    soft_wrap: bool,
     close_times: u32,
     msg_bar_life: Duration,
+    /// How often the screen updates while waiting for input, for timed updates like the status msg disappearing
+    tick_rate: Duration,
     prompt_bar_cursor_style: CursorStyle,
+    /// How the user is alerted when something can't be done (eg. there is nothing to undo)
+    alert: Alert,
+    /// Whether CTRL+Z suspends the editor (like in a shell) instead of undoing
+    ctrl_z_suspends: bool,
     hide_cursor_on_new_buf: bool, 
+    json_indent: usize,
+    /// Commands used to format buffers of a given language, which read from stdin and write to stdout
+    formatters: Vec<(Language, String)>,
+    format_on_save: bool,
+    /// Commands used to lint files of a given language, which output `file:line:message` or `file:line:col:message` lines
+    linters: Vec<(Language, String)>,
+    /// Whether typing an opening bracket or quote also types the closing one (see [`Syntax::bracket_pairs`])
+    auto_pairs: bool,
+    /// Whether new lines start with the indentation of the line before, and one more level after eg. `{` (see [`Syntax::indent_triggers`])
+    auto_indent: bool,
+    /// Text that replaces a trigger word when a separator is typed after it, for every language (`None`) or just one, as `(language, trigger, expansion)`
+    abbreviations: Vec<(Option<Language>, String, String)>,
     color_support: ColorSupport,
-    theme: Theme
+    /// Whether the terminal's own background is used instead of the theme's (eg. for transparent terminals)
+    transparent_bg: bool,
+    theme: Theme,
+    /// What is shown when the editor has an empty buffer
+    welcome: Welcome,
+    /// Whether to draw for terminal screen readers, leaving out what only adds noise (eg. the welcome screen, the `~`s on empty lines and alerts) and keeping each status msg until the next one
+    screen_reader: bool
 }
 
 impl Config {
-    pub fn new(readonly: bool) -> Config {
+    /// Creates the config from the defaults, overridden by the config file (if there is one), then by the `project`'s own config file (if it has one), and then by any options given on the command line.
+    pub fn load(cli: &Cli, project: Option<&Project>) -> Result<Config, ConfigError> {
         let mut config = Config::default();
-        config.readonly = readonly;
 
-        config
+        if let Some(file) = ConfigFile::load()? {
+            config.apply_file(&file)?;
+        }
+        if let Some(path) = project.and_then(Project::config_path) {
+            if let Some(file) = ConfigFile::load_from(&path).map_err(|err| err.in_file(PROJECT_FILE))? {
+                config.apply_file(&file).map_err(|err| err.in_file(PROJECT_FILE))?;
+            }
+        }
+        config.apply_cli(cli);
+
+        Ok(config)
+    }
+
+    /// Overrides the config with the options set in the config `file`. If any of them are invalid, the config is left unchanged.
+    pub fn apply_file(&mut self, file: &ConfigFile) -> Result<(), ConfigError> {
+        let mut config = self.clone();
+
+        for entry in file.section("") {
+            let value = entry.value();
+
+            match entry.key() {
+                "tab_stop" => config.tab_stop = match value.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(entry.error("Expected a number above 0"))
+                },
+                "json_indent" => config.json_indent = value.parse().map_err(|_| entry.error("Expected a number"))?,
+                "line_numbers" => config.line_numbers = parse_bool(entry)?,
+                "line_number_min_width" => config.line_number_min_width = value.parse().map_err(|_| entry.error("Expected a number"))?,
+                "line_number_separator" => config.line_number_separator = if value.chars().any(char::is_control) {
+                    return Err(entry.error("Expected text without control characters"));
+                } else {
+                    value.to_owned()
+                },
+                "soft_wrap" => config.soft_wrap = parse_bool(entry)?,
+                "indent_guides" => config.indent_guides = parse_bool(entry)?,
+                "highlight_trailing_whitespace" => config.highlight_trailing_whitespace = parse_bool(entry)?,
+                "rainbow_brackets" => config.rainbow_brackets = parse_bool(entry)?,
+                "rulers" => config.rulers = value
+                    .split(',')
+                    .map(|col| match col.trim().parse() {
+                        Ok(n) if n > 0 => Ok(n),
+                        _ => Err(entry.error("Expected numbers above 0, separated by commas"))
+                    })
+                    .collect::<Result<_, _>>()?,
+                "ignore" => config.ignore = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|pattern| !pattern.is_empty())
+                    .map(str::to_owned)
+                    .collect(),
+                "format_on_save" => config.format_on_save = parse_bool(entry)?,
+                "auto_pairs" => config.auto_pairs = parse_bool(entry)?,
+                "auto_indent" => config.auto_indent = parse_bool(entry)?,
+                "transparent_bg" => config.transparent_bg = parse_bool(entry)?,
+                "ctrl_z_suspends" => config.ctrl_z_suspends = parse_bool(entry)?,
+                "screen_reader" => config.screen_reader = parse_bool(entry)?,
+                "alert" => config.alert = match value {
+                    "flash" => Alert::Flash,
+                    "invert" => Alert::Invert,
+                    "none" => Alert::None,
+                    _ => return Err(entry.error("Expected flash, invert or none"))
+                },
+                _ => return Err(entry.error("Unknown option"))
+            }
+        }
+
+        if let Some(entry) = file.get("theme", "base") {
+            config.theme = match Themes::from_name(entry.value()) {
+                Some(base) => base.theme(),
+                None => load_user_theme(entry)?
+            };
+        }
+
+        for entry in file.section("theme").filter(|entry| entry.key() != "base") {
+            config.theme
+                .set(entry.key(), entry.value())
+                .map_err(|msg| entry.error(&msg))?;
+        }
+
+        // `[abbreviations]` apply to every language, and `[abbreviations.LANG]` to just one (eg. `[abbreviations.rust]`)
+        for entry in file.entries() {
+            let lang = match entry.section() {
+                "abbreviations" => None,
+                section => match section.strip_prefix("abbreviations.") {
+                    Some(name) => match Syntax::from_name(name) {
+                        Some(syntax) => Some(*syntax.lang()),
+                        None => return Err(entry.error(&format!("Unknown language '{name}'")))
+                    },
+                    None => continue
+                }
+            };
+
+            let trigger = entry.key();
+            if trigger.chars().any(char::is_whitespace) {
+                return Err(entry.error("Expected an abbreviation without spaces"));
+            }
+
+            // Later files (eg. the project's) replace earlier ones' abbreviations
+            config.abbreviations.retain(|(l, t, _)| *l != lang || t != trigger);
+            config.abbreviations.push((lang, trigger.to_owned(), entry.value().to_owned()));
+        }
+
+        // `[formatters]` and `[linters]` set the command for a language by its name (eg. `rust = "rustfmt"`), and an empty one turns it off
+        for (section, commands) in [("formatters", &mut config.formatters), ("linters", &mut config.linters)] {
+            for entry in file.section(section) {
+                let lang = match Syntax::from_name(entry.key()) {
+                    Some(syntax) => *syntax.lang(),
+                    None => return Err(entry.error(&format!("Unknown language '{}'", entry.key())))
+                };
+
+                commands.retain(|(l, _)| *l != lang);
+                if !entry.value().trim().is_empty() {
+                    commands.push((lang, entry.value().to_owned()));
+                }
+            }
+        }
+
+        *self = config;
+
+        Ok(())
+    }
+
+    /// Overrides the config with any options given on the command line.
+    pub fn apply_cli(&mut self, cli: &Cli) {
+        self.readonly = cli.readonly();
+        self.profile = cli.profile();
+        self.soft_wrap |= cli.soft_wrap();
+
+        if cli.no_line_numbers() {
+            self.line_numbers = false;
+        }
+
+        if cli.no_welcome() {
+            self.welcome = Welcome::disabled();
+        }
+
+        if let Some(tab_stop) = cli.tab_stop() {
+            self.tab_stop = tab_stop;
+        }
+
+        if let Some(theme) = cli.theme() {
+            self.theme = theme.theme();
+        }
+
+        if cli.no_color() {
+            self.color_support = ColorSupport::None;
+        }
+
+        self.screen_reader |= cli.screen_reader();
+        if self.screen_reader {
+            self.welcome = Welcome::disabled();
+            self.alert = Alert::None;
+        }
     }
 
     pub fn readonly(&self) -> bool {
         self.readonly
     }
 
+    pub fn profile(&self) -> bool {
+        self.profile
+    }
+
+    pub fn ignore(&self) -> &[String] {
+        &self.ignore
+    }
+
     pub fn tab_stop(&self) -> usize {
         self.tab_stop
     }
 
-    pub fn quit_times(&self) -> u32 {
-        self.quit_times
+    /// Gets a copy of the config with another tab stop, for a buffer that sets its own (see [`Modeline`](crate::modeline::Modeline)).
+    pub fn with_tab_stop(&self, tab_stop: usize) -> Config {
+        Config {
+            tab_stop,
+            ..self.clone()
+        }
+    }
+
+    pub fn line_numbers(&self) -> bool {
+        self.line_numbers
+    }
+
+    pub fn line_number_min_width(&self) -> usize {
+        self.line_number_min_width
+    }
+
+    pub fn line_number_separator(&self) -> &str {
+        &self.line_number_separator
+    }
+
+    pub fn indent_guides(&self) -> bool {
+        self.indent_guides
+    }
+
+    pub fn highlight_trailing_whitespace(&self) -> bool {
+        self.highlight_trailing_whitespace
+    }
+
+    pub fn rainbow_brackets(&self) -> bool {
+        self.rainbow_brackets
+    }
+
+    pub fn rulers(&self) -> &[usize] {
+        &self.rulers
+    }
+
+    pub fn soft_wrap(&self) -> bool {
+        self.soft_wrap
     }
 
     pub fn close_times(&self) -> u32 {
@@ -47,34 +293,143 @@ impl Config {
         self.msg_bar_life
     }
 
+    pub fn tick_rate(&self) -> Duration {
+        self.tick_rate
+    }
+
     pub fn prompt_bar_cursor_style(&self) -> CursorStyle {
         self.prompt_bar_cursor_style
     }
 
+    pub fn alert(&self) -> Alert {
+        self.alert
+    }
+
+    pub fn ctrl_z_suspends(&self) -> bool {
+        self.ctrl_z_suspends
+    }
+
     pub fn hide_cursor_on_new_buf(&self) -> bool {
         self.hide_cursor_on_new_buf
     }
 
+    pub fn json_indent(&self) -> usize {
+        self.json_indent
+    }
+
+    /// Gets the formatter command for the given language, if there is one.
+    pub fn formatter(&self, lang: &Language) -> Option<&str> {
+        self.formatters
+            .iter()
+            .find(|(l, _)| l == lang)
+            .map(|(_, cmd)| cmd.as_str())
+    }
+
+    pub fn format_on_save(&self) -> bool {
+        self.format_on_save
+    }
+
+    pub fn auto_pairs(&self) -> bool {
+        self.auto_pairs
+    }
+
+    pub fn auto_indent(&self) -> bool {
+        self.auto_indent
+    }
+
+    /// Gets the lint command for the given language, if there is one.
+    pub fn linter(&self, lang: &Language) -> Option<&str> {
+        self.linters
+            .iter()
+            .find(|(l, _)| l == lang)
+            .map(|(_, cmd)| cmd.as_str())
+    }
+
+    /// Gets the abbreviation that the `text` before the cursor ends with in a buffer of the given language, as `(trigger, expansion)`. The trigger has to be at the start of the text or after a separator, so that it isn't the end of another word. Abbreviations for the language are used over ones for every language, and longer triggers over shorter ones.
+    pub fn abbreviation(&self, lang: &Language, text: &str) -> Option<(&str, &str)> {
+        self.abbreviations
+            .iter()
+            .filter(|(l, _, _)| l.as_ref().is_none_or(|l| l == lang))
+            .filter(|(_, trigger, _)| text
+                .strip_suffix(trigger.as_str())
+                .is_some_and(|before| before.chars().next_back().is_none_or(is_sep))
+            )
+            .max_by_key(|(l, trigger, _)| (l.is_some(), trigger.len()))
+            .map(|(_, trigger, expansion)| (trigger.as_str(), expansion.as_str()))
+    }
+
     pub fn color_support(&self) -> ColorSupport {
         self.color_support
     }
 
+    pub fn transparent_bg(&self) -> bool {
+        self.transparent_bg
+    }
+
     pub fn theme(&self) -> &Theme {
         &self.theme
     }
+
+    pub fn welcome(&self) -> &Welcome {
+        &self.welcome
+    }
+
+    pub fn screen_reader(&self) -> bool {
+        self.screen_reader
+    }
+}
+
+impl RenderConfig for Config {
+    fn tab_stop(&self) -> usize {
+        self.tab_stop
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             readonly: false,
+            profile: false,
             tab_stop: 4,
-            quit_times: 1,
+            line_numbers: true,
+            line_number_min_width: 1,
+            line_number_separator: String::new(),
+            indent_guides: false,
+            rulers: vec![],
+            highlight_trailing_whitespace: false,
+            rainbow_brackets: false,
+            ignore: vec!["target/".to_owned(), "node_modules/".to_owned()],
+            soft_wrap: false,
             close_times: 1,
             msg_bar_life: Duration::from_secs(1),
+            tick_rate: Duration::from_millis(250),
             prompt_bar_cursor_style: CursorStyle::Regular,
+            alert: Alert::Flash,
+            ctrl_z_suspends: false,
             hide_cursor_on_new_buf: true,
-            color_support: if let Some(support) = supports_color::on(Stream::Stdout) {
+            json_indent: 4,
+            formatters: vec![
+                (Language::Rust, "rustfmt --edition 2021".to_owned()),
+                (Language::Python, "black -q -".to_owned()),
+                (Language::Js, "prettier --stdin-filepath {file}".to_owned()),
+                (Language::Ts, "prettier --stdin-filepath {file}".to_owned()),
+                (Language::C, "clang-format --assume-filename={file}".to_owned()),
+                (Language::Cpp, "clang-format --assume-filename={file}".to_owned()),
+            ],
+            format_on_save: false,
+            linters: vec![
+                (Language::Python, "flake8 {file}".to_owned()),
+                (Language::Js, "eslint --format unix {file}".to_owned()),
+                (Language::Ts, "eslint --format unix {file}".to_owned()),
+            ],
+            auto_pairs: false,
+            auto_indent: false,
+            abbreviations: vec![],
+            transparent_bg: false,
+            // NO_COLOR (https://no-color.org) is checked first, as supports_color lets FORCE_COLOR override it
+            color_support: if env::var_os("NO_COLOR").is_some_and(|val| !val.is_empty()) {
+                ColorSupport::None
+            } else if let Some(support) = supports_color::on(Stream::Stdout) {
                 if support.has_16m {
                     ColorSupport::RGB
                 } else if support.has_256 {
@@ -87,9 +442,93 @@ impl Default for Config {
             } else {
                 ColorSupport::None
             },
-            theme: Themes::default().theme(),
+            theme: background::default_theme().theme(),
+            welcome: Welcome::default(),
+            screen_reader: false
+        }
+    }
+}
+
+/// Watches the config file, so that the config can be loaded again when it changes.
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    /// The command line options, which still override the config file after it changes
+    cli: Cli,
+    project: Option<Project>,
+    /// When the config file and the project's config file were last modified
+    modified: (Option<SystemTime>, Option<SystemTime>)
+}
+
+impl ConfigWatcher {
+    pub fn new(cli: Cli, project: Option<Project>) -> Self {
+        let mut watcher = Self {
+            cli,
+            project,
+            modified: (None, None)
+        };
+        watcher.modified = watcher.modified();
+
+        watcher
+    }
+
+    /// Checks whether the config file or the project's config file was changed (or created or deleted) since the last check, and if so, loads the config again.
+    pub fn check(&mut self) -> Option<Result<Config, ConfigError>> {
+        let modified = self.modified();
+        if modified == self.modified {
+            return None;
         }
+
+        self.modified = modified;
+
+        Some(Config::load(&self.cli, self.project.as_ref()))
     }
+
+    fn modified(&self) -> (Option<SystemTime>, Option<SystemTime>) {
+        let project_config = self.project
+            .as_ref()
+            .map(|project| project.root().join(PROJECT_FILE));
+
+        (
+            config_file::modified(), 
+            project_config.as_deref().and_then(config_file::modified_at)
+        )
+    }
+}
+
+fn parse_bool(entry: &Entry) -> Result<bool, ConfigError> {
+    match entry.value() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(entry.error("Expected true or false"))
+    }
+}
+
+/// Loads the user's theme named by the `base` entry from its file in the themes directory. The file has the same options as the config's `[theme]` section, including a `base` to start from, which must be a built-in theme.
+fn load_user_theme(base: &Entry) -> Result<Theme, ConfigError> {
+    let name = base.value();
+    let in_file = |err: ConfigError| err.in_file(&format!("themes/{name}.toml"));
+
+    let file = paths::theme_file(name)
+        .map(|path| ConfigFile::load_from(&path))
+        .transpose()
+        .map_err(in_file)?
+        .flatten()
+        .ok_or_else(|| base.error("Unknown theme"))?;
+
+    let mut theme = match file.get("", "base") {
+        Some(entry) => Themes::from_name(entry.value())
+            .ok_or_else(|| in_file(entry.error("Unknown built-in theme")))?
+            .theme(),
+        None => Themes::default().theme()
+    };
+
+    for entry in file.section("").filter(|entry| entry.key() != "base") {
+        theme
+            .set(entry.key(), entry.value())
+            .map_err(|msg| in_file(entry.error(&msg)))?;
+    }
+
+    Ok(theme)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -100,8 +539,12 @@ pub enum ColorSupport {
     None
 }
 
+/// How the user is alerted when something can't be done.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum CursorStyle {
-    Regular,
-    BigBar
+pub enum Alert {
+    /// Briefly color the status bar
+    Flash,
+    /// Briefly invert the colors of the whole screen
+    Invert,
+    None
 }
@@ -0,0 +1,207 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::paths;
+
+/// An error found while reading the config file, along with the line (starting at 1) where it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    msg: String,
+    line: usize
+}
+
+impl ConfigError {
+    pub fn new(msg: String, line: usize) -> Self {
+        Self { msg, line }
+    }
+
+    /// Says which file the error is in, for errors in files other than the main config file.
+    pub fn in_file(self, name: &str) -> Self {
+        Self::new(format!("{name}: {}", self.msg), self.line)
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.msg)
+        } else {
+            write!(f, "{} at line {}", self.msg, self.line)
+        }
+    }
+}
+
+/// Gets when the config file was last modified, or `None` if there isn't one.
+pub fn modified() -> Option<SystemTime> {
+    modified_at(&paths::config_file()?)
+}
+
+/// Gets when the file at `path` was last modified, or `None` if there isn't one.
+pub fn modified_at(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// A `key = value` line of the config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    section: String,
+    key: String,
+    value: String,
+    line: usize
+}
+
+impl Entry {
+    /// Gets the name of the section that the entry is in, where `""` is before any section header.
+    pub fn section(&self) -> &str {
+        &self.section
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Creates an error about this entry, at its line.
+    pub fn error(&self, msg: &str) -> ConfigError {
+        ConfigError::new(format!("{msg} for '{}'", self.key), self.line)
+    }
+}
+
+/// The entries of the config file, which uses a small subset of TOML: `key = value` lines, grouped under `[section]` headers, with `#` comments. Values can be quoted strings, or bare words and numbers. Keys can also be quoted, so that they can start with `#`.
+///
+/// For example:
+/// ```toml
+/// tab_stop = 2
+///
+/// [theme]
+/// base = "github-light"
+/// comment = "#6a737d italic"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigFile {
+    entries: Vec<Entry>
+}
+
+impl ConfigFile {
+    /// Reads and parses the config file. Returns `None` if there isn't one.
+    pub fn load() -> Result<Option<Self>, ConfigError> {
+        match paths::config_file() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(None)
+        }
+    }
+
+    /// Reads and parses the config file at `path`. Returns `None` if there isn't one.
+    pub fn load_from(path: &Path) -> Result<Option<Self>, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map(Some),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(ConfigError::new(format!("Could not read '{}': {err}", path.display()), 0))
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let mut entries = vec![];
+        let mut section = String::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line_num = i + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let header = strip_comment(header);
+                section = match header.strip_suffix(']') {
+                    Some(name) => name.trim().to_owned(),
+                    None => return Err(ConfigError::new("Expected ']' after section name".to_owned(), line_num))
+                };
+
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err(ConfigError::new("Expected 'key = value'".to_owned(), line_num))
+            };
+
+            let key = key
+                .strip_prefix('"')
+                .and_then(|key| key.strip_suffix('"'))
+                .unwrap_or(key);
+
+            if key.is_empty() {
+                return Err(ConfigError::new("Missing key before '='".to_owned(), line_num));
+            }
+
+            entries.push(Entry {
+                section: section.clone(),
+                key: key.to_owned(),
+                value: parse_value(value).map_err(|msg| ConfigError::new(msg.to_owned(), line_num))?,
+                line: line_num
+            });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Gets the entries in the given section, where `""` is the entries before any section header.
+    pub fn section<'a>(&'a self, section: &'a str) -> impl Iterator<Item = &'a Entry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.section == section)
+    }
+
+    /// Gets every entry, in the order they are in the file.
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    /// Gets the entry with the given key in the given section. If there are several, the last one is used.
+    pub fn get(&self, section: &str, key: &str) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|entry| entry.section == section && entry.key == key)
+    }
+}
+
+/// Removes a trailing `#` comment. Only used outside of quoted strings, so that colors like `"#ff0000"` aren't cut off.
+fn strip_comment(s: &str) -> &str {
+    match s.find('#') {
+        Some(i) => s[..i].trim_end(),
+        None => s
+    }
+}
+
+fn parse_value(value: &str) -> Result<String, &'static str> {
+    match value.strip_prefix('"') {
+        Some(rest) => {
+            let end = rest.find('"').ok_or("Missing closing '\"'")?;
+
+            if !strip_comment(&rest[end + 1..]).trim().is_empty() {
+                return Err("Unexpected text after string");
+            }
+
+            Ok(rest[..end].to_owned())
+        }
+        None => {
+            let value = strip_comment(value);
+            if value.is_empty() {
+                return Err("Missing value after '='");
+            }
+
+            Ok(value.to_owned())
+        }
+    }
+}
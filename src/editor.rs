@@ -1,35 +1,44 @@
+use std::cell::{Ref, RefCell, RefMut};
+use std::cmp;
 use std::ops;
+use std::rc::Rc;
+use std::time::Duration;
 use crossterm::{
     self, 
     event::{self, Event, KeyEvent, KeyEventKind}
 };
 
-use crate::buffer::TextBuffer;
+use crate::buffer::{Mode, TextBuffer};
+use crate::highlight::SelectHighlight;
 use crate::clipboard::Clipboard;
 use crate::config::Config;
 use crate::error::{self, Error};
+use crate::project::Project;
+use crate::util::Pos;
 
 #[derive(Debug)]
 pub struct Editor {
-    bufs: Vec<TextBuffer>,
+    /// The tabs, several of which can show the same buffer
+    bufs: Vec<View>,
     current_buf: usize,
-    quit_times: u32,
     close_times: u32,
     last_match: LastMatch,
     is_search_forward: bool,
-    clipboard: Clipboard
+    clipboard: Clipboard,
+    /// The project that the files being edited are in, if they are in one
+    project: Option<Project>
 }
 
 impl Editor {
     pub fn new(is_readonly: bool) -> Self {
         Self {
-            bufs: vec![TextBuffer::new(is_readonly)],
+            bufs: vec![View::new(TextBuffer::new(is_readonly))],
             current_buf: 0,
-            quit_times: 0,
             close_times: 0,
             last_match: LastMatch::MinusOne,
             is_search_forward: true,
-            clipboard: Clipboard::new()
+            clipboard: Clipboard::new(),
+            project: None
         }
     }
 
@@ -51,7 +60,20 @@ impl Editor {
         Ok(editor)
     }
 
-    pub fn read_event(&mut self) -> error::Result<Option<Event>> {
+    pub fn project(&self) -> Option<&Project> {
+        self.project.as_ref()
+    }
+
+    pub fn set_project(&mut self, project: Option<Project>) {
+        self.project = project;
+    }
+
+    /// Waits up to `timeout` for an event, returning `None` if there wasn't one (or it isn't relevant).
+    pub fn read_event(&mut self, timeout: Duration) -> error::Result<Option<Event>> {
+        if !event::poll(timeout).map_err(Error::from)? {
+            return Ok(None);
+        }
+
         let e = event::read().map_err(Error::from)?;
 
         match e {
@@ -71,6 +93,9 @@ impl Editor {
             // Resize
             Event::Resize(cols, rows) => Ok(Some(Event::Resize(cols, rows))),
 
+            // Paste
+            Event::Paste(text) => Ok(Some(Event::Paste(text))),
+
             // Other
             _ => Ok(None)
         }
@@ -107,22 +132,58 @@ impl Editor {
         }
     }
 
-    pub fn get_buf(&self) -> &TextBuffer {
+    pub fn get_buf(&self) -> Ref<'_, TextBuffer> {
+        self.bufs[self.current_buf].buf()
+    }
+
+    pub fn get_buf_mut(&mut self) -> RefMut<'_, TextBuffer> {
+        self.bufs[self.current_buf].buf_mut()
+    }
+
+    /// Gets the view of the current tab.
+    pub fn view(&self) -> &View {
         &self.bufs[self.current_buf]
     }
 
-    pub fn get_buf_mut(&mut self) -> &mut TextBuffer {
+    pub fn view_mut(&mut self) -> &mut View {
         &mut self.bufs[self.current_buf]
     }
 
     pub fn append_buf(&mut self, buf: TextBuffer) {
-        self.bufs.push(buf);
+        self.bufs.push(View::new(buf));
+    }
+
+    /// Opens another view of the current buffer in a new tab right after the current one, and switches to it. The new view starts where the current one is.
+    pub fn split_buf(&mut self) {
+        let view = self.bufs[self.current_buf].clone();
+
+        self.current_buf += 1;
+        self.bufs.insert(self.current_buf, view);
+    }
+
+    /// Whether the current buffer is also shown in another tab.
+    pub fn is_buf_shared(&self) -> bool {
+        let view = self.view();
+
+        self.bufs
+            .iter()
+            .filter(|other| view.shares_buf(other))
+            .count() > 1
+    }
+
+    /// Gets the tabs that show each buffer first, along with their index, so that buffers shown in several tabs are only gone through once.
+    pub fn unique_bufs(&self) -> impl Iterator<Item = (usize, &View)> {
+        self.bufs
+            .iter()
+            .enumerate()
+            .filter(|&(i, view)| !self.bufs[..i].iter().any(|other| view.shares_buf(other)))
     }
 
     pub fn remove_buf(&mut self, idx: usize) {
         self.bufs.remove(idx);
 
-        if self.bufs.len() > 0 && self.current_buf == self.bufs.len() {
+        // Stay on the same tab when one before it is removed
+        if idx < self.current_buf || (self.bufs.len() > 0 && self.current_buf == self.bufs.len()) {
             self.current_buf -= 1;
         }
     }
@@ -131,10 +192,14 @@ impl Editor {
         self.remove_buf(self.current_buf);
     }
 
-    pub fn bufs(&self) -> &Vec<TextBuffer> {
+    pub fn bufs(&self) -> &Vec<View> {
         &self.bufs
     }
 
+    pub fn bufs_mut(&mut self) -> &mut Vec<View> {
+        &mut self.bufs
+    }
+
     pub fn current_buf(&self) -> usize {
         self.current_buf
     }
@@ -151,18 +216,6 @@ impl Editor {
         self.bufs.len()
     }
 
-    pub fn quit_times(&self) -> u32 {
-        self.quit_times
-    }
-
-    pub fn set_quit_times(&mut self, quit_times: u32) {
-        self.quit_times = quit_times;
-    }
-
-    pub fn quit_times_mut(&mut self) -> &mut u32 {
-        &mut self.quit_times
-    }
-
     pub fn close_times(&self) -> u32 {
         self.close_times
     }
@@ -204,6 +257,149 @@ impl Editor {
     }
 }
 
+/// A tab, which shows a buffer that other tabs can show too, with its own cursor, scroll position, selection and search match. Edits made in one view of a buffer show up in all of them.
+#[derive(Debug, Clone)]
+pub struct View {
+    buf: Rc<RefCell<TextBuffer>>,
+    is_selecting: bool,
+    select_anchor: Option<Pos>,
+    /// The text found by the search, from its start to its end
+    search_match: Option<(Pos, Pos)>,
+    /// Where the cursor was when the view was last left, so that it can be put back when switching to it
+    saved_cursor_pos: Pos,
+    /// The row and column offsets the view was scrolled to when it was last left
+    saved_offset: Pos,
+    options: ViewOptions
+}
+
+impl View {
+    pub fn new(buf: TextBuffer) -> Self {
+        Self {
+            buf: Rc::new(RefCell::new(buf)),
+            is_selecting: false,
+            select_anchor: None,
+            search_match: None,
+            saved_cursor_pos: Pos(0, 0),
+            saved_offset: Pos(0, 0),
+            options: ViewOptions::default()
+        }
+    }
+
+    pub fn buf(&self) -> Ref<'_, TextBuffer> {
+        self.buf.borrow()
+    }
+
+    /// Gets the buffer to change. The buffer is shared with any other views of it, so this takes `&self`. 
+    pub fn buf_mut(&self) -> RefMut<'_, TextBuffer> {
+        self.buf.borrow_mut()
+    }
+
+    /// Whether this view and `other` show the same buffer.
+    pub fn shares_buf(&self, other: &View) -> bool {
+        Rc::ptr_eq(&self.buf, &other.buf)
+    }
+
+    /// Gets the mode the view is in, which is [`Mode::Select`] while selecting, and otherwise depends on whether the buffer can be edited (see [`TextBuffer::mode`]).
+    pub fn mode(&self) -> Mode {
+        if self.is_selecting {
+            Mode::Select
+        } else {
+            self.buf().mode()
+        }
+    }
+
+    pub fn is_in_select_mode(&self) -> bool {
+        self.is_selecting
+    }
+
+    /// Starts selecting from `anchor`.
+    pub fn enter_select_mode(&mut self, anchor: Pos) {
+        self.is_selecting = true;
+        self.select_anchor = Some(anchor);
+    }
+
+    pub fn exit_select_mode(&mut self) {
+        self.is_selecting = false;
+        self.select_anchor = None;
+    }
+
+    /// Gets where the selection started, moved back into the buffer if edits in another view removed what it was on.
+    pub fn select_anchor(&self) -> Option<Pos> {
+        self.select_anchor.map(|anchor| self.buf().clamp_pos(anchor))
+    }
+
+    pub fn set_search_match(&mut self, search_match: Option<(Pos, Pos)>) {
+        self.search_match = search_match;
+    }
+
+    /// Gets the columns of row `y` that the selection (up to the `cursor`) and search match cover (see [`TextBuffer::overlays_at`]).
+    pub fn overlays_at(&self, y: usize, cursor: Pos) -> Vec<(ops::Range<usize>, SelectHighlight)> {
+        let selection = self.select_anchor().map(|anchor| (cmp::min(anchor, cursor), cmp::max(anchor, cursor)));
+
+        self.buf().overlays_at(y, selection, self.search_match)
+    }
+
+    /// Gets the saved cursor position, moved back into the buffer if edits in another view removed what it was on.
+    pub fn saved_cursor_pos(&self) -> Pos {
+        self.buf().clamp_pos(self.saved_cursor_pos)
+    }
+
+    pub fn set_cursor_pos(&mut self, pos: Pos) {
+        self.saved_cursor_pos = pos;
+    }
+
+    pub fn saved_offset(&self) -> Pos {
+        self.saved_offset
+    }
+
+    pub fn set_offset(&mut self, offset: Pos) {
+        self.saved_offset = offset;
+    }
+
+    pub fn options(&self) -> &ViewOptions {
+        &self.options
+    }
+
+    pub fn options_mut(&mut self) -> &mut ViewOptions {
+        &mut self.options
+    }
+}
+
+/// How a view shows its buffer, where it differs from the config, so that views of the same buffer can be shown differently (eg. one wrapped, and one not). A new view of a buffer starts with the options of the one it was opened from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ViewOptions {
+    soft_wrap: Option<bool>,
+    line_numbers: Option<bool>,
+    rulers: Option<bool>
+}
+
+impl ViewOptions {
+    pub fn soft_wrap(&self, config: &Config) -> bool {
+        self.soft_wrap.unwrap_or(config.soft_wrap())
+    }
+
+    pub fn set_soft_wrap(&mut self, soft_wrap: bool) {
+        self.soft_wrap = Some(soft_wrap);
+    }
+
+    pub fn line_numbers(&self, config: &Config) -> bool {
+        self.line_numbers.unwrap_or(config.line_numbers())
+    }
+
+    pub fn set_line_numbers(&mut self, line_numbers: bool) {
+        self.line_numbers = Some(line_numbers);
+    }
+
+    /// Whether the rulers in the config (or the language's own) are drawn.
+    pub fn rulers(&self) -> bool {
+        self.rulers.unwrap_or(true)
+    }
+
+    pub fn set_rulers(&mut self, rulers: bool) {
+        self.rulers = Some(rulers);
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum LastMatch {
     MinusOne,
@@ -0,0 +1,84 @@
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{self, Error};
+
+/// How long a formatter can run before it is stopped, so that one that hangs (eg. waiting for input) doesn't freeze the editor.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pipes `text` through the given formatter `command`, returning what it wrote to its standard output.
+/// 
+/// The command is split on whitespace, and any `{file}` in it is replaced with `file_name` (eg. for `prettier --stdin-filepath {file}`). It is run in `dir`, or the current directory if that is empty. It is killed if it takes longer than [`TIMEOUT`].
+pub fn run(command: &str, dir: &Path, file_name: &str, text: &str) -> error::Result<String> {
+    let mut parts = command
+        .split_whitespace()
+        .map(|part| part.replace("{file}", file_name));
+
+    let program = match parts.next() {
+        Some(program) => program,
+        None => return Err(Error::Command("Formatter command is empty".to_owned()))
+    };
+
+    let mut cmd = Command::new(&program);
+    if !dir.as_os_str().is_empty() {
+        cmd.current_dir(dir);
+    }
+
+    let mut child = cmd
+        .args(parts)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| Error::Command(format!("Could not run '{program}': {err}")))?;
+
+    // Write and read on other threads so that a formatter filling up its stdout doesn't deadlock
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = text.to_owned();
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let stdout = read_all(child.stdout.take().expect("stdout is piped"));
+    let stderr = read_all(child.stderr.take().expect("stderr is piped"));
+
+    let deadline = Instant::now() + TIMEOUT;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(Error::Command(format!("{program} took longer than {} seconds, so it was stopped", TIMEOUT.as_secs())));
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    let _ = writer.join();
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        let msg = stderr
+            .lines()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or("exited unsuccessfully");
+
+        return Err(Error::Command(format!("{program}: {msg}")));
+    }
+
+    String::from_utf8(stdout).map_err(|_| Error::Command(format!("{program} produced invalid UTF-8")))
+}
+
+/// Reads everything from `pipe` on another thread, returning the bytes read once it is joined.
+fn read_all(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = vec![];
+        let _ = pipe.read_to_end(&mut bytes);
+        bytes
+    })
+}
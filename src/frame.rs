@@ -0,0 +1,493 @@
+use std::cmp;
+use std::collections::HashMap;
+use std::fmt::Write;
+use unicode_width::UnicodeWidthChar;
+
+use crate::config::ColorSupport;
+use crate::style::{Rgb, Style};
+
+/// Converts the colors that cells are drawn with to ones the terminal supports. The conversions are remembered, as the same few colors are drawn over and over.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    support: ColorSupport,
+    /// The theme's background color
+    bg: Rgb,
+    /// Whether the theme's background is left to the terminal
+    is_transparent_bg: bool,
+    cache: HashMap<Rgb, u8>
+}
+
+impl Palette {
+    /// Creates a palette for the given color `support` and theme background color `bg`, which is left as the terminal's own background if `is_transparent_bg`.
+    pub fn new(support: ColorSupport, bg: Rgb, is_transparent_bg: bool) -> Self {
+        Self {
+            support,
+            bg,
+            is_transparent_bg,
+            cache: HashMap::new()
+        }
+    }
+
+    /// Gets the escape sequence for text in `style`'s color and font, leaving the background to the terminal, for text that is printed instead of drawn on the screen.
+    pub fn text_sgr(&mut self, style: &Style) -> String {
+        let mut s = String::from("\x1b[0");
+
+        let font = style.font().to_string();
+        let font = font.trim_end_matches(';');
+        if !font.is_empty() {
+            s.push(';');
+            s.push_str(font);
+        }
+
+        let Rgb(r, g, b) = *style.fg();
+        self.write_color(&mut s, Color::Rgb(r, g, b), 38);
+        s.push('m');
+
+        s
+    }
+
+    /// Writes the SGR parameters (eg. `;38;2;0;0;0`) to draw the text (`code` 38) or background (`code` 48) with `color`.
+    fn write_color(&mut self, s: &mut String, color: Color, code: u8) {
+        if let (Color::Rgb(r, g, b), 48) = (color, code) {
+            if self.is_transparent_bg && self.bg == Rgb(r, g, b) {
+                return;
+            }
+        }
+
+        let _ = match (color, self.support) {
+            (Color::Default, _) | (_, ColorSupport::None) => Ok(()),
+            (Color::Indexed(n), _) => write!(s, ";{code};5;{n}"),
+            (Color::Rgb(r, g, b), ColorSupport::RGB) => write!(s, ";{code};2;{r};{g};{b}"),
+            (Color::Rgb(r, g, b), ColorSupport::Bit256) => {
+                let n = *self.cache.entry(Rgb(r, g, b)).or_insert_with(|| Rgb(r, g, b).to_ansi256());
+                write!(s, ";{code};5;{n}")
+            }
+            (Color::Rgb(r, g, b), ColorSupport::Basic) => {
+                let n = *self.cache.entry(Rgb(r, g, b)).or_insert_with(|| Rgb(r, g, b).to_ansi16());
+
+                // 30-37 and 40-47 for the normal colors, and 90-97 and 100-107 for the bright ones
+                if n < 8 {
+                    write!(s, ";{}", code - 8 + n)
+                } else {
+                    write!(s, ";{}", code + 52 + n - 8)
+                }
+            }
+        };
+    }
+
+    /// Gets the attributes that a cell drawn on `bg` is given in place of its background color. Without any colors, cells on a background other than the theme's (eg. selections and search matches) are drawn in reverse video so that they still stand out.
+    fn bg_attrs(&self, bg: Color) -> u8 {
+        match (bg, self.support) {
+            (Color::Rgb(r, g, b), ColorSupport::None) if self.bg != Rgb(r, g, b) => Sgr::REVERSE,
+            (Color::Indexed(_), ColorSupport::None) => Sgr::REVERSE,
+            _ => 0
+        }
+    }
+}
+
+/// A color that a cell's text or background can be drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Color {
+    #[default]
+    Default,
+    Indexed(u8),
+    Rgb(u8, u8, u8)
+}
+
+/// The graphic rendition (ie. colors and font attributes) that a cell is drawn with, as set by SGR escape sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Sgr {
+    fg: Color,
+    bg: Color,
+    attrs: u8
+}
+
+impl Sgr {
+    const BOLD: u8       = 0b0000_0001;
+    const DIM: u8        = 0b0000_0010;
+    const ITALIC: u8     = 0b0000_0100;
+    const UNDERLINE: u8  = 0b0000_1000;
+    const BLINK: u8      = 0b0001_0000;
+    const REVERSE: u8    = 0b0010_0000;
+    const STRIKETHRU: u8 = 0b0100_0000;
+
+    /// The attributes along with their SGR codes, in the order they are written.
+    const ATTR_CODES: [(u8, &'static str); 7] = [
+        (Self::BOLD, "1"), 
+        (Self::DIM, "2"), 
+        (Self::ITALIC, "3"), 
+        (Self::UNDERLINE, "4"), 
+        (Self::BLINK, "5"), 
+        (Self::REVERSE, "7"), 
+        (Self::STRIKETHRU, "9")
+    ];
+
+    /// Applies the parameters of an SGR escape sequence (ie. the `1;38;2;0;0;0` in `\x1b[1;38;2;0;0;0m`).
+    fn apply(&mut self, params: &str) {
+        let params: Vec<u16> = params
+            .split(';')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect();
+
+        let mut i = 0;
+        while i < params.len() {
+            match params[i] {
+                0 => *self = Self::default(),
+                1 => self.attrs |= Self::BOLD,
+                2 => self.attrs |= Self::DIM,
+                3 => self.attrs |= Self::ITALIC,
+                4 => self.attrs |= Self::UNDERLINE,
+                5 => self.attrs |= Self::BLINK,
+                7 => self.attrs |= Self::REVERSE,
+                9 => self.attrs |= Self::STRIKETHRU,
+                22 => self.attrs &= !(Self::BOLD | Self::DIM),
+                23 => self.attrs &= !Self::ITALIC,
+                24 => self.attrs &= !Self::UNDERLINE,
+                25 => self.attrs &= !Self::BLINK,
+                27 => self.attrs &= !Self::REVERSE,
+                29 => self.attrs &= !Self::STRIKETHRU,
+                n @ 30..=37 => self.fg = Color::Indexed((n - 30) as u8),
+                39 => self.fg = Color::Default,
+                n @ 40..=47 => self.bg = Color::Indexed((n - 40) as u8),
+                49 => self.bg = Color::Default,
+                n @ 90..=97 => self.fg = Color::Indexed((n - 90 + 8) as u8),
+                n @ 100..=107 => self.bg = Color::Indexed((n - 100 + 8) as u8),
+                n @ (38 | 48) => {
+                    let color = match params.get(i + 1) {
+                        Some(5) => {
+                            let color = Color::Indexed(*params.get(i + 2).unwrap_or(&0) as u8);
+                            i += 2;
+                            color
+                        }
+                        Some(2) => {
+                            let channel = |j: usize| *params.get(i + j).unwrap_or(&0) as u8;
+                            let color = Color::Rgb(channel(2), channel(3), channel(4));
+                            i += 4;
+                            color
+                        }
+                        _ => Color::Default
+                    };
+
+                    if n == 38 {
+                        self.fg = color;
+                    } else {
+                        self.bg = color;
+                    }
+                }
+                _ => ()
+            }
+
+            i += 1;
+        }
+    }
+
+    /// Writes the escape sequence that resets the terminal to this rendition, with its colors converted by the `palette`.
+    fn write_to(&self, s: &mut String, palette: &mut Palette) {
+        s.push_str("\x1b[0");
+
+        let attrs = self.attrs | palette.bg_attrs(self.bg);
+        for (attr, code) in Self::ATTR_CODES {
+            if attrs & attr != 0 {
+                s.push(';');
+                s.push_str(code);
+            }
+        }
+
+        for (color, code) in [(self.fg, 38), (self.bg, 48)] {
+            palette.write_color(s, color, code);
+        }
+
+        s.push('m');
+    }
+}
+
+/// A single cell of the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    ch: char,
+    sgr: Sgr
+}
+
+impl Cell {
+    const BLANK: Self = Self {
+        ch: ' ',
+        sgr: Sgr {
+            fg: Color::Default,
+            bg: Color::Default,
+            attrs: 0
+        }
+    };
+
+    /// Marks the second cell taken up by a wide char (eg. CJK), which is drawn along with the cell before it.
+    const WIDE_CONT: char = '\0';
+}
+
+/// A grid of styled cells holding everything drawn to the terminal during a refresh.
+/// 
+/// Comparing a frame to the previous one allows for only redrawing the cells that changed.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    /// Zero-width chars (eg. combining accents) drawn on top of the char in a cell, by cell index
+    marks: HashMap<usize, String>,
+    cursor: (usize, usize),
+    is_cursor_visible: bool,
+    cursor_style: Option<String>,
+    did_reset: bool
+}
+
+impl Frame {
+    /// Creates a blank `Frame` of the given size.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::BLANK; width * height],
+            marks: HashMap::new(),
+            cursor: (0, 0),
+            is_cursor_visible: true,
+            cursor_style: None,
+            did_reset: false
+        }
+    }
+
+    /// Builds a `Frame` by interpreting the text and escape sequences written to the terminal while drawing.
+    pub fn from_output(output: &str, width: usize, height: usize) -> Self {
+        let mut frame = Self::new(width, height);
+        frame.draw(output);
+
+        frame
+    }
+
+    /// Builds a `Frame` by interpreting `output` on top of the `prev` frame, for when only part of the screen was drawn.
+    pub fn from_output_over(prev: &Frame, output: &str) -> Self {
+        let mut frame = Self {
+            cursor_style: None,
+            is_cursor_visible: true,
+            did_reset: false,
+            ..prev.clone()
+        };
+        frame.draw(output);
+
+        frame
+    }
+
+    /// Interprets the text and escape sequences in `output`, drawing them onto the frame.
+    fn draw(&mut self, output: &str) {
+        let (width, height) = (self.width, self.height);
+        let (mut x, mut y) = (0, 0);
+        let mut sgr = Sgr::default();
+
+        let mut chars = output.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\x1b' => match chars.next() {
+                    // Control Sequence
+                    Some('[') => {
+                        let mut params = String::new();
+                        let mut command = None;
+
+                        for c in chars.by_ref() {
+                            if ('\x40'..='\x7e').contains(&c) {
+                                command = Some(c);
+                                break;
+                            }
+                            params.push(c);
+                        }
+
+                        match command {
+                            Some('m') => sgr.apply(&params),
+                            Some('H') => {
+                                let mut coords = params.split(';').map(|p| p.parse::<usize>().unwrap_or(1).saturating_sub(1));
+                                y = coords.next().unwrap_or(0);
+                                x = coords.next().unwrap_or(0);
+                            }
+                            Some('K') => {
+                                let range = match params.as_str() {
+                                    "" | "0" => x..width,
+                                    "1" => 0..x + 1,
+                                    _ => 0..width
+                                };
+
+                                self.erase(y, range, sgr);
+                            }
+                            Some('J') if params == "2" => {
+                                for row in 0..height {
+                                    self.erase(row, 0..width, sgr);
+                                }
+                            }
+                            Some('q') => self.cursor_style = Some(format!("\x1b[{params}q")),
+                            Some(c @ ('h' | 'l')) if params == "?25" => self.is_cursor_visible = c == 'h',
+                            _ => ()
+                        }
+                    }
+
+                    // Full Reset
+                    Some('c') => {
+                        *self = Self::new(width, height);
+                        self.did_reset = true;
+                        (x, y) = (0, 0);
+                        sgr = Sgr::default();
+                    }
+
+                    _ => ()
+                },
+
+                '\r' => x = 0,
+                '\n' => y += 1,
+                _ if ch.is_control() => (),
+
+                _ => match ch.width().unwrap_or(0) {
+                    0 => {
+                        let prev_x = match self.cell_mut(x.wrapping_sub(1), y) {
+                            Some(Cell { ch: Cell::WIDE_CONT, .. }) => x.wrapping_sub(2),
+                            _ => x.wrapping_sub(1)
+                        };
+
+                        if prev_x < width && y < height {
+                            self.marks.entry(y * width + prev_x).or_default().push(ch);
+                        }
+                    }
+
+                    char_width => {
+                        // Wide chars that don't fit on the row are cut off
+                        if x + char_width <= width {
+                            for (i, ch) in [ch, Cell::WIDE_CONT].into_iter().take(char_width).enumerate() {
+                                self.put(x + i, y, Cell { ch, sgr });
+                            }
+                        }
+                        x += char_width;
+                    }
+                }
+            }
+        }
+
+        self.cursor = (x, y);
+    }
+
+    /// Gets the text and escape sequences needed to update the terminal from showing the `prev` frame to showing this one.
+    /// 
+    /// If there is no previous frame, or it can't be compared to this one, everything is redrawn. If nothing changed at all, nothing is written, so that idle redraws don't send the terminal (or a screen reader following it) anything.
+    pub fn diff(&self, prev: Option<&Frame>, palette: &mut Palette) -> String {
+        let mut out = String::new();
+        let blank = Self::new(self.width, self.height);
+
+        let prev = match prev {
+            Some(prev) if !self.did_reset && prev.width == self.width && prev.height == self.height => {
+                let is_cursor_same = self.cursor == prev.cursor 
+                    && self.is_cursor_visible == prev.is_cursor_visible 
+                    && self.cursor_style == prev.cursor_style;
+
+                if is_cursor_same && self.cells == prev.cells && self.marks == prev.marks {
+                    return out;
+                }
+
+                prev
+            }
+            _ => {
+                out.push_str(if self.did_reset { "\x1bc" } else { "\x1b[0m\x1b[2J" });
+                &blank
+            }
+        };
+
+        out.push_str("\x1b[?25l");
+
+        for y in 0..self.height {
+            let row = &self.cells[y * self.width..(y + 1) * self.width];
+            let prev_row = &prev.cells[y * self.width..(y + 1) * self.width];
+
+            let is_same = |x: usize| {
+                let i = y * self.width + x;
+                row[x] == prev_row[x] && self.marks.get(&i) == prev.marks.get(&i)
+            };
+
+            let mut x = 0;
+            while x < self.width {
+                if is_same(x) {
+                    x += 1;
+                    continue;
+                }
+
+                // Wide chars are redrawn from their first cell
+                if row[x].ch == Cell::WIDE_CONT && x > 0 {
+                    x -= 1;
+                }
+
+                // Draw the changed span, continuing through short runs of unchanged cells rather than moving the cursor again
+                let _ = write!(out, "\x1b[{};{}H", y + 1, x + 1);
+                let mut sgr = None;
+
+                while x < self.width {
+                    if (x..cmp::min(x + 4, self.width)).all(is_same) {
+                        break;
+                    }
+
+                    // The char before this already covers it
+                    if row[x].ch == Cell::WIDE_CONT {
+                        x += 1;
+                        continue;
+                    }
+
+                    if sgr != Some(row[x].sgr) {
+                        row[x].sgr.write_to(&mut out, palette);
+                        sgr = Some(row[x].sgr);
+                    }
+
+                    out.push(row[x].ch);
+                    if let Some(marks) = self.marks.get(&(y * self.width + x)) {
+                        out.push_str(marks);
+                    }
+                    x += 1;
+                }
+            }
+        }
+
+        out.push_str("\x1b[0m");
+
+        if let Some(style) = &self.cursor_style {
+            out.push_str(style);
+        }
+
+        let (x, y) = self.cursor;
+        let _ = write!(out, "\x1b[{};{}H", y + 1, x + 1);
+
+        if self.is_cursor_visible {
+            out.push_str("\x1b[?25h");
+        }
+
+        out
+    }
+
+    /// Puts the `cell` at the given position (if it's on the frame), replacing anything that was drawn there.
+    fn put(&mut self, x: usize, y: usize, cell: Cell) {
+        if let Some(old) = self.cell_mut(x, y) {
+            *old = cell;
+            self.marks.remove(&(y * self.width + x));
+        }
+    }
+
+    fn cell_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell> {
+        if x < self.width && y < self.height {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Erases the given range of cells in row `y`. Like terminals do, erased cells keep the current background color.
+    fn erase(&mut self, y: usize, range: std::ops::Range<usize>, sgr: Sgr) {
+        let erased = Cell {
+            ch: ' ',
+            sgr: Sgr {
+                bg: sgr.bg,
+                ..Sgr::default()
+            }
+        };
+
+        for x in range {
+            self.put(x, y, erased);
+        }
+    }
+}
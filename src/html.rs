@@ -0,0 +1,108 @@
+use std::fmt::Write;
+use std::fs;
+use std::io;
+
+use crate::buffer::TextBuffer;
+use crate::config::Config;
+use crate::highlight::Highlight;
+use crate::style::{FontStyle, Style};
+use crate::theme::Theme;
+
+/// Renders the rows of `buf` as a standalone HTML page, with the colors and font styles of the `theme`. The rows should be highlighted first (see [`TextBuffer::highlight_rows`]), and selections and search matches are left out.
+pub fn render(buf: &TextBuffer, theme: &Theme) -> String {
+    let normal = *theme.normal();
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body style=\"margin: 0; background: {}\">\n<pre style=\"margin: 0; padding: 1em; {}\">",
+        escape(buf.display_name()),
+        theme.bg().to_hex(),
+        css(&normal, theme)
+    );
+
+    for row in buf.rows() {
+        // The style of the span that is open, if any
+        let mut span = None;
+
+        for (i, ch) in row.render().char_indices() {
+            let hl = row.hl().get(i).map_or(Highlight::NORMAL, |hl| Highlight::from_syntax_hl(hl.syntax_hl()));
+            let style = hl.to_style(theme);
+
+            if span != Some(style) {
+                if span.is_some() {
+                    html.push_str("</span>");
+                }
+
+                span = (style != normal).then_some(style);
+                if let Some(style) = span {
+                    let _ = write!(html, "<span style=\"{}\">", css(&style, theme));
+                }
+            }
+
+            html.push_str(&escape(ch.encode_utf8(&mut [0; 4])));
+        }
+
+        if span.is_some() {
+            html.push_str("</span>");
+        }
+        html.push('\n');
+    }
+
+    html.push_str("</pre>\n</body>\n</html>\n");
+
+    html
+}
+
+/// Gets the CSS for drawing text in `style`. The background is only set if it isn't the `theme`'s.
+fn css(style: &Style, theme: &Theme) -> String {
+    let font = style.font();
+    let (fg, bg) = if font.contains(FontStyle::REVERSE) {
+        (style.bg(), style.fg())
+    } else {
+        (style.fg(), style.bg())
+    };
+
+    let mut css = format!("color: {}", fg.to_hex());
+    if bg != theme.bg() {
+        let _ = write!(css, "; background: {}", bg.to_hex());
+    }
+    if font.contains(FontStyle::BOLD) {
+        css.push_str("; font-weight: bold");
+    }
+    if font.contains(FontStyle::ITALIC) {
+        css.push_str("; font-style: italic");
+    }
+    if font.contains(FontStyle::DIM) {
+        css.push_str("; opacity: 0.6");
+    }
+
+    let lines: Vec<_> = [(FontStyle::UNDERLINE, "underline"), (FontStyle::STRIKETHRU, "line-through")]
+        .into_iter()
+        .filter(|(style, _)| font.contains(*style))
+        .map(|(_, line)| line)
+        .collect();
+    if !lines.is_empty() {
+        let _ = write!(css, "; text-decoration: {}", lines.join(" "));
+    }
+
+    css
+}
+
+/// Escapes the chars in `text` that have a meaning in HTML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes the whole of `buf` to an HTML file at `path` (see [`render`]), loading and highlighting any rows that aren't yet.
+pub fn export(buf: &mut TextBuffer, path: &str, config: &Config) -> io::Result<()> {
+    buf.load_all_rows(config);
+
+    let num_rows = buf.num_rows();
+    buf.highlight_rows(0..num_rows, usize::MAX, config.rainbow_brackets());
+
+    fs::write(path, render(buf, config.theme()))
+}
@@ -0,0 +1,296 @@
+use std::fmt;
+
+/// How deeply arrays and objects can be nested, as each level is parsed by a nested call and too many would overflow the stack.
+const MAX_DEPTH: usize = 256;
+
+/// An error found while parsing JSON, along with the line and column (both starting at 1) where it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    msg: String,
+    line: usize,
+    col: usize
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at line {}, column {}", self.msg, self.line, self.col)
+    }
+}
+
+/// Reformats the given JSON `text`, indenting nested values by `indent` spaces, or minifying it if `indent` is `None`.
+pub fn format(text: &str, indent: Option<usize>) -> Result<String, JsonError> {
+    let mut formatter = Formatter {
+        chars: text.chars().collect(),
+        idx: 0,
+        out: String::with_capacity(text.len()),
+        indent,
+        depth: 0
+    };
+
+    formatter.skip_whitespace();
+    formatter.value()?;
+    formatter.skip_whitespace();
+
+    if formatter.idx < formatter.chars.len() {
+        return Err(formatter.error("Unexpected trailing characters"));
+    }
+
+    Ok(formatter.out)
+}
+
+/// Parses JSON and writes it back out in the requested style at the same time.
+struct Formatter {
+    chars: Vec<char>,
+    idx: usize,
+    out: String,
+    indent: Option<usize>,
+    depth: usize
+}
+
+impl Formatter {
+    fn value(&mut self) -> Result<(), JsonError> {
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string(),
+            Some('-' | '0'..='9') => self.number(),
+            Some('t') => self.literal("true"),
+            Some('f') => self.literal("false"),
+            Some('n') => self.literal("null"),
+            Some(ch) => Err(self.error(&format!("Unexpected character '{ch}'"))),
+            None => Err(self.error("Unexpected end of input"))
+        }
+    }
+
+    fn object(&mut self) -> Result<(), JsonError> {
+        self.expect('{')?;
+        self.skip_whitespace();
+
+        if self.peek() == Some('}') {
+            self.idx += 1;
+            self.out.push_str("{}");
+            return Ok(());
+        }
+
+        self.out.push('{');
+        self.nest()?;
+
+        loop {
+            self.newline();
+            self.skip_whitespace();
+
+            if self.peek() != Some('"') {
+                return Err(self.error("Expected a string key"));
+            }
+            self.string()?;
+
+            self.skip_whitespace();
+            self.expect(':')?;
+            self.out.push(':');
+            if self.indent.is_some() {
+                self.out.push(' ');
+            }
+
+            self.skip_whitespace();
+            self.value()?;
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => {
+                    self.idx += 1;
+                    self.out.push(',');
+                }
+                Some('}') => {
+                    self.idx += 1;
+                    break;
+                }
+                _ => return Err(self.error("Expected ',' or '}'"))
+            }
+        }
+
+        self.depth -= 1;
+        self.newline();
+        self.out.push('}');
+
+        Ok(())
+    }
+
+    fn array(&mut self) -> Result<(), JsonError> {
+        self.expect('[')?;
+        self.skip_whitespace();
+
+        if self.peek() == Some(']') {
+            self.idx += 1;
+            self.out.push_str("[]");
+            return Ok(());
+        }
+
+        self.out.push('[');
+        self.nest()?;
+
+        loop {
+            self.newline();
+            self.skip_whitespace();
+            self.value()?;
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => {
+                    self.idx += 1;
+                    self.out.push(',');
+                }
+                Some(']') => {
+                    self.idx += 1;
+                    break;
+                }
+                _ => return Err(self.error("Expected ',' or ']'"))
+            }
+        }
+
+        self.depth -= 1;
+        self.newline();
+        self.out.push(']');
+
+        Ok(())
+    }
+
+    fn string(&mut self) -> Result<(), JsonError> {
+        self.expect('"')?;
+        self.out.push('"');
+
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.idx += 1;
+                    self.out.push('"');
+                    return Ok(());
+                }
+                Some('\\') => {
+                    self.idx += 1;
+                    self.out.push('\\');
+
+                    match self.peek() {
+                        Some(ch @ ('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't')) => {
+                            self.idx += 1;
+                            self.out.push(ch);
+                        }
+                        // Followed by exactly 4 hex digits
+                        Some('u') => {
+                            let digits = self.chars.get(self.idx + 1..self.idx + 5).unwrap_or_default();
+                            if digits.len() < 4 || !digits.iter().all(char::is_ascii_hexdigit) {
+                                return Err(self.error("Expected 4 hex digits after '\\u'"));
+                            }
+
+                            self.out.push('u');
+                            self.out.extend(digits);
+                            self.idx += 5;
+                        }
+                        _ => return Err(self.error("Invalid escape sequence"))
+                    }
+                }
+                // Only the C0 control characters have to be escaped
+                Some(ch) if ch < '\u{20}' => return Err(self.error("Unescaped control character in string")),
+                Some(ch) => {
+                    self.idx += 1;
+                    self.out.push(ch);
+                }
+                None => return Err(self.error("Unterminated string"))
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<(), JsonError> {
+        let start = self.idx;
+
+        while let Some(ch) = self.peek() {
+            if ch.is_ascii_digit() || matches!(ch, '-' | '+' | '.' | 'e' | 'E') {
+                self.idx += 1;
+            } else {
+                break;
+            }
+        }
+
+        let num: String = self.chars[start..self.idx].iter().collect();
+        if num.parse::<f64>().is_err() || num.starts_with('+') || num.ends_with('.') {
+            self.idx = start;
+            return Err(self.error(&format!("Invalid number '{num}'")));
+        }
+
+        self.out.push_str(&num);
+
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &str) -> Result<(), JsonError> {
+        for expected in word.chars() {
+            if self.peek() != Some(expected) {
+                return Err(self.error(&format!("Expected '{word}'")));
+            }
+            self.idx += 1;
+        }
+
+        self.out.push_str(word);
+
+        Ok(())
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(), JsonError> {
+        if self.peek() == Some(ch) {
+            self.idx += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{ch}'")))
+        }
+    }
+
+    /// Goes one level deeper into an array or object, unless they are nested too deeply.
+    fn nest(&mut self) -> Result<(), JsonError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error(&format!("Nested more than {MAX_DEPTH} levels deep")));
+        }
+
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Starts a new line at the current depth, if not minifying.
+    fn newline(&mut self) {
+        if let Some(indent) = self.indent {
+            self.out.push('\n');
+
+            for _ in 0..indent * self.depth {
+                self.out.push(' ');
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.idx += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.idx).copied()
+    }
+
+    fn error(&self, msg: &str) -> JsonError {
+        let mut line = 1;
+        let mut col = 1;
+
+        for &ch in &self.chars[..self.idx.min(self.chars.len())] {
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+
+        JsonError {
+            msg: msg.to_owned(),
+            line,
+            col
+        }
+    }
+}
@@ -0,0 +1,110 @@
+/// A keybinding shown on the keybinds help page, and on the welcome screen if it has a welcome label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keybind {
+    keys: &'static str,
+    action: &'static str,
+    welcome: Option<&'static str>
+}
+
+impl Keybind {
+    const fn new(keys: &'static str, action: &'static str) -> Self {
+        Self {
+            keys,
+            action,
+            welcome: None
+        }
+    }
+
+    const fn on_welcome(keys: &'static str, action: &'static str, welcome: &'static str) -> Self {
+        Self {
+            keys,
+            action,
+            welcome: Some(welcome)
+        }
+    }
+
+    /// Gets the keys, in the form used on the help page (eg. `CTRL + SHIFT + S`).
+    pub fn keys(&self) -> &'static str {
+        self.keys
+    }
+
+    pub fn action(&self) -> &'static str {
+        self.action
+    }
+
+    /// Gets the shorter name of the action shown on the welcome screen, if it is shown there.
+    pub fn welcome(&self) -> Option<&'static str> {
+        self.welcome
+    }
+
+    /// Gets the keys in the shorter form used on the welcome screen (eg. `Ctrl Shift S`).
+    pub fn short_keys(&self) -> String {
+        self.keys
+            .split(" + ")
+            .map(|key| match key {
+                "CTRL" => "Ctrl",
+                "SHIFT" => "Shift",
+                "ALT" => "Alt",
+                _ => key
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Every keybinding, in the order they're listed in.
+pub const KEYBINDS: &[Keybind] = &[
+    Keybind::on_welcome("CTRL + Q", "Quit Mino Editor", "Quit"),
+    Keybind::on_welcome("CTRL + W", "Close Current Tab", "Close Tab"),
+    Keybind::new("CTRL + ALT + W", "Close All Other Tabs"),
+    Keybind::new("CTRL + SHIFT + W", "Close All Tabs"),
+    Keybind::on_welcome("CTRL + N", "Create New File", "New"),
+    Keybind::on_welcome("CTRL + O", "Open File", "Open"),
+    Keybind::new("ALT + O", "Browse Project Files"),
+    Keybind::on_welcome("CTRL + S", "Save File", "Save"),
+    Keybind::new("CTRL + SHIFT + S", "Rename & Save File (Save As)"),
+    Keybind::on_welcome("CTRL + F", "Find Text", "Find Text"),
+    Keybind::new("ALT + S", "Find & Replace All"),
+    Keybind::new("CTRL + R", "Rename File"),
+    Keybind::new("ALT + R", "Revert File To Last Save"),
+    Keybind::new("ALT + E", "Convert Line Endings (LF/CRLF)"),
+    Keybind::new("CTRL + ALT + E", "Export File To HTML"),
+    Keybind::new("CTRL + ALT + S", "Write Selection To File"),
+    Keybind::new("CTRL + SHIFT + R", "Reload Editor (\x1b[3min case of visual bug\x1b[23m)"),
+    Keybind::new("CTRL + T", "Toggle Table View (CSV/TSV)"),
+    Keybind::new("CTRL + P", "Toggle Markdown Preview"),
+    Keybind::new("ALT + H", "Show Message History"),
+    Keybind::new("CTRL + G", "Show File Statistics"),
+    Keybind::new("CTRL + ALT + D", "Compare With Another Tab (Side By Side)"),
+    Keybind::new("ALT + N", "Jump To Next Difference (Diff Mode)"),
+    Keybind::new("ALT + P", "Jump To Previous Difference (Diff Mode)"),
+    Keybind::new("ALT + F", "Format File"),
+    Keybind::new("ALT + L", "Lint File"),
+    Keybind::new("ALT + D", "Open/Focus/Close Diagnostics Panel"),
+    Keybind::new("ALT + J", "Pretty-print JSON"),
+    Keybind::new("ALT + M", "Minify JSON"),
+    Keybind::new("CTRL + A", "Select Entire File"),
+    Keybind::new("CTRL + D", "Select Word"),
+    Keybind::new("CTRL + L", "Select Line (Again To Extend)"),
+    Keybind::new("ALT + I", "Select Inside Brackets Or Quotes (Again To Extend)"),
+    Keybind::new("ALT + A", "Select Around Brackets Or Quotes (Again To Extend)"),
+    Keybind::new("SHIFT + Home/End", "Select To Start/End Of Line"),
+    Keybind::new("SHIFT + PgUp/PgDn", "Select By Page"),
+    Keybind::new("CTRL + C", "Copy Selection To Clipboard"),
+    Keybind::new("CTRL + V", "Paste From Clipboard"),
+    Keybind::new("CTRL + ALT + V", "Paste From Primary Selection"),
+    Keybind::new("CTRL + Z", "Undo (Or Suspend, With `ctrl_z_suspends`)"),
+    Keybind::new("ALT + Z", "Undo"),
+    Keybind::new("ALT + .", "Repeat Last Edit At Cursor"),
+    Keybind::new("CTRL + Y", "Redo"),
+    Keybind::new("CTRL + Tab", "Go To Next Tab"),
+    Keybind::new("ALT + 1-9", "Go To Tab 1-9"),
+    Keybind::new("ALT + 0", "Go To Last Tab"),
+    Keybind::new("ALT + V", "Open Another View Of File In New Tab"),
+    Keybind::new("ALT + W", "Toggle Soft Wrap In This Tab"),
+    Keybind::new("ALT + G", "Toggle Line Numbers In This Tab"),
+    Keybind::new("ALT + C", "Toggle Rulers In This Tab"),
+    Keybind::new("ALT + U", "Open URL Under Cursor In Browser"),
+    Keybind::on_welcome("CTRL + ?", "Open This Help Page", "Keybinds"),
+    Keybind::new("CTRL + SHIFT + /", "Open This Help Page")
+];
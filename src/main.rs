@@ -1,64 +1,439 @@
-mod buffer;
+mod background;
 mod cleanup;
 mod cli;
 mod clipboard;
 mod config;
-mod diff;
+mod config_file;
 mod editor;
-mod error;
-mod highlight;
-mod history;
-mod lang;
+mod formatter;
+mod frame;
+mod html;
+mod json;
+mod keybinds;
+mod markdown;
+mod panel;
+mod picker;
+mod profile;
+mod project;
+mod remote;
+mod repeat;
 mod screen;
+mod script;
 mod status;
-mod style;
-mod theme;
-mod util;
+mod walk;
+mod welcome;
+
+// The editing engine, which the modules here use through the same paths as their own
+use mino_core::{
+    buffer, diff, error, highlight, lang, lint, log, paths, style, table, theme, url, util
+};
 
 use core::time;
+use std::backtrace::Backtrace;
 use std::env;
+use std::fs;
+use std::panic;
 use std::process;
 use std::thread;
-use config::Config;
-use crossterm::terminal::enable_raw_mode;
-use clap::Parser;
+use config::{ColorSupport, Config, ConfigWatcher};
+use std::io::{self, IsTerminal, Read, Write};
 
 use cleanup::CleanUp;
-use cli::Cli;
+use clap::CommandFactory;
+
+use buffer::TextBuffer;
+use frame::Palette;
+use highlight::Highlight;
+use style::Style;
+use cli::{Cli, Command, FileArg, RemoteCommand};
+use lang::Syntax;
+use project::Project;
+use remote::Request;
+use theme::Themes;
+use walk::{FileFilter, Ignore};
 use screen::Screen;
+use script::Script;
 
 const MINO_VER: &str = env!("CARGO_PKG_VERSION");
 
+/// The most files that are opened with `--open-dir`, so that opening a large directory by mistake doesn't hang the editor.
+const MAX_OPEN_DIR_FILES: usize = 100;
+
 fn setup() -> CleanUp {
-    enable_raw_mode().expect("An error occurred when trying to setup the program.");
+    cleanup::set_up_terminal();
 
     CleanUp
 }
 
+/// Prints the name and file extensions (or file names) of each supported language.
+fn list_languages() {
+    for syntax in Syntax::SYNTAX_SET {
+        let lang = syntax.lang();
+        let names: Vec<_> = lang.ext().iter().chain(lang.file_names()).copied().collect();
+        println!("{:<12}{}", lang.name(), names.join(", "));
+    }
+}
+
+/// Prints the name of each available theme, marking the default one.
+fn list_themes() {
+    for theme in Themes::AVAILABLE {
+        println!(
+            "{:<16}{}{}", 
+            theme.name(), 
+            if theme.is_light() { "light" } else { "dark" },
+            if theme == Themes::default() { 
+                " (default)" 
+            } else if theme == Themes::LIGHT_DEFAULT { 
+                " (default on light terminals)" 
+            } else { 
+                "" 
+            }
+        );
+    }
+
+    // The user's own themes, from the themes directory
+    let mut user_themes: Vec<_> = paths::themes_dir()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "toml").then(|| path.file_stem()?.to_str().map(str::to_owned))?
+        })
+        .collect();
+    user_themes.sort();
+
+    for name in user_themes {
+        println!("{name:<16}user");
+    }
+}
+
+/// Loads the config for the file at `path`, for commands that don't open the editor. If the config file has an error, it's printed and the default config is used instead.
+fn load_config(cli: &Cli, path: &str) -> Config {
+    let project = Project::find_for(&[path.to_owned()]);
+
+    Config::load(cli, project.as_ref()).unwrap_or_else(|err| {
+        eprintln!("Config file ignored: {err}");
+
+        let mut config = Config::default();
+        config.apply_cli(cli);
+        config
+    })
+}
+
+/// Exports the file given on the command line to an HTML file at `out`, drawn with the theme from the config, without opening the editor.
+fn export_html(cli: &Cli, out: &str) {
+    let path = util::prepend_prefix(cli.files(), cli.prefix()).remove(0);
+    let config = load_config(cli, &path);
+
+    let mut buf = TextBuffer::new(true);
+    if let Err(err) = buf.open(&path, &config) {
+        eprintln!("Could not open '{path}': {err}");
+        process::exit(1);
+    }
+
+    if let Err(err) = html::export(&mut buf, out, &config) {
+        eprintln!("Could not write '{out}': {err}");
+        process::exit(1);
+    }
+}
+
+/// Makes the edits in the script at `script_path` to the file given on the command line, without opening the editor. If the script doesn't save the file, the edited text is printed instead.
+fn run_script(cli: &Cli, script_path: &str) {
+    let script = Script::load(script_path).unwrap_or_else(|err| {
+        eprintln!("Script error: {err}");
+        process::exit(1);
+    });
+
+    let path = util::prepend_prefix(cli.files(), cli.prefix()).remove(0);
+    let config = load_config(cli, &path);
+
+    let mut buf = TextBuffer::new(false);
+    if let Err(err) = buf.open(&path, &config) {
+        eprintln!("Could not open '{path}': {err}");
+        process::exit(1);
+    }
+    buf.load_all_rows(&config);
+
+    // Only the rows before the error were read, so saving them would cut off the rest of the file
+    if buf.is_partial() {
+        eprintln!("Could not read all of '{path}', so the script wasn't run");
+        process::exit(1);
+    }
+
+    if let Err(err) = script.run(&mut buf, &config) {
+        eprintln!("Script error: {err}");
+        process::exit(1);
+    }
+
+    if !script.saves() {
+        print!("{}", buf.file_text());
+    }
+}
+
+/// Prints the file given on the command line or stdin (or just the lines given with --lines) to stdout, without opening the editor. When printing to a terminal, the text is highlighted with the theme's colors.
+fn print_file(cli: &Cli) {
+    let (mut buf, config) = if cli.read_stdin() {
+        let config = load_config(cli, ".");
+        let mut text = String::new();
+
+        if io::stdin().is_terminal() {
+            eprintln!("Nothing was piped to stdin to read from.");
+            process::exit(1);
+        } else if let Err(err) = io::stdin().read_to_string(&mut text) {
+            eprintln!("Could not read from stdin: {err}");
+            process::exit(1);
+        }
+
+        (TextBuffer::from_text(&text, true, &config), config)
+    } else {
+        let path = util::prepend_prefix(cli.files(), cli.prefix()).remove(0);
+        let config = load_config(cli, &path);
+
+        let mut buf = TextBuffer::new(true);
+        if let Err(err) = buf.open(&path, &config) {
+            eprintln!("Could not open '{path}': {err}");
+            process::exit(1);
+        }
+
+        (buf, config)
+    };
+
+    // Large files are mapped, so only the rows up to the last one printed have to be loaded
+    buf.load_rows(cli.lines().and_then(|lines| lines.last()).unwrap_or(usize::MAX), &config);
+    if buf.load_progress().is_some() {
+        buf.load_all_rows(&config);
+    }
+
+    let rows = cli.lines().map_or(0..buf.num_rows(), |lines| lines.rows(buf.num_rows()));
+    let is_highlighted = io::stdout().is_terminal() && config.color_support() != ColorSupport::None;
+    if is_highlighted {
+        buf.highlight_rows(0..rows.end, usize::MAX, config.rainbow_brackets());
+    }
+
+    let theme = config.theme();
+    let mut palette = Palette::new(config.color_support(), *theme.bg(), true);
+    let mut out = String::new();
+
+    for row in &buf.rows()[rows] {
+        if !is_highlighted {
+            out.push_str(row.chars());
+            out.push('\n');
+            continue;
+        }
+
+        let mut prev_style = None;
+        for (i, ch) in row.render().char_indices() {
+            let hl = row.hl().get(i).map_or(Highlight::NORMAL, |hl| Highlight::from_syntax_hl(hl.syntax_hl()));
+            let style = hl.to_style(theme);
+
+            if prev_style != Some(style) {
+                out.push_str(&palette.text_sgr(&style));
+                prev_style = Some(style);
+            }
+            out.push(ch);
+        }
+        out.push_str(Style::RESET);
+        out.push('\n');
+    }
+
+    // The reader may stop early (eg. when piped into head), which isn't an error
+    let _ = io::stdout().write_all(out.as_bytes());
+}
+
+/// Logs any panics to the log file, as their output is otherwise lost in raw mode.
+fn log_panics() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        log::error(&format!("{info}\n{}", Backtrace::force_capture()));
+        default_hook(info);
+    }));
+}
+
+/// Sends the files given on the command line to the editor that is already running. Returns `false` if there isn't one, so they should be opened here instead.
+fn open_remote(cli: &Cli) -> bool {
+    let requests: Vec<_> = util::prepend_prefix(cli.files(), cli.prefix())
+        .iter()
+        .map(|f| Request::Open(FileArg::parse(f)))
+        .collect();
+
+    match remote::send(&requests, cli.remote() == Some(RemoteCommand::Wait)) {
+        Ok(()) => true,
+        Err(err) if matches!(err.kind(), io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused) => false,
+        Err(err) => {
+            eprintln!("Could not send the files to the running mino: {err}");
+            process::exit(1);
+        }
+    }
+}
+
 fn main() {
     // Debugging
     #[cfg(debug_assertions)] {
         env::set_var("RUST_BACKTRACE", "1");
     }
 
-    let cli = Cli::parse();
+    let cli = Cli::parse_args();
+
+    if let Some(Command::Completions { shell }) = cli.subcommand() {
+        clap_complete::generate(*shell, &mut Cli::command(), "mino", &mut io::stdout());
+        return;
+    }
+
+    if cli.list_languages() || cli.list_themes() {
+        if cli.list_languages() {
+            list_languages();
+        }
+
+        if cli.list_themes() {
+            list_themes();
+        }
+
+        return;
+    }
+
+    // Exporting and scripts don't need the terminal, so it's done before setting it up
+    if let Some(out) = cli.export_html() {
+        export_html(&cli, out);
+        return;
+    }
+
+    if let Some(script) = cli.script() {
+        run_script(&cli, script);
+        return;
+    }
+
+    if cli.print() {
+        print_file(&cli);
+        return;
+    }
+
+    if cli.remote().is_some() && open_remote(&cli) {
+        return;
+    }
+
+    let log_err = if cli.log() {
+        log::init().map(|_| log_panics()).err()
+    } else {
+        None
+    };
+
+    // Stdin has to be read before entering raw mode. After that, crossterm reads events from /dev/tty, as stdin isn't a terminal
+    let stdin_text = if cli.read_stdin() {
+        let mut text = String::new();
+
+        if io::stdin().is_terminal() {
+            eprintln!("Nothing was piped to stdin to read from.");
+            process::exit(1);
+        } else if let Err(err) = io::stdin().read_to_string(&mut text) {
+            eprintln!("Could not read from stdin: {err}");
+            process::exit(1);
+        }
+
+        Some(text)
+    } else {
+        None
+    };
 
     let _cleanup = setup();
-    let exit = |msg: &'static str| -> ! {
+    let exit = |msg: &str| -> ! {
         drop(_cleanup);
         eprintln!("{msg}");
         thread::sleep(time::Duration::from_secs(3));
         process::exit(1);
     };
 
-    let config = Config::new(cli.readonly());
-    let file_names = util::prepend_prefix(cli.files(), cli.prefix());
-    let screen = match Screen::open(config, file_names) {
-        Ok(screen) => screen,
-        _ => {
-            exit("An error occurred.")
+    // Has to be done in raw mode, before any events are read
+    background::detect();
+
+    let mut files: Vec<FileArg> = util::prepend_prefix(cli.files(), cli.prefix())
+        .iter()
+        .map(|f| FileArg::parse(f))
+        .collect();
+
+    // The directory given with --open-dir decides the project if no files were given by themselves
+    let mut project_paths: Vec<_> = files.iter().map(|f| f.path().to_owned()).collect();
+    project_paths.extend(cli.open_dir().map(|dir| dir.to_string_lossy().into_owned()));
+    let project = Project::find_for(&project_paths);
+
+    let (config, config_err) = match Config::load(&cli, project.as_ref()) {
+        Ok(config) => (config, None),
+        Err(err) => {
+            let mut config = Config::default();
+            config.apply_cli(&cli);
+
+            (config, Some(err))
         }
     };
 
-    screen.run();
+    // The files under the directory given with --open-dir are opened after the ones given by themselves
+    let mut open_dir_msg = None;
+    if let Some(dir) = cli.open_dir() {
+        let ignore = Ignore::for_dir(dir, config.ignore(), dir);
+        let filter = FileFilter::new(cli.exts(), Some(cli.max_size()));
+
+        match walk::find_files(dir, &ignore, &filter) {
+            Ok(found) => {
+                if found.is_empty() {
+                    open_dir_msg = Some(format!("No files to open under '{}'", dir.display()));
+                } else if found.len() > MAX_OPEN_DIR_FILES {
+                    open_dir_msg = Some(format!("Opened the first {MAX_OPEN_DIR_FILES} of the {} files under '{}'", found.len(), dir.display()));
+                }
+
+                files.extend(found
+                    .iter()
+                    .take(MAX_OPEN_DIR_FILES)
+                    .map(|path| FileArg::parse(&util::normalize_separators(&path.to_string_lossy())))
+                );
+            }
+            Err(err) => exit(&format!("Could not open '{}': {err}", dir.display()))
+        }
+    }
+
+    let res = Screen::open(config, files).and_then(|mut screen| {
+        screen.editor_mut().set_project(project.clone());
+        screen.watch_config(ConfigWatcher::new(cli.clone(), project));
+
+        if let Some(text) = stdin_text {
+            screen.open_text(&text);
+        }
+
+        if cli.diff() {
+            screen.start_diff();
+        }
+
+        if let Some(root) = cli.tree() {
+            screen.open_picker(root);
+        }
+
+        if let Some(start) = cli.start() {
+            screen.start_at(start);
+        }
+
+        if let Some(err) = config_err {
+            log::warn(&format!("Config file ignored: {err}"));
+            screen.set_status_error(format!("Config file ignored: {err}"));
+        }
+
+        if let Some(msg) = open_dir_msg {
+            screen.set_status_msg(msg);
+        }
+
+        match remote::Server::start() {
+            Ok(Some(server)) => screen.listen(server),
+            Ok(None) => (),
+            Err(err) => log::warn(&format!("Could not listen for files from other instances: {err}"))
+        }
+
+        if let Some(err) = log_err {
+            screen.set_status_warning(format!("Could not open log file: {err}"));
+        }
+
+        screen.run()
+    });
+
+    if let Err(err) = res {
+        log::error(&format!("Exiting: {err}"));
+        exit(&err.to_string());
+    }
 }
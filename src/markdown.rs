@@ -0,0 +1,225 @@
+use crate::style::{FontStyle, Style};
+use crate::theme::Theme;
+
+/// A line of the rendered Markdown preview, made up of styled spans of text.
+pub type PreviewLine = Vec<(Style, String)>;
+
+/// Renders Markdown `lines` into styled preview lines.
+/// 
+/// Exactly one preview line is created for each source line, so that the preview can be scrolled along with the buffer.
+pub fn render<'a, I>(lines: I, width: usize, theme: &Theme) -> Vec<PreviewLine>
+where 
+    I: IntoIterator<Item = &'a str>
+{
+    let mut res = vec![];
+    let mut in_code_block = false;
+
+    for line in lines {
+        let trimmed = line.trim_start();
+        let mut preview = PreviewLine::new();
+
+        // Code block fences
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            preview.push((dim_style(theme), "─".repeat(width)));
+        // Code block contents
+        } else if in_code_block {
+            preview.push((code_style(theme), line.replace('\t', "    ")));
+        // Headings
+        } else if let Some(level) = heading_level(trimmed) {
+            let mut font = FontStyle::BOLD;
+            if level == 1 {
+                font |= FontStyle::UNDERLINE;
+            }
+
+            let style = Style::new(*theme.keyword().fg(), *theme.bg(), font);
+            render_inline(trimmed[level..].trim(), style, theme, &mut preview);
+        // Horizontal rules
+        } else if is_rule(trimmed) {
+            preview.push((dim_style(theme), "─".repeat(width)));
+        // Block quotes
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            let style = Style::new(*theme.dimmed(), *theme.bg(), FontStyle::ITALIC);
+
+            preview.push((dim_style(theme), "│ ".to_owned()));
+            render_inline(quote.trim_start(), style, theme, &mut preview);
+        // List items
+        } else if let Some((marker, item)) = list_item(trimmed) {
+            let indent = &line[..line.len() - trimmed.len()];
+            let bullet = Style::new(*theme.flowword().fg(), *theme.bg(), FontStyle::BOLD);
+
+            preview.push((*theme.normal(), indent.replace('\t', "    ")));
+            preview.push((bullet, marker));
+            render_inline(item, *theme.normal(), theme, &mut preview);
+        // Regular text
+        } else {
+            render_inline(line, *theme.normal(), theme, &mut preview);
+        }
+
+        res.push(preview);
+    }
+
+    res
+}
+
+/// Writes the given preview `line` as a string taking up exactly `width` columns, truncating or padding it as needed.
+pub fn fit_line(line: &PreviewLine, width: usize, theme: &Theme) -> String {
+    let mut s = String::new();
+    let mut remaining = width;
+
+    for (style, text) in line {
+        if remaining == 0 {
+            break;
+        }
+
+        let text: String = text.chars().take(remaining).collect();
+        remaining -= text.chars().count();
+
+        s.push_str(&format!("{}{style}{text}", FontStyle::RESET));
+    }
+
+    s.push_str(&format!("{}{}{:remaining$}", FontStyle::RESET, Style::default(theme), ""));
+
+    s
+}
+
+/// Renders the inline elements of a line (emphasis, code spans and links), appending them to `preview`.
+fn render_inline(text: &str, base: Style, theme: &Theme, preview: &mut PreviewLine) {
+    let mut acc = String::new();
+    let mut is_bold = false;
+    let mut is_italic = false;
+    let mut is_code = false;
+    let mut prev = ' ';
+
+    let current_style = |is_bold: bool, is_italic: bool, is_code: bool| -> Style {
+        if is_code {
+            return code_style(theme);
+        }
+
+        let mut style = base;
+        let mut font = base.font();
+        if is_bold {
+            font |= FontStyle::BOLD;
+        }
+        if is_italic {
+            font |= FontStyle::ITALIC;
+        }
+        style.set_font(font);
+
+        style
+    };
+
+    let flush = |preview: &mut PreviewLine, acc: &mut String, style: Style| {
+        if !acc.is_empty() {
+            preview.push((style, std::mem::take(acc)));
+        }
+    };
+
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, ch)) = chars.next() {
+        let next = chars.peek().map(|(_, c)| *c);
+
+        match ch {
+            '`' => {
+                flush(preview, &mut acc, current_style(is_bold, is_italic, is_code));
+                is_code = !is_code;
+            }
+
+            '*' | '_' if !is_code && (ch == '*' || !prev.is_alphanumeric() || !next.is_some_and(char::is_alphanumeric)) => {
+                flush(preview, &mut acc, current_style(is_bold, is_italic, is_code));
+
+                if next == Some(ch) {
+                    chars.next();
+                    is_bold = !is_bold;
+                } else {
+                    is_italic = !is_italic;
+                }
+            }
+
+            // Links: [text](url) -- only the text is shown
+            '[' if !is_code => {
+                let rest = &text[i + 1..];
+
+                if let Some((label, after)) = rest.split_once("](") {
+                    if let Some(end) = after.find(')') {
+                        flush(preview, &mut acc, current_style(is_bold, is_italic, is_code));
+
+                        let mut style = current_style(is_bold, is_italic, is_code);
+                        style.set_font(style.font() | FontStyle::UNDERLINE);
+                        style.set_fg(*theme.function().fg());
+                        preview.push((style, label.to_owned()));
+
+                        // Skip past the closing parenthesis
+                        let skip_to = i + 1 + label.len() + 2 + end;
+                        while let Some((j, _)) = chars.peek() {
+                            if *j > skip_to {
+                                break;
+                            }
+                            chars.next();
+                        }
+
+                        prev = ')';
+                        continue;
+                    }
+                }
+
+                acc.push(ch);
+            }
+
+            '\t' => acc.push_str("    "),
+
+            _ => acc.push(ch)
+        }
+
+        prev = ch;
+    }
+
+    flush(preview, &mut acc, current_style(is_bold, is_italic, is_code));
+}
+
+/// Gets the level of a heading line (ie. the number of `#`s), or `None` if it is not a heading.
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|&ch| ch == '#').count();
+
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+
+    let rest = &line[level..];
+    if rest.is_empty() || rest.starts_with([' ', '\t']) {
+        Some(level)
+    } else {
+        None
+    }
+}
+
+/// Checks if a line is a horizontal rule (eg. `---` or `***`).
+fn is_rule(line: &str) -> bool {
+    let chars: Vec<char> = line.chars().filter(|&ch| ch != ' ').collect();
+
+    chars.len() >= 3 && ['-', '*', '_'].iter().any(|&c| chars.iter().all(|&ch| ch == c))
+}
+
+/// Gets the marker to display and the text of a list item, or `None` if the line is not a list item.
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = line.strip_prefix(bullet) {
+            return Some(("• ".to_owned(), item));
+        }
+    }
+
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 && (line[digits..].starts_with(". ") || line[digits..].starts_with(") ")) {
+        return Some((line[..digits + 2].to_owned(), &line[digits + 2..]));
+    }
+
+    None
+}
+
+fn code_style(theme: &Theme) -> Style {
+    Style::new(*theme.string().fg(), *theme.bg(), FontStyle::default())
+}
+
+fn dim_style(theme: &Theme) -> Style {
+    Style::new(*theme.dimmed(), *theme.bg(), FontStyle::default())
+}
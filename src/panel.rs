@@ -0,0 +1,106 @@
+use std::cmp;
+
+use crate::editor::Editor;
+use crate::lint::Diagnostic;
+
+/// Holds the state of the toggleable diagnostics panel at the bottom of the screen, which lists the diagnostics of every buffer.
+#[derive(Debug)]
+pub struct DiagnosticsPanel {
+    is_open: bool,
+    is_focused: bool,
+    selected: usize,
+    offset: usize
+}
+
+impl DiagnosticsPanel {
+    /// The maximum number of rows the panel takes up, including its header.
+    pub const MAX_HEIGHT: usize = 8;
+
+    /// Creates a closed `DiagnosticsPanel`.
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            is_focused: false,
+            selected: 0,
+            offset: 0
+        }
+    }
+
+    /// Gets all diagnostics across the editor's buffers, along with the index of the (first) tab showing the buffer they belong to.
+    pub fn entries(editor: &Editor) -> Vec<(usize, Diagnostic)> {
+        editor
+            .unique_bufs()
+            .flat_map(|(i, view)| view
+                .buf()
+                .diagnostics()
+                .iter()
+                .map(|d| (i, d.clone()))
+                .collect::<Vec<_>>()
+            )
+            .collect()
+    }
+
+    /// Gets the number of rows the panel takes up, given the number of rows available on the screen.
+    pub fn height(&self, screen_rows: usize) -> usize {
+        if self.is_open {
+            cmp::min(Self::MAX_HEIGHT, screen_rows / 3)
+        } else {
+            0
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.is_focused = true;
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+        self.is_focused = false;
+    }
+
+    pub fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.is_focused = false;
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn select_next(&mut self, num_entries: usize) {
+        if self.selected + 1 < num_entries {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Keeps the selected entry within the list and scrolls so that it is visible among `num_visible` entries.
+    pub fn scroll(&mut self, num_entries: usize, num_visible: usize) {
+        self.selected = cmp::min(self.selected, num_entries.saturating_sub(1));
+
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if num_visible > 0 && self.selected >= self.offset + num_visible {
+            self.offset = self.selected - num_visible + 1;
+        }
+    }
+}
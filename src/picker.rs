@@ -0,0 +1,179 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::walk::Ignore;
+
+/// Lists the entries of a directory so that the user can choose a file to open, moving into other directories as they go.
+#[derive(Debug)]
+pub struct FilePicker {
+    /// The directory the picker was opened on, which the `ignore_patterns` are relative to when it isn't in a project
+    root: PathBuf,
+    dir: PathBuf,
+    entries: Vec<PickerEntry>,
+    selected: usize,
+    /// Patterns of entries to leave out (eg. from the config), on top of the ones in `.gitignore` files
+    ignore_patterns: Vec<String>,
+    /// Whether ignored entries are listed too
+    shows_ignored: bool
+}
+
+impl FilePicker {
+    /// Creates a `FilePicker` listing the entries of `dir`, leaving out the ones matched by the `ignore_patterns` or a `.gitignore` file.
+    pub fn open(dir: &Path, ignore_patterns: &[String]) -> io::Result<Self> {
+        let mut picker = Self {
+            root: dir.canonicalize()?,
+            dir: PathBuf::new(),
+            entries: vec![],
+            selected: 0,
+            ignore_patterns: ignore_patterns.to_owned(),
+            shows_ignored: false
+        };
+        picker.set_dir(dir)?;
+
+        Ok(picker)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn entries(&self) -> &Vec<PickerEntry> {
+        &self.entries
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn shows_ignored(&self) -> bool {
+        self.shows_ignored
+    }
+
+    /// Switches between listing ignored entries and leaving them out, keeping the selected entry selected if it is still listed.
+    pub fn toggle_ignored(&mut self) -> io::Result<()> {
+        let selected = self.entries.get(self.selected).map(|entry| entry.name.clone());
+        let dir = self.dir.clone();
+
+        self.shows_ignored = !self.shows_ignored;
+        self.set_dir(&dir)?;
+
+        if let Some(i) = selected.and_then(|name| self.entries.iter().position(|entry| entry.name == name)) {
+            self.selected = i;
+        }
+
+        Ok(())
+    }
+
+    /// Moves the selection up by `n` entries.
+    pub fn select_prev(&mut self, n: usize) {
+        self.selected = self.selected.saturating_sub(n);
+    }
+
+    /// Moves the selection down by `n` entries.
+    pub fn select_next(&mut self, n: usize) {
+        self.selected = (self.selected + n).min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Chooses the selected entry. If it is a directory, the picker moves into it and `None` is returned, otherwise the path of the chosen file is returned.
+    pub fn choose(&mut self) -> io::Result<Option<PathBuf>> {
+        let entry = match self.entries.get(self.selected) {
+            Some(entry) => entry,
+            None => return Ok(None)
+        };
+
+        let path = self.dir.join(&entry.name);
+        if entry.is_dir {
+            self.set_dir(&path)?;
+            Ok(None)
+        } else {
+            Ok(Some(path))
+        }
+    }
+
+    /// Moves the picker into the parent of its current directory.
+    pub fn go_up(&mut self) -> io::Result<()> {
+        let parent = self.dir.join("..");
+        self.set_dir(&parent)
+    }
+
+    /// Lists the entries of `dir` (besides ignored ones, unless they are being shown), with directories first, and then by name.
+    fn set_dir(&mut self, dir: &Path) -> io::Result<()> {
+        let dir = dir.canonicalize()?;
+
+        let mut ignore = Ignore::for_dir(&dir, &self.ignore_patterns, &self.root);
+        ignore.add_gitignore(&dir);
+
+        let mut entries = fs::read_dir(&dir)?
+            .filter_map(Result::ok)
+            .map(|entry| PickerEntry {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                is_dir: entry.path().is_dir()
+            })
+            .filter(|entry| self.shows_ignored || !ignore.is_ignored(&dir.join(&entry.name), entry.is_dir))
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+
+        if dir.parent().is_some() {
+            entries.insert(0, PickerEntry {
+                name: "..".to_owned(),
+                is_dir: true
+            });
+        }
+
+        self.dir = dir;
+        self.entries = entries;
+        self.selected = 0;
+
+        Ok(())
+    }
+}
+
+/// Gets the paths that `partial` could be completed to, sorted by name, with a trailing separator on directories. Hidden files are only included if the name being completed starts with a `.`.
+/// 
+/// A relative `partial` is completed from the `base` directory, although the candidates are still relative to it.
+pub fn complete_path(partial: &str, base: &Path) -> Vec<String> {
+    let (dir, prefix) = match partial.rfind(std::path::is_separator) {
+        Some(i) => partial.split_at(i + 1),
+        None => ("", partial)
+    };
+
+    let search_dir = base.join(dir);
+    let entries = match fs::read_dir(if search_dir.as_os_str().is_empty() { Path::new(".") } else { &search_dir }) {
+        Ok(entries) => entries,
+        Err(_) => return vec![]
+    };
+
+    let mut candidates = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+
+            let sep = if entry.path().is_dir() { std::path::MAIN_SEPARATOR_STR } else { "" };
+            Some(format!("{dir}{name}{sep}"))
+        })
+        .collect::<Vec<_>>();
+    candidates.sort();
+
+    candidates
+}
+
+/// A file or directory listed in a [`FilePicker`].
+#[derive(Debug, Clone)]
+pub struct PickerEntry {
+    name: String,
+    is_dir: bool
+}
+
+impl PickerEntry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+}
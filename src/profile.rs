@@ -0,0 +1,83 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// A part of drawing a frame that gets timed while profiling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    DrawRows,
+    Highlight,
+    Flush
+}
+
+impl Stage {
+    const ALL: [Stage; 3] = [Stage::DrawRows, Stage::Highlight, Stage::Flush];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::DrawRows => "draw",
+            Self::Highlight => "hl",
+            Self::Flush => "flush"
+        }
+    }
+}
+
+/// Keeps track of how long each stage of drawing takes over the last few frames, so that performance regressions are visible.
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    current: [Duration; 3],
+    samples: [VecDeque<Duration>; 3]
+}
+
+impl Profiler {
+    /// The number of frames that the averages are taken over.
+    const WINDOW: usize = 60;
+
+    pub fn new() -> Self {
+        Self {
+            current: [Duration::ZERO; 3],
+            samples: Default::default()
+        }
+    }
+
+    /// Adds time spent in a stage during the current frame.
+    pub fn add(&mut self, stage: Stage, time: Duration) {
+        self.current[stage.index()] += time;
+    }
+
+    /// Finishes the current frame, recording the time spent in each stage.
+    pub fn end_frame(&mut self) {
+        for (samples, time) in self.samples.iter_mut().zip(self.current) {
+            if samples.len() == Self::WINDOW {
+                samples.pop_front();
+            }
+
+            samples.push_back(time);
+        }
+
+        self.current = [Duration::ZERO; 3];
+    }
+
+    /// Gets the average time spent in a stage per frame.
+    pub fn average(&self, stage: Stage) -> Duration {
+        let samples = &self.samples[stage.index()];
+
+        if samples.is_empty() {
+            Duration::ZERO
+        } else {
+            samples.iter().sum::<Duration>() / samples.len() as u32
+        }
+    }
+
+    /// Gets a short summary of the average time spent in each stage (eg. `draw 1.20ms  hl 0.35ms  flush 0.08ms`).
+    pub fn summary(&self) -> String {
+        Stage::ALL
+            .iter()
+            .map(|&stage| format!("{} {:.2}ms", stage.name(), self.average(stage).as_secs_f64() * 1000.0))
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+}
@@ -0,0 +1,55 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The file that marks the root of a project, which can also hold config options for just that project, in the same format as the config file.
+pub const PROJECT_FILE: &str = ".mino";
+
+/// A directory that the files being edited belong to, found by looking for a `.git` directory or a [`PROJECT_FILE`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    root: PathBuf
+}
+
+impl Project {
+    /// Finds the project that `path` is in, by looking in it (if it's a directory) and each of its parents for a `.mino` file or `.git` directory. A relative `path` is taken from the current directory.
+    pub fn find(path: &Path) -> Option<Self> {
+        let path = if path.as_os_str().is_empty() { Path::new(".") } else { path };
+        let start = fs::canonicalize(path)
+            .or_else(|_| env::current_dir().map(|dir| dir.join(path)))
+            .ok()?;
+
+        start
+            .ancestors()
+            .find(|dir| dir.join(PROJECT_FILE).is_file() || dir.join(".git").exists())
+            .map(|root| Self { root: root.to_owned() })
+    }
+
+    /// Finds the project of the first of `paths`, or of the current directory if there aren't any.
+    pub fn find_for(paths: &[String]) -> Option<Self> {
+        match paths.first() {
+            Some(path) if Path::new(path).is_dir() => Self::find(Path::new(path)),
+            Some(path) => Self::find(Path::new(path).parent().unwrap_or(Path::new(""))),
+            None => Self::find(Path::new(""))
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Gets the name of the project, which is the name of its root directory.
+    pub fn name(&self) -> &str {
+        self.root
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("/")
+    }
+
+    /// Gets the path of the project's [`PROJECT_FILE`], if it has one.
+    pub fn config_path(&self) -> Option<PathBuf> {
+        let path = self.root.join(PROJECT_FILE);
+
+        path.is_file().then_some(path)
+    }
+}
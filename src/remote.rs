@@ -0,0 +1,221 @@
+use std::env;
+use std::io;
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::{
+    fs::{self, DirBuilder},
+    io::{Read, Write},
+    mem,
+    net::Shutdown,
+    os::unix::fs::DirBuilderExt,
+    os::unix::net::{UnixListener, UnixStream}
+};
+
+use crate::cli::FileArg;
+use crate::paths;
+
+/// Something that another instance of the editor asked the running one to do.
+#[derive(Debug, Clone)]
+pub enum Request {
+    /// Open a file in a new tab, at the line and column given with it
+    Open(FileArg)
+}
+
+impl Request {
+    /// Parses a line sent to the server, which is `open PATH` (where the path can end with `:LINE` or `:LINE:COL`).
+    fn parse(line: &str) -> Option<Self> {
+        let (cmd, arg) = line.split_once(' ')?;
+
+        match cmd {
+            "open" if !arg.is_empty() => Some(Self::Open(FileArg::parse(arg))),
+            _ => None
+        }
+    }
+
+    /// Gets the line to send to the server for this request. Paths are made absolute first, as the running editor may be in another directory.
+    fn to_line(&self) -> io::Result<String> {
+        match self {
+            Self::Open(file) => {
+                let mut arg = env::current_dir()?.join(file.path()).to_string_lossy().into_owned();
+
+                if let Some(line) = file.line() {
+                    arg.push_str(&format!(":{line}"));
+
+                    if let Some(col) = file.col() {
+                        arg.push_str(&format!(":{col}"));
+                    }
+                }
+
+                Ok(format!("open {arg}\n"))
+            }
+        }
+    }
+}
+
+/// The line sent after the requests to keep waiting until the files they opened are closed (see [`send`]).
+const WAIT_LINE: &str = "wait";
+
+fn socket_path() -> io::Result<PathBuf> {
+    paths::socket_file().ok_or(io::Error::from(io::ErrorKind::NotFound))
+}
+
+/// Sends the `requests` to the editor that is already running, if there is one. Fails with [`io::ErrorKind::NotFound`] or [`io::ErrorKind::ConnectionRefused`] if there isn't.
+/// 
+/// If `wait` is set, this only returns once the files that were opened have all been closed (or the editor quits), like an editor started on its own would (eg. when used as `$EDITOR`).
+#[cfg(unix)]
+pub fn send(requests: &[Request], wait: bool) -> io::Result<()> {
+    let mut stream = UnixStream::connect(socket_path()?)?;
+
+    for request in requests {
+        stream.write_all(request.to_line()?.as_bytes())?;
+    }
+    if wait {
+        stream.write_all(format!("{WAIT_LINE}\n").as_bytes())?;
+    }
+
+    // Closing our end tells the server that everything has been sent
+    stream.shutdown(Shutdown::Write)?;
+
+    // The server closes its end once we are done waiting
+    if wait {
+        stream.read_to_end(&mut vec![])?;
+    }
+
+    Ok(())
+}
+
+/// Sending to a running editor needs a Unix socket, so on other platforms there is never one to send to.
+#[cfg(not(unix))]
+pub fn send(_requests: &[Request], _wait: bool) -> io::Result<()> {
+    Err(io::Error::from(io::ErrorKind::NotFound))
+}
+
+/// The requests sent by another instance of the editor at once. If it is waiting for the files to be closed (see [`send`]), it waits until the `Batch` is dropped.
+#[derive(Debug)]
+pub struct Batch {
+    requests: Vec<Request>,
+    is_waiting: bool,
+    /// Closing the connection is what tells the sender to stop waiting
+    #[cfg(unix)]
+    _stream: UnixStream
+}
+
+impl Batch {
+    pub fn requests(&self) -> &[Request] {
+        &self.requests
+    }
+
+    /// Whether the sender is waiting for the files it opened to be closed.
+    pub fn is_waiting(&self) -> bool {
+        self.is_waiting
+    }
+}
+
+/// Listens on a socket for [`Request`]s from other instances of the editor (see [`send`]), so that files can be opened in this one instead of starting another. Only the first editor that is started listens.
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct Server {
+    listener: UnixListener,
+    path: PathBuf,
+    /// The connections that haven't sent everything yet, with what they have sent so far
+    pending: Vec<(UnixStream, Vec<u8>)>
+}
+
+#[cfg(unix)]
+impl Server {
+    /// Starts listening, unless another editor already is. Returns `None` if one is.
+    pub fn start() -> io::Result<Option<Self>> {
+        let path = socket_path()?;
+
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                return Ok(None);
+            }
+
+            // Left behind by an editor that didn't shut down properly
+            fs::remove_file(&path)?;
+        }
+
+        // Only the user can connect to the socket, so no one else can open files in their editor
+        if let Some(dir) = path.parent() {
+            DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(dir)?;
+        }
+
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Some(Self { listener, path, pending: vec![] }))
+    }
+
+    /// Gets the batches of requests that have been sent in full since the last poll, without waiting for any more.
+    pub fn poll(&mut self) -> Vec<Batch> {
+        while let Ok((stream, _)) = self.listener.accept() {
+            if stream.set_nonblocking(true).is_ok() {
+                self.pending.push((stream, vec![]));
+            }
+        }
+
+        let mut batches = vec![];
+
+        for (mut stream, mut bytes) in mem::take(&mut self.pending) {
+            match read_available(&mut stream, &mut bytes) {
+                Ok(true) => {
+                    let text = String::from_utf8_lossy(&bytes);
+
+                    batches.push(Batch {
+                        requests: text.lines().filter_map(Request::parse).collect(),
+                        is_waiting: text.lines().any(|line| line == WAIT_LINE),
+                        _stream: stream
+                    });
+                }
+                Ok(false) => self.pending.push((stream, bytes)),
+                // The connection is dropped
+                Err(_) => ()
+            }
+        }
+
+        batches
+    }
+}
+
+/// Reads what has been sent on `stream` so far onto the end of `bytes`, without waiting for more. Returns whether everything has been sent, which the sender shows by closing its end.
+#[cfg(unix)]
+fn read_available(stream: &mut UnixStream, bytes: &mut Vec<u8>) -> io::Result<bool> {
+    let mut buf = [0; 1024];
+
+    loop {
+        match stream.read(&mut buf) {
+            Ok(0) => return Ok(true),
+            Ok(n) => bytes.extend_from_slice(&buf[..n]),
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(false),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err)
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Without Unix sockets, nothing is listened for.
+#[cfg(not(unix))]
+#[derive(Debug)]
+pub struct Server;
+
+#[cfg(not(unix))]
+impl Server {
+    pub fn start() -> io::Result<Option<Self>> {
+        Ok(None)
+    }
+
+    pub fn poll(&mut self) -> Vec<Batch> {
+        vec![]
+    }
+}
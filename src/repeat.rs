@@ -0,0 +1,49 @@
+use crate::util::Pos;
+
+/// An edit that can be made again somewhere else (see [`LastEdit`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    /// Text typed at the cursor, including line breaks and tabs
+    Type(String),
+    /// Text pasted at the cursor
+    Paste(String),
+    /// Chars removed before the cursor (with Backspace), or after it if `is_delete` is set (with Delete)
+    Remove { count: usize, is_delete: bool }
+}
+
+/// The last edit that was made, so that it can be repeated at another place. Edits that carry on from where the one before left off (eg. typing a word one char at a time) are joined into one.
+#[derive(Debug, Clone, Default)]
+pub struct LastEdit {
+    edit: Option<Edit>,
+    /// Where the cursor was after the edit, if the next one can be joined onto it
+    end: Option<Pos>
+}
+
+impl LastEdit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an `edit` that moved the cursor from `start` to `end`.
+    pub fn record(&mut self, edit: Edit, start: Pos, end: Pos) {
+        let carries_on = self.end == Some(start);
+
+        match (&mut self.edit, edit) {
+            (Some(Edit::Type(text)), Edit::Type(more)) if carries_on => text.push_str(&more),
+            (Some(Edit::Remove { count, is_delete }), Edit::Remove { count: more, is_delete: is_also_delete })
+                if carries_on && *is_delete == is_also_delete => *count += more,
+            (last, edit) => *last = Some(edit)
+        }
+
+        self.end = Some(end);
+    }
+
+    /// Stops the next edit from being joined onto the last one, eg. after it was repeated.
+    pub fn finish(&mut self) {
+        self.end = None;
+    }
+
+    pub fn edit(&self) -> Option<&Edit> {
+        self.edit.as_ref()
+    }
+}
@@ -1,58 +1,78 @@
 use std::path::Path;
 use std::cmp;
-use std::fs::File;
+use std::fs::{self, File};
+use std::fmt::Write as _;
 use std::io::{self, Write};
+use std::ops::{ControlFlow, Range};
+use std::cell::Ref;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+use unicode_segmentation::UnicodeSegmentation;
 use crossterm::{
     cursor::{Hide, MoveTo, Show}, 
     event::{Event, KeyCode, KeyEvent, KeyModifiers}, 
     style::Print, 
     terminal::{self, Clear, ClearType}, 
-    ExecutableCommand, 
     QueueableCommand
 };
 
-use crate::{MINO_VER, pos};
-use crate::style::Style;
-use crate::config::{Config, CursorStyle};
-use crate::highlight::SelectHighlight;
-use crate::lang::Syntax;
-use crate::cleanup::CleanUp;
-use crate::buffer::{Mode, Row, TextBuffer};
-use crate::editor::{Editor, LastMatch};
+use mino_core::pos;
+use crate::style::{FontStyle, Style};
+use crate::config::{Alert, Config, ConfigWatcher};
+use crate::lang::{is_sep, Language};
+use crate::markdown;
+use crate::cleanup::{self, CleanUp};
+use crate::cli::{FileArg, Start};
+use crate::diff::{DiffView, LineChange};
+use crate::buffer::{Decorations, LineEnding, Mode, Row, TextBuffer};
+use crate::editor::{Editor, LastMatch, View, ViewOptions};
 use crate::error::{self, Error};
-use crate::status::Status;
-use crate::util::{AsU16, IntLen, Pos};
-
-const KEYBINDS_HELP: &'static str = "\
-\x1b[1mKEYBINDS HELP\x1b[22m
-
-\x1b[4mKeybind\x1b[24m             \x1b[4mAction\x1b[24m
-CTRL + Q            Quit Mino Editor
-CTRL + W            Close Current Tab
-CTRL + N            Create New File
-CTRL + O            Open File
-CTRL + S            Save File
-CTRL + SHIFT + S    Rename & Save File (Save As)
-CTRL + F            Find Text
-CTRL + R            Rename File
-CTRL + SHIFT + R    Reload Editor (\x1b[3min case of visual bug\x1b[23m)
-CTRL + A            Select Entire File
-CTRL + C            Copy Selection To Clipboard
-CTRL + V            Paste From Clipboard
-CTRL + Z            Undo
-CTRL + Y            Redo
-CTRL + Tab          Go To Next Tab
-CTRL + ?            Open This Help Page
-CTRL + SHIFT + /    Open This Help Page";
+use crate::formatter;
+use crate::frame::{Frame, Palette};
+use crate::html;
+use crate::json;
+use crate::keybinds::KEYBINDS;
+use crate::lint::{self, Linting, Severity};
+use crate::log;
+use crate::panel::DiagnosticsPanel;
+use crate::picker::{self, FilePicker};
+use crate::profile::{Profiler, Stage};
+use crate::remote::{Batch, Request, Server};
+use crate::repeat::{Edit, LastEdit};
+use crate::status::{Indicator, MsgLevel, Status};
+use crate::table;
+use crate::theme::CursorStyle;
+use crate::url;
+use crate::util::{self, AsU16, IntLen, Pos};
+use crate::welcome::WelcomeLine;
+
+/// What the size of a selection was counted for: the id of the buffer, its number of changes, and the region selected
+type SelectionKey = (usize, usize, Pos, Pos);
 
 #[derive(Debug)]
 pub struct Screen {
     stdout: io::Stdout,
+    out: Vec<u8>,
+    prev_frame: Option<Frame>,
+    /// The terminal title that was last set
+    title: String,
+    /// When the current alert stops being shown, if there is one
+    alert_until: Option<Instant>,
+    /// Whether the screen is inverted to show an alert
+    is_inverted: bool,
+    /// Whether what was queued since the last flush only redraws part of the screen, to be drawn on top of the previous frame
+    is_partial: bool,
+    /// A note shown on the right of the message bar while prompting (eg. that the search wrapped)
+    prompt_note: Option<String>,
+    palette: Palette,
     screen_rows: usize,
     screen_cols: usize,
     editor: Editor,
+    /// The config, with the current buffer's own settings (from its modeline) applied
     config: Rc<Config>,
+    /// The config without any buffer's own settings
+    base_config: Rc<Config>,
     row_offset: usize,
     col_offset: usize,
     col_start: usize,
@@ -60,21 +80,62 @@ pub struct Screen {
     cy: usize,
     rx: usize,
     in_status_area: bool,
+    show_preview: bool,
+    panel: DiagnosticsPanel,
+    msg_log: Option<usize>,
+    picker: Option<FilePicker>,
+    diff_view: Option<DiffView>,
+    /// When the files being compared are to be diffed again, after being edited (see [`Screen::schedule_diff`])
+    diff_due: Option<Instant>,
+    /// The linter running on a file that was just saved, with the id of its buffer
+    linting: Option<(Linting, usize)>,
+    /// The size of the selection when it was last counted, with what it was counted for (see [`Screen::selection_size`])
+    selection_size: Option<(SelectionKey, (usize, usize))>,
+    show_diff_pane: bool,
+    last_edit: LastEdit,
+    profiler: Option<Profiler>,
+    config_watcher: Option<ConfigWatcher>,
+    /// Where other instances of the editor send files to open in this one, if this is the one that listens
+    remote: Option<Server>,
+    /// The requests from instances that are waiting for the files they opened to be closed, with the ids of those files' buffers
+    waiting: Vec<(Batch, Vec<usize>)>,
     status: Status,
     _cleanup: CleanUp
 }
 
 impl Screen {
     const ERASE_TERM: &'static str = "\x1bc";
+    /// How long an alert is shown for
+    const ALERT_LIFE: Duration = Duration::from_millis(150);
+    /// How long each redraw can spend adding rows that have been read in the background
+    const LOAD_BUDGET: Duration = Duration::from_millis(30);
+    /// How many columns the screen needs for the Markdown preview or the file being compared with to be drawn next to the buffer
+    const MIN_SPLIT_COLS: usize = 40;
+    /// How long after the last edit the files being compared are diffed again, so that it isn't done for every key typed
+    const DIFF_DELAY: Duration = Duration::from_millis(300);
 
     pub fn new(config: Config) -> Self {
         let (cs, rs) = terminal::size().expect("An error occurred");
+        let profiler = if config.profile() {
+            Some(Profiler::new())
+        } else {
+            None
+        };
 
         Self {
             stdout: io::stdout(),
+            out: vec![],        // Everything drawn since the last flush
+            prev_frame: None,   // What the terminal is currently showing
+            title: String::new(),
+            alert_until: None,
+            is_inverted: false,
+            is_partial: false,
+            prompt_note: None,
+            palette: Self::palette(&config),
             screen_rows: rs as usize - 2, // Make room for status bar and status msg area
             screen_cols: cs as usize,
             editor: Editor::new(config.readonly()),
+            base_config: Rc::new(config.clone()),
             config: Rc::new(config),
             row_offset: 0,
             col_offset: 0,
@@ -83,73 +144,393 @@ impl Screen {
             cy: 0,
             rx: 0,
             in_status_area: false,  // If the cursor is in the status area, instead of in buffer
+            show_preview: false,    // If the Markdown preview is shown next to Markdown buffers
+            panel: DiagnosticsPanel::new(),
+            msg_log: None,          // The scroll offset of the message history, if it is open
+            picker: None,           // The directory listing to open a file from, if it is open
+            diff_view: None,        // The two files being compared, if in diff mode
+            diff_due: None,
+            linting: None,
+            selection_size: None,
+            show_diff_pane: false,  // If the file being compared with is shown next to the current buffer
+            last_edit: LastEdit::new(), // The edit that ALT+. repeats
+            profiler,
+            config_watcher: None,
+            remote: None,
+            waiting: vec![],
             status: Status::new(),
             _cleanup: CleanUp
         }
     }
 
-    pub fn open(config: Config, file_names: Vec<String>) -> error::Result<Self> {
+    pub fn open(config: Config, files: Vec<FileArg>) -> error::Result<Self> {
         let mut screen = Self::new(config);
+
+        // Directories are opened in the file picker instead
+        let (dirs, files): (Vec<_>, Vec<_>) = files
+            .into_iter()
+            .partition(|file| Path::new(file.path()).is_dir());
         
-        if !file_names.is_empty() {
+        if !files.is_empty() {
+            let file_names = files
+                .iter()
+                .map(|file| file.path().to_owned())
+                .collect();
+
             screen.editor = Editor::open_from(&file_names, screen.config())?;
             screen.col_start = screen.calc_col_start();
+
+            // Moves to the positions given with the files (eg. `main.rs:12:5`)
+            for (view, file) in screen.editor.bufs_mut().iter_mut().zip(&files) {
+                if let Some(line) = file.line() {
+                    let pos = view.buf().pos_at_line_col(line, file.col().unwrap_or(1));
+                    view.set_cursor_pos(pos);
+                }
+            }
+            Pos(screen.cx, screen.cy) = screen.editor.view().saved_cursor_pos();
+            screen.update_config();
+        }
+
+        if let Some(dir) = dirs.first() {
+            screen.open_picker(dir.path());
         }
 
         Ok(screen)
     }
 
-    pub fn run(mut self) {
-        self.init().expect("An error occurred");
+    /// Runs the editor until it is quit, then shuts it down.
+    /// 
+    /// Returns the error that stopped the editor, if there was one.
+    pub fn run(mut self) -> error::Result<()> {
+        let res = self.init().and_then(|_| self.main_loop());
+        self.shut_down();
+
+        res
+    }
+
+    /// Draws the screen and processes events until the editor is quit.
+    fn main_loop(&mut self) -> error::Result<()> {
+        // The row the cursor was on, if the last key only moved the cursor
+        let mut moved_from = None;
 
-        let main = || loop {
-            self.refresh().expect("An error occured");
-            self.flush().expect("An error occurred");
+        loop {
+            self.poll_loaders();
+            match moved_from.take() {
+                Some(prev_cy) => self.refresh_cursor(prev_cy)?,
+                None => self.refresh()?
+            }
+            self.flush()?;
     
             let ke = loop {
-                match self.editor_mut().read_event().expect("Some error occurred") {
+                // Ticks more often while files are loading or being linted, so that what is done in the background is shown soon after
+                let tick_rate = if self.linting.is_some() || self.editor.bufs().iter().any(|view| view.buf().load_progress().is_some()) {
+                    Self::LOAD_BUDGET
+                } else {
+                    // Wakes up in time to redo the diff
+                    self.diff_due.map_or(self.config.tick_rate(), |due| cmp::min(
+                        self.config.tick_rate(),
+                        due.saturating_duration_since(Instant::now())
+                    ))
+                };
+
+                match self.editor_mut().read_event(tick_rate)? {
                     Some(Event::Key(ke)) => break ke,
                     Some(Event::Resize(cols, rows)) => {
                         // screen.set_size(cols as usize, rows as usize);
     
                         // let _ = screen.refresh(); // TODO: Put this stuff in function to handle all errors together
                     }
-                    _ => ()
+                    Some(Event::Paste(text)) => {
+                        self.paste_text(&text);
+                        self.schedule_diff();
+                        self.tick()?;
+                    }
+                    _ => self.tick()?
                 }
             };
-    
-            self = match self.process_key_event(&ke) {
-                Ok(val) => val,
-                err @ Err(_) => {
-                    drop(CleanUp);
-                    err.expect("An error occurred");
-                    std::process::exit(1);
+
+            let prev_cy = self.cy;
+            let prev_view = self.cursor_move_view(&ke);
+
+            if let ControlFlow::Break(()) = self.process_key_event(&ke)? {
+                return Ok(());
+            }
+
+            // The text didn't change, so neither did the diff
+            if prev_view.is_some() {
+                self.scroll();
+
+                if prev_view == Some(self.drawn_view()) {
+                    moved_from = Some(prev_cy);
                 }
-            };
-        };
+            } else {
+                self.schedule_diff();
+            }
+        }
+    }
+
+    /// Gets the parts of the screen's state that the rows are drawn from (besides the cursor), if `key` only moves the cursor and the rows can't change for another reason, so that they don't have to be redrawn if those parts stay the same.
+    fn cursor_move_view(&self, key: &KeyEvent) -> Option<(usize, usize, usize, usize)> {
+        let is_move = key.modifiers == KeyModifiers::NONE && matches!(
+            key.code,
+            KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right | 
+            KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End
+        );
+
+        // Moving out of select mode clears the selection, and loading files add rows
+        let is_plain = self.msg_log.is_none()
+            && self.picker.is_none()
+            && !self.panel.is_focused()
+            && !self.in_status_area
+            && self.prev_frame.is_some()
+            && !self.editor.view().is_in_select_mode()
+            && self.editor.bufs().iter().all(|view| view.buf().load_progress().is_none());
 
-        main()
+        (is_move && is_plain).then(|| self.drawn_view())
     }
 
-    /// Queues a command to the main buffer screen (ie. stdout; not the status area).
-    pub fn queue<C>(&mut self, command: C) -> error::Result<&mut io::Stdout> 
+    /// Gets the current buffer, scroll offsets and where the text starts (after the line numbers).
+    fn drawn_view(&self) -> (usize, usize, usize, usize) {
+        (self.editor.current_buf(), self.row_offset, self.col_offset, self.calc_col_start())
+    }
+
+    /// Tears down the editor before exiting, whether it was quit or stopped by an error.
+    /// 
+    /// Anything that has to happen before the program exits should be done here.
+    fn shut_down(self) {
+        log::debug("Shutting down");
+
+        // Clears the screen, and then restores the terminal when `CleanUp` is dropped
+        drop(self);
+    }
+
+    /// Queues a command to be drawn in the next frame.
+    pub fn queue<C>(&mut self, command: C) -> error::Result<&mut Vec<u8>> 
     where 
         C: crossterm::Command
     {
-        self.stdout.queue(command).map_err(Error::from)
+        self.out.queue(command).map_err(Error::from)
     }
 
-    /// Executes a command to the main buffer screen (ie. stdout; not the status area).
-    pub fn execute<C>(&mut self, command: C) -> error::Result<&mut io::Stdout> 
+    /// Queues a command to be drawn, then immediately flushes it.
+    pub fn execute<C>(&mut self, command: C) -> error::Result<()> 
     where 
         C: crossterm::Command
     {
-        self.stdout.execute(command).map_err(Error::from)
+        self.queue(command)?;
+        self.flush()
     }
 
-    /// Flushes all commands to be written to the main buffer screen (ie. stdout; not the status area).
+    /// Flushes everything queued since the last flush to stdout as a single frame. 
+    /// 
+    /// The frame is compared to the one currently on the terminal, so that only the cells that changed are written.
     pub fn flush(&mut self) -> error::Result<()> {
-        self.stdout.flush().map_err(error::Error::from)
+        if self.out.is_empty() {
+            return self.stdout.flush().map_err(error::Error::from);
+        }
+
+        let start = Instant::now();
+
+        // Include the status bar and status msg area
+        let output = String::from_utf8_lossy(&self.out);
+        let frame = match &self.prev_frame {
+            Some(prev) if self.is_partial => Frame::from_output_over(prev, &output),
+            _ => Frame::from_output(&output, self.screen_cols, self.screen_rows + 2)
+        };
+        self.out.clear();
+        self.is_partial = false;
+
+        let mut out = frame.diff(self.prev_frame.as_ref(), &mut self.palette);
+
+        // Name the terminal after the current file and project (OSC 2), only when it changes
+        let buf = self.editor.get_buf();
+        let file_name = buf.display_name();
+        let title = format!(
+            "{}{} — mino", 
+            Path::new(file_name).file_name().map_or(file_name.into(), |name| name.to_string_lossy()),
+            self.editor.project().map_or(String::new(), |project| format!(" ({})", project.name()))
+        );
+        if title != self.title {
+            let _ = write!(out, "\x1b]2;{}\x07", title.replace(|ch: char| ch.is_control(), ""));
+            self.title = title;
+        }
+
+        // Inverts the whole screen (DECSCNM) while an alert is shown
+        let should_invert = self.is_alerting() && self.config.alert() == Alert::Invert;
+        if should_invert != self.is_inverted {
+            out.push_str(if should_invert { "\x1b[?5h" } else { "\x1b[?5l" });
+            self.is_inverted = should_invert;
+        }
+
+        // Write the whole frame with a single write
+        self.stdout.lock().write_all(out.as_bytes())?;
+        self.stdout.flush()?;
+        self.prev_frame = Some(frame);
+
+        if let Some(profiler) = &mut self.profiler {
+            profiler.add(Stage::Flush, start.elapsed());
+            profiler.end_frame();
+        }
+
+        Ok(())
+    }
+
+    /// Does timed updates that happen without any input, such as clearing the status msg once it expires. 
+    /// 
+    /// Only the cells that changed are redrawn, so this is cheap when nothing did.
+    pub fn tick(&mut self) -> error::Result<()> {
+        self.reload_config();
+        self.poll_remote();
+        self.poll_loaders();
+        self.poll_linting();
+
+        if self.diff_due.is_some_and(|due| Instant::now() >= due) {
+            self.update_diff();
+        }
+
+        self.refresh()?;
+        self.flush()
+    }
+
+    /// Adds the rows that have been read in the background to the buffers whose files are still loading, telling the user if one couldn't be read all the way.
+    fn poll_loaders(&mut self) {
+        let mut failed = vec![];
+
+        for (_, view) in self.editor.unique_bufs() {
+            let mut buf = view.buf_mut();
+            let config = self.config_for(&buf);
+
+            if let Err(err) = buf.poll_loader(Self::LOAD_BUDGET, &config) {
+                failed.push(format!("Stopped loading '{}': {err}. It is now readonly, as saving it would cut off the rest of the file", buf.display_name()));
+            }
+        }
+
+        // A file that finished loading may have a modeline
+        self.update_config();
+
+        for msg in failed {
+            self.set_status_error(msg);
+            self.alert();
+        }
+    }
+
+    /// Gives the diagnostics from the linter running in the background to the buffer it was run on, once it finishes.
+    fn poll_linting(&mut self) {
+        let result = match &self.linting {
+            Some((linting, _)) => match linting.try_result() {
+                Some(result) => result,
+                None => return
+            },
+            None => return
+        };
+        let (_, id) = self.linting.take().unwrap();
+
+        // The buffer may have been closed while the linter ran
+        let Some(view) = self.diff_buf(id) else { return };
+        let name = view.buf().display_name().to_owned();
+
+        match result {
+            Ok(diagnostics) => {
+                let n = diagnostics.len();
+                view.buf_mut().set_diagnostics(diagnostics);
+
+                if n > 0 {
+                    self.set_status_msg(format!("{n} lint problem{} in '{name}'", if n == 1 { "" } else { "s" }));
+                }
+            }
+            Err(err) => log::warn(&format!("Lint on save failed: {err}"))
+        }
+    }
+
+    /// Creates the palette to draw with, for the terminal's color support and whether its background is used.
+    fn palette(config: &Config) -> Palette {
+        Palette::new(
+            config.color_support(), 
+            *config.theme().bg(),
+            config.transparent_bg()
+        )
+    }
+
+    /// Opens the files that other instances of the editor send to the `server`.
+    pub fn listen(&mut self, server: Server) {
+        self.remote = Some(server);
+    }
+
+    /// Handles the requests sent by other instances of the editor since the last poll, and lets the ones that are waiting stop once the files they opened are all closed.
+    fn poll_remote(&mut self) {
+        let batches = match self.remote.as_mut() {
+            Some(server) => server.poll(),
+            None => return
+        };
+
+        for batch in batches {
+            let mut ids = vec![];
+
+            for request in batch.requests() {
+                match request {
+                    Request::Open(file) => {
+                        log::debug(&format!("Opening '{}' for another instance", file.path()));
+                        if !self.open_file(file.path()) {
+                            continue;
+                        }
+
+                        if let Some(line) = file.line() {
+                            Pos(self.cx, self.cy) = self.editor.get_buf().pos_at_line_col(line, file.col().unwrap_or(1));
+                        }
+                        ids.push(self.editor.get_buf().id());
+                        self.set_status_msg(format!("Opened '{}'", file.path()));
+                    }
+                }
+            }
+
+            if batch.is_waiting() {
+                self.waiting.push((batch, ids));
+            }
+        }
+
+        // Dropping a batch tells its sender to stop waiting
+        let bufs = self.editor.bufs();
+        self.waiting.retain(|(_, ids)| ids
+            .iter()
+            .any(|&id| bufs.iter().any(|view| view.buf().id() == id))
+        );
+    }
+
+    /// Reloads the config from the config file whenever it changes, using the `watcher`.
+    pub fn watch_config(&mut self, watcher: ConfigWatcher) {
+        self.config_watcher = Some(watcher);
+    }
+
+    /// Applies the config file if it changed since the last check, rendering the buffers again and redrawing the whole screen with it. If the new config is invalid, the current one is kept.
+    fn reload_config(&mut self) {
+        let res = match self.config_watcher.as_mut().and_then(ConfigWatcher::check) {
+            Some(res) => res,
+            None => return
+        };
+
+        match res {
+            Ok(config) => {
+                self.palette = Self::palette(&config);
+                self.base_config = Rc::new(config);
+                self.config = self.config_for(&self.editor.get_buf());
+
+                for (_, view) in self.editor.unique_bufs() {
+                    let mut buf = view.buf_mut();
+                    let config = self.config_for(&buf);
+
+                    buf.rerender(&config);
+                }
+                self.col_start = self.calc_col_start();
+                self.prev_frame = None;
+
+                log::debug("Reloaded config file");
+                self.set_status_msg("Config file reloaded".to_owned());
+            }
+            Err(err) => {
+                log::warn(&format!("Config file not reloaded: {err}"));
+                self.set_status_error(format!("Config file not reloaded: {err}"));
+            }
+        }
     }
 
     pub fn init(&mut self) -> error::Result<()> {
@@ -177,20 +558,66 @@ impl Screen {
     pub fn refresh(&mut self) -> error::Result<()> {
         self.queue(Print("\x1b[0 q"))?;
 
+        // Large read-only files are loaded as they are scrolled through, a screen ahead of what is shown
+        let num_rows = self.row_offset + 2 * self.screen_rows;
+        self.editor.get_buf_mut().load_rows(num_rows, &self.config);
+
         self.scroll();
 
         self.queue(Hide)?;
         self.queue(MoveTo(0, 0))?;
 
+        let start = Instant::now();
         self.draw_rows()?;
+        if let Some(profiler) = &mut self.profiler {
+            profiler.add(Stage::DrawRows, start.elapsed());
+        }
+
+        self.draw_panel()?;
+        self.draw_msg_log()?;
+        self.draw_picker()?;
+        self.draw_status_bar()?;
+        self.draw_msg_bar()?;
+        self.place_cursor()
+    }
+
+    /// Redraws only what changes when the cursor moves from row `prev_cy` without the rows changing or scrolling: the line numbers of the two rows, the status bar and the msg bar. The rest of the screen is kept from the previous frame.
+    pub fn refresh_cursor(&mut self, prev_cy: usize) -> error::Result<()> {
+        self.is_partial = true;
+        self.queue(Hide)?;
+
+        if self.col_start > 0 && self.cy != prev_cy {
+            let num_rows = self.editor.get_buf().num_rows();
+            let screen_lines = self.screen_lines(self.text_rows());
+
+            for (y, (file_row, cols)) in screen_lines.into_iter().enumerate() {
+                let is_wrapped = cols.start > 0 && self.soft_wrap();
+
+                if (file_row == prev_cy || file_row == self.cy) && file_row < num_rows && !is_wrapped {
+                    let mut line = format!("\x1b[48;2;{}m", self.config.theme().bg());
+                    self.write_gutter(file_row, &mut line);
+                    line.push_str("\x1b[m");
+
+                    self.queue(MoveTo(0, y.as_u16()))?;
+                    self.queue(Print(line))?;
+                }
+            }
+        }
+
+        self.queue(MoveTo(0, self.screen_rows.as_u16()))?;
         self.draw_status_bar()?;
         self.draw_msg_bar()?;
+        self.place_cursor()
+    }
 
-        if !self.in_status_area {
-            self.queue(MoveTo(
-                (self.rx - self.col_offset + self.col_start).as_u16(), 
-                (self.cy - self.row_offset).as_u16()
-            ))?;
+    /// Moves the cursor to where it is in the buffer, prompt or panel, and shows it if it should be shown.
+    fn place_cursor(&mut self) -> error::Result<()> {
+        if self.panel.is_focused() {
+            let y = self.text_rows() + 1 + self.panel.selected() - self.panel.offset();
+            self.queue(MoveTo(0, y.as_u16()))?;
+        } else if !self.in_status_area {
+            let (x, y) = self.cursor_screen_pos();
+            self.queue(MoveTo(x.as_u16(), y.as_u16()))?;
 
             if let CursorStyle::BigBar = self.config.prompt_bar_cursor_style() {
                 self.queue(Print("\x1b[1 q"))?;
@@ -199,12 +626,14 @@ impl Screen {
             if let CursorStyle::BigBar = self.config.prompt_bar_cursor_style() {
                 self.queue(Print("\x1b[0 q"))?;
             }
-            self.execute(Show)?;
+            self.queue(Show)?;
             self.queue(MoveTo(self.status.msg().len().as_u16(), self.screen_rows.as_u16() + 1))?;
         }
 
-        if !self.config.hide_cursor_on_new_buf() || self.editor.get_buf().num_rows() > 0 {
-            self.execute(Show)?;
+        if self.msg_log.is_some() || self.picker.is_some() {
+            self.queue(Hide)?;
+        } else if !self.config.hide_cursor_on_new_buf() || self.editor.get_buf().num_rows() > 0 {
+            self.queue(Show)?;
         }
 
         Ok(())
@@ -218,45 +647,180 @@ impl Screen {
     pub fn scroll(&mut self) {
         self.rx = self.cx;
 
-        if self.cx < self.editor.get_buf().num_rows() {
-            self.rx = self.get_row().cx_to_rx(self.cx, &*self.config);
+        if self.cy < self.editor.get_buf().num_rows() {
+            let rx = self.get_row().cx_to_rx(self.cx);
+            self.rx = rx;
+        }
+
+        if self.soft_wrap() {
+            self.col_offset = 0;
+
+            if self.cy < self.row_offset {
+                self.row_offset = self.cy;
+            } else if self.cy >= self.row_offset + self.text_rows() {
+                self.row_offset = self.cy - self.text_rows() + 1;
+            }
+
+            // Rows before the cursor may take up more than 1 line
+            while self.row_offset < self.cy && self.cursor_screen_pos().1 >= self.text_rows() {
+                self.row_offset += 1;
+            }
+
+            return;
         }
 
         if self.cy < self.row_offset {
             self.row_offset = self.cy;
-        } else if self.cy >= self.row_offset + self.screen_rows {
-            self.row_offset = self.cy - self.screen_rows + 1;
+        } else if self.cy >= self.row_offset + self.text_rows() {
+            self.row_offset = self.cy - self.text_rows() + 1;
         }
 
+        let text_cols = self.text_cols().saturating_sub(self.col_start);
         if self.rx < self.col_offset {
             self.col_offset = self.rx;
-        } else if self.rx >= self.col_offset + self.screen_cols {
-            self.col_offset = self.rx - self.screen_cols + 1;
+        } else if self.rx >= self.col_offset + text_cols {
+            self.col_offset = self.rx - text_cols + 1;
         }
     }
 
-    pub fn draw_status_bar(&mut self) -> error::Result<()> {
-        self.queue(Print("\x1b[7m"))?; // Inverts colors
-
-        // File name & number of lines -- Left Aligned
+    /// Gets the row of the file and the range of terminal columns of that row that are shown on each line of the screen, starting at `self.row_offset`. 
+    /// 
+    /// Without soft wrap, each row takes up one line. Lines past the end of the file have an empty range.
+    pub fn screen_lines(&self, num_lines: usize) -> Vec<(usize, Range<usize>)> {
         let buf = self.editor.get_buf();
-        let name_str = format!("{:.30} - {} lines {}",  
-            if buf.file_name().is_empty() {
-                "[No Name]"
-            } else {
-                buf.file_name()
-            }, 
-            buf.num_rows(), 
-            if buf.is_dirty() {
-                "(modified)"
+        let width = self.text_cols().saturating_sub(self.col_start);
+        let mut lines = Vec::with_capacity(num_lines);
+        let mut file_row = self.row_offset;
+
+        while lines.len() < num_lines {
+            if file_row >= buf.num_rows() {
+                lines.push((file_row, 0..0));
+            } else if self.soft_wrap() {
+                let row = &buf.rows()[file_row];
+                let starts = row.wrap_starts(width);
+
+                for (i, &start) in starts.iter().enumerate() {
+                    let end = starts.get(i + 1).copied().unwrap_or(row.rwidth());
+                    lines.push((file_row, start..end));
+                }
             } else {
-                ""
+                lines.push((file_row, self.col_offset..self.col_offset + width));
             }
-        );
-        let name_len = name_str.len();
 
-        // Line number -- Right Aligned
-        let line_str = format!("{}/{} [{}]", self.cy + 1, buf.num_rows(), buf.syntax().name());
+            file_row += 1;
+        }
+
+        lines.truncate(num_lines);
+        lines
+    }
+
+    /// Gets where the cursor is on the screen, relative to the top left of the buffer's rows.
+    pub fn cursor_screen_pos(&self) -> (usize, usize) {
+        if !self.soft_wrap() || self.cy >= self.editor.get_buf().num_rows() {
+            return (self.rx - self.col_offset + self.col_start, self.cy.saturating_sub(self.row_offset));
+        }
+
+        let buf = self.editor.get_buf();
+        let width = self.text_cols().saturating_sub(self.col_start);
+
+        let mut y = buf.rows()[self.row_offset..self.cy]
+            .iter()
+            .map(|row| row.wrap_starts(width).len())
+            .sum::<usize>();
+
+        let starts = self.get_row().wrap_starts(width);
+        let line = starts.iter().rposition(|&start| start <= self.rx).unwrap_or(0);
+        y += line;
+
+        let mut x = self.rx - starts[line];
+        if x >= width {
+            // The cursor is past the end of a full line
+            x = 0;
+            y += 1;
+        }
+
+        (x + self.col_start, y)
+    }
+
+    /// The number of rows used for the current buffer, which is less than the screen's when the diagnostics panel is open.
+    pub fn text_rows(&self) -> usize {
+        self.screen_rows - self.panel.height(self.screen_rows)
+    }
+
+    /// Whether the Markdown preview is being drawn next to the current buffer.
+    pub fn is_previewing(&self) -> bool {
+        self.show_preview && *self.editor.get_buf().syntax().lang() == Language::Markdown
+    }
+
+    /// Whether the file that the current buffer is being compared with is being drawn next to it. The Markdown preview is drawn instead if it is on.
+    pub fn is_comparing(&self) -> bool {
+        self.show_diff_pane 
+            && !self.is_previewing() 
+            && self.diff_view
+                .as_ref()
+                .and_then(|d| d.other_id(self.editor.get_buf().id()))
+                .is_some_and(|id| self.diff_buf(id).is_some())
+    }
+
+    /// Gets a view of the buffer with the given id, if it is still open, to compare with.
+    fn diff_buf(&self, id: usize) -> Option<&View> {
+        self.editor
+            .bufs()
+            .iter()
+            .find(|view| view.buf().id() == id)
+    }
+
+    /// The number of columns used for the current buffer (including line numbers), which is less than the screen's when the preview or the file being compared with is shown. They are only shown if the screen is at least [`Screen::MIN_SPLIT_COLS`] wide.
+    pub fn text_cols(&self) -> usize {
+        if (self.is_previewing() || self.is_comparing()) && self.screen_cols >= Self::MIN_SPLIT_COLS {
+            self.screen_cols / 2
+        } else {
+            self.screen_cols
+        }
+    }
+
+    pub fn draw_status_bar(&mut self) -> error::Result<()> {
+        if self.is_alerting() && self.config.alert() == Alert::Flash {
+            let theme = self.config.theme();
+            let flash = format!("\x1b[48;2;{};38;2;{}m", theme.error(), theme.bg());
+            self.queue(Print(flash))?;
+        } else {
+            self.queue(Print("\x1b[7m"))?; // Inverts colors
+        }
+
+        self.update_indicators();
+
+        // File name & number of lines -- Left Aligned
+        let (name_str, line_str) = {
+            let buf = self.editor.get_buf();
+            let name_str = format!("{:.30}{} - {} lines {}",  
+                buf.display_name(), 
+                if buf.is_write_protected() {
+                    " [RO]"
+                } else {
+                    ""
+                },
+                if buf.is_fully_loaded() { buf.num_rows().to_string() } else { format!("{}+", buf.num_rows()) }, 
+                if let Some(progress) = buf.load_progress() {
+                    // The progress isn't shown to screen readers, which would announce every change to it
+                    if self.config.screen_reader() { "(loading)".to_owned() } else { format!("(loading {progress}%)") }
+                } else if buf.is_dirty() {
+                    "(modified)".to_owned()
+                } else {
+                    String::new()
+                }
+            );
+
+            // Indicators, encoding, line ending & line number -- Right Aligned
+            let mut line_str = String::new();
+            for indicator in self.status.indicators() {
+                line_str.push_str(indicator);
+                line_str.push_str("  ");
+            }
+            let _ = write!(line_str, "UTF-8 {}  {}/{} [{}]", buf.line_ending().name(), self.cy + 1, buf.num_rows(), buf.syntax().name());
+            (name_str, line_str)
+        };
+        let name_len = name_str.width();
         let line_len = line_str.len();
 
         // Tab number -- Centered
@@ -268,76 +832,346 @@ impl Screen {
             tab_len = 0;
         }
 
-        self.queue(Print(&name_str))?;
+        let mut bar = name_str;
 
         for i in name_len..self.screen_cols {
             if i == px {
-                self.queue(Print(tab_str.clone()))?;
+                bar.push_str(&tab_str);
             } else if i > px && i - px < tab_len {
                 continue;
             } else if self.screen_cols - i == line_len {
-                self.queue(Print(line_str))?;
+                bar.push_str(&line_str);
                 break;
             } else {
-                self.queue(Print(" "))?;
+                bar.push(' ');
             }
         }
 
-        self.queue(Print("\x1b[m\r\n"))?;
+        bar.push_str("\x1b[m\r\n");
+        self.queue(Print(bar))?;
 
         Ok(())
     }
 
+    /// Updates the status bar's indicators for the modes that the current buffer is in.
+    fn update_indicators(&mut self) {
+        let view = self.editor.view();
+        let is_selecting = view.is_in_select_mode() && view.select_anchor().is_some();
+        let is_readonly = view.mode() == Mode::View;
+
+        let select = is_selecting.then(|| {
+            let (chars, lines) = self.selection_size();
+            format!("SEL {chars} chars, {lines} lines")
+        });
+        self.status.set_indicator(Indicator::Select, select);
+        self.status.set_indicator(Indicator::Readonly, is_readonly.then(|| "READONLY".to_owned()));
+    }
+
     pub fn set_status_msg(&mut self, msg: String) {
-        self.status.set_msg(msg, self.screen_cols)
+        self.status.set_msg(msg, MsgLevel::Info, self.screen_cols)
     }
 
-    pub fn draw_msg_bar(&mut self) -> error::Result<()> {
-        self.queue(Clear(ClearType::CurrentLine))?;
+    /// Sets a status msg that warns the user, which is shown for longer than others.
+    pub fn set_status_warning(&mut self, msg: String) {
+        self.status.set_msg(msg, MsgLevel::Warning, self.screen_cols)
+    }
 
-        if self.status.msg().len() > 0 && self.status.timestamp().elapsed() < self.config.msg_bar_life() {
-            self.queue(Print(self.status.msg().to_owned()))?;
-        }
+    /// Sets a status msg saying that something went wrong, which is shown until it is dismissed with ESC or replaced by another message.
+    pub fn set_status_error(&mut self, msg: String) {
+        self.status.set_msg(msg, MsgLevel::Error, self.screen_cols)
+    }
 
-        Ok(())
+    /// Draws the message history (if it is open) over the buffer's rows, with the newest messages first.
+    pub fn draw_msg_log(&mut self) -> error::Result<()> {
+        let (offset, (_, num_shown)) = match (self.msg_log, self.overlay_size()) {
+            (Some(offset), Some(size)) => (offset, size),
+            _ => return Ok(())
+        };
+
+        let log = self.status.log();
+        let offset = cmp::min(offset, log.len().saturating_sub(num_shown));
+        self.msg_log = Some(offset);
+
+        let title = format!("Messages ({})", log.len());
+        let lines = log
+            .iter()
+            .rev()
+            .skip(offset)
+            .take(num_shown)
+            .map(|msg| format!("{:>7}  {}{}", msg.age(), msg.level().label(), util::strip_escapes(msg.msg())))
+            .collect::<Vec<_>>();
+
+        self.draw_overlay(&title, &lines, None, "Up/Down: Scroll  Esc: Close")
     }
 
-    pub fn prompt<F>(&mut self, prompt: &str, f: &F) -> error::Result<Option<String>> 
-    where 
-        F: Fn(&mut Self, String, KeyEvent)
-    {
-        let mut text = String::new();
-        
-        loop {
-            self.set_status_msg(prompt.to_owned() + &text);
-            self.in_status_area = true;
-            self.refresh()?;
-    
-            let e;
-    
-            match self.editor.read_event()? {
-                Some(Event::Key(ke)) => e = ke,
-                _ => continue
+    /// Draws the file picker (if it is open) over the buffer's rows.
+    pub fn draw_picker(&mut self) -> error::Result<()> {
+        let num_shown = match (&self.picker, self.overlay_size()) {
+            (Some(_), Some((_, num_shown))) => num_shown,
+            _ => return Ok(())
+        };
+        let picker = self.picker.as_ref().unwrap();
+
+        // Keeps the selected entry in view
+        let offset = (picker.selected() + 1).saturating_sub(num_shown);
+
+        let title = if picker.shows_ignored() {
+            format!("{} (showing ignored)", picker.dir().display())
+        } else {
+            picker.dir().display().to_string()
+        };
+        let lines = picker
+            .entries()
+            .iter()
+            .skip(offset)
+            .take(num_shown)
+            .map(|entry| if entry.is_dir() {
+                format!("{}/", entry.name())
+            } else {
+                entry.name().to_owned()
+            })
+            .collect::<Vec<_>>();
+        let selected = picker.selected() - offset;
+
+        self.draw_overlay(&title, &lines, Some(selected), "Enter: Open  Backspace: Up  .: Ignored  Esc: Close")
+    }
+
+    /// Opens the file picker at `dir`.
+    pub fn open_picker(&mut self, dir: &str) {
+        match FilePicker::open(Path::new(dir), self.config.ignore()) {
+            Ok(picker) => self.picker = Some(picker),
+            Err(err) => self.set_status_error(format!("Could not open '{dir}': {}", Error::from(err)))
+        }
+    }
+
+    /// Processes a key event while the file picker is open.
+    fn process_picker_key(&mut self, key: &KeyEvent) {
+        let page = self.text_rows().saturating_sub(4);
+        let picker = match self.picker.as_mut() {
+            Some(picker) => picker,
+            None => return
+        };
+
+        let res = match key.code {
+            KeyCode::Up => {
+                picker.select_prev(1);
+                return;
             }
-    
-            match e {
-                // Submit the text
-                KeyEvent { 
-                    code: KeyCode::Enter, 
-                    modifiers: KeyModifiers::NONE, 
-                    ..
-                } => {
-                    if text.len() != 0 {
-                        self.set_status_msg(String::new());
-                        f(self, text.clone(), e);
-    
-                        self.in_status_area = false;
-                        return Ok(Some(text));
-                    }
+            KeyCode::Down => {
+                picker.select_next(1);
+                return;
+            }
+            KeyCode::PageUp => {
+                picker.select_prev(page);
+                return;
+            }
+            KeyCode::PageDown => {
+                picker.select_next(page);
+                return;
+            }
+            KeyCode::Backspace | KeyCode::Left => picker.go_up(),
+            KeyCode::Char('.') => picker.toggle_ignored(),
+            KeyCode::Enter | KeyCode::Right => picker.choose().map(|path| {
+                if let Some(path) = path {
+                    self.picker = None;
+                    self.open_file(&path.to_string_lossy());
                 }
-    
-                // Escape w/out submitting
-                KeyEvent {
+            }),
+            KeyCode::Esc => {
+                self.picker = None;
+                return;
+            }
+            _ => return
+        };
+
+        if let Err(err) = res {
+            self.set_status_error(format!("Could not open directory: {}", Error::from(err)));
+        }
+    }
+
+    /// Gets the width of an overlay (eg. the message history) and the number of lines that fit inside it, or `None` if the screen is too small for one.
+    fn overlay_size(&self) -> Option<(usize, usize)> {
+        let width = cmp::min(self.screen_cols.saturating_sub(4), 100);
+        let height = self.text_rows().saturating_sub(2);
+
+        if width < 20 || height < 3 {
+            None
+        } else {
+            Some((width, height - 2))
+        }
+    }
+
+    /// Draws a box over the buffer's rows, with `title` and `hint` in its borders and `lines` inside it. The line at index `selected` is highlighted.
+    fn draw_overlay(&mut self, title: &str, lines: &[String], selected: Option<usize>, hint: &str) -> error::Result<()> {
+        let (width, num_shown) = match self.overlay_size() {
+            Some(size) => size,
+            None => return Ok(())
+        };
+
+        // Pads (or cuts off) a line so it fills the inside of the box
+        let fit = |s: &str| {
+            let mut s = format!(" {s}");
+            util::truncate_to_width(&mut s, width - 2);
+            let pad = width - 2 - s.width();
+            s + &" ".repeat(pad)
+        };
+
+        let theme = self.config.theme();
+        let style = format!("\x1b[48;2;{}m\x1b[38;2;{}m", theme.bg(), theme.fg());
+        let select_style = format!("\x1b[48;2;{}m", theme.select());
+
+        let mut out = Vec::with_capacity(num_shown + 2);
+
+        let mut title = format!(" {title} ");
+        util::truncate_to_width(&mut title, width - 2);
+        out.push(format!("{style}┌{title}{}┐", "─".repeat(width - 2 - title.width())));
+
+        for i in 0..num_shown {
+            let line = fit(lines.get(i).map_or("", String::as_str));
+
+            if selected == Some(i) {
+                out.push(format!("{style}│{select_style}{line}{style}│"));
+            } else {
+                out.push(format!("{style}│{line}│"));
+            }
+        }
+
+        let mut hint = format!(" {hint} ");
+        util::truncate_to_width(&mut hint, width - 2);
+        out.push(format!("{style}└{}{hint}┘", "─".repeat(width - 2 - hint.width())));
+
+        let x = (self.screen_cols - width) / 2;
+        for (y, line) in out.into_iter().enumerate() {
+            self.queue(MoveTo(x.as_u16(), (y + 1).as_u16()))?;
+            self.queue(Print(format!("{line}\x1b[m")))?;
+        }
+
+        Ok(())
+    }
+
+    /// Processes a key event while the message history is open.
+    fn process_msg_log_key(&mut self, key: &KeyEvent) {
+        let offset = self.msg_log.unwrap_or(0);
+        let page = self.text_rows().saturating_sub(4);
+
+        match key.code {
+            KeyCode::Up => self.msg_log = Some(offset.saturating_sub(1)),
+            KeyCode::Down => self.msg_log = Some(offset + 1),
+            KeyCode::PageUp => self.msg_log = Some(offset.saturating_sub(page)),
+            KeyCode::PageDown => self.msg_log = Some(offset + page),
+            KeyCode::Esc | KeyCode::Char('h') => self.msg_log = None,
+            _ => ()
+        }
+    }
+
+    pub fn draw_msg_bar(&mut self) -> error::Result<()> {
+        self.queue(Clear(ClearType::CurrentLine))?;
+
+        let mut msg = String::new();
+        // For screen readers, the msg stays until the next one, so that it isn't announced again as it disappears
+        let is_shown = self.config.screen_reader() || self.status.is_msg_shown(self.config.msg_bar_life());
+        if self.status.msg().len() > 0 && is_shown {
+            let color = match self.status.level() {
+                MsgLevel::Info => None,
+                MsgLevel::Warning => Some(self.config.theme().warning()),
+                MsgLevel::Error => Some(self.config.theme().error())
+            };
+
+            match color {
+                Some(color) => {
+                    let _ = write!(msg, "\x1b[38;2;{color}m{}{}", self.status.msg(), Style::FG_RESET);
+                }
+                None => msg.push_str(self.status.msg())
+            }
+        }
+
+        // Show the prompt note, or else the profiling results, on the right, if they fit
+        let note = match (&self.prompt_note, &self.profiler) {
+            (Some(note), _) if self.in_status_area => Some(note.clone()),
+            (_, Some(profiler)) => Some(profiler.summary()),
+            _ => None
+        };
+
+        if let Some(summary) = note {
+            let msg_len = util::strip_escapes(&msg).width();
+
+            if msg_len + summary.len() + 1 <= self.screen_cols {
+                msg = format!("{msg}{:>width$}", summary, width=self.screen_cols - msg_len);
+            }
+        }
+
+        if !msg.is_empty() {
+            self.queue(Print(msg))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn prompt<F>(&mut self, prompt: &str, f: &F) -> error::Result<Option<String>> 
+    where 
+        F: Fn(&mut Self, String, KeyEvent)
+    {
+        self.read_prompt(prompt, f, PromptKind::Text)
+    }
+
+    /// Prompts for a file path, where Tab (or Shift+Tab, backwards) completes the path, cycling through the candidates when there are several.
+    /// 
+    /// The path is entered relative to the directory of the current buffer's file, and is returned resolved against it (see [`TextBuffer::resolve_path`]).
+    pub fn prompt_path(&mut self, prompt: &str) -> error::Result<Option<String>> {
+        let path = self.read_prompt(prompt, &|_, _, _| { }, PromptKind::Path)?;
+
+        Ok(path.map(|path| self.editor.get_buf().resolve_path(&path)))
+    }
+
+    /// Prompts for text that can be left empty, such as a replacement.
+    pub fn prompt_maybe_empty(&mut self, prompt: &str) -> error::Result<Option<String>> {
+        self.read_prompt(prompt, &|_, _, _| { }, PromptKind::MaybeEmpty)
+    }
+
+    fn read_prompt<F>(&mut self, prompt: &str, f: &F, kind: PromptKind) -> error::Result<Option<String>> 
+    where 
+        F: Fn(&mut Self, String, KeyEvent)
+    {
+        let mut text = String::new();
+        // The candidates being cycled through, and the index of the one shown
+        let mut completions: Option<(Vec<String>, usize)> = None;
+        
+        loop {
+            self.status.set_prompt_msg(prompt.to_owned() + &text, self.screen_cols);
+            self.in_status_area = true;
+            self.refresh()?;
+            self.flush()?;
+    
+            let e;
+    
+            match self.editor.read_event(self.config.tick_rate())? {
+                Some(Event::Key(ke)) => e = ke,
+                Some(Event::Paste(pasted)) => {
+                    text.extend(pasted.chars().filter(|ch| !ch.is_control()));
+                    completions = None;
+                    continue;
+                }
+                _ => continue
+            }
+    
+            match e {
+                // Submit the text
+                KeyEvent { 
+                    code: KeyCode::Enter, 
+                    modifiers: KeyModifiers::NONE, 
+                    ..
+                } => {
+                    if !text.is_empty() || kind == PromptKind::MaybeEmpty {
+                        self.set_status_msg(String::new());
+                        f(self, text.clone(), e);
+    
+                        self.in_status_area = false;
+                        return Ok(Some(text));
+                    }
+                }
+    
+                // Escape w/out submitting
+                KeyEvent {
                     code: KeyCode::Esc,
                     modifiers: KeyModifiers::NONE,
                     ..
@@ -355,9 +1189,42 @@ impl Screen {
                     modifiers: KeyModifiers::NONE,
                     ..
                 } => {
-                    if !text.is_empty() {
-                        text = text[..(text.len()-1)].to_owned();
+                    // Remove the whole last grapheme, so that multi-byte chars (and accents) aren't split
+                    if let Some((i, _)) = text.grapheme_indices(true).next_back() {
+                        text.truncate(i);
+                    }
+                }
+
+                // Complete Path (Tab/Shift+Tab)
+                KeyEvent {
+                    code: code @ (KeyCode::Tab | KeyCode::BackTab),
+                    ..
+                } if kind == PromptKind::Path => {
+                    let is_back = code == KeyCode::BackTab;
+
+                    if let Some((candidates, idx)) = &mut completions {
+                        *idx = if is_back {
+                            (*idx + candidates.len() - 1) % candidates.len()
+                        } else {
+                            (*idx + 1) % candidates.len()
+                        };
+                        text = candidates[*idx].clone();
+                    } else {
+                        let candidates = picker::complete_path(&text, self.editor.get_buf().dir());
+                        let idx = if is_back { candidates.len().saturating_sub(1) } else { 0 };
+
+                        if let Some(candidate) = candidates.get(idx) {
+                            text = candidate.clone();
+                        }
+
+                        // With only one candidate, the next Tab completes inside it instead (eg. when it's a directory)
+                        if candidates.len() > 1 {
+                            completions = Some((candidates, idx));
+                        }
                     }
+
+                    f(self, text.clone(), e);
+                    continue;
                 }
     
                 // Regular Character
@@ -372,7 +1239,8 @@ impl Screen {
                 // Anything else
                 _ => ()
             }
-    
+
+            completions = None;
             f(self, text.clone(), e);
         }
     }
@@ -383,14 +1251,20 @@ impl Screen {
         let saved_coloff = self.col_offset;
         let saved_rowoff = self.row_offset;
 
-        if self.editor.get_buf().is_in_select_mode() {
+        if self.editor.view().is_in_select_mode() {
             self.exit_select_mode();
         }
+
+        // Every row needs to be searched
+        self.editor.get_buf_mut().load_all_rows(&self.config);
         
-        if let None = self.prompt( 
+        let res = self.prompt( 
             "Search (Use ESC/Arrows/Enter): ", 
             &|a, b, c| Self::incremental_search(a, b, c)
-        )? {
+        );
+        self.prompt_note = None;
+
+        if let None = res? {
             self.cx = saved_cx;
             self.cy = saved_cy;
             self.col_offset = saved_coloff;
@@ -403,11 +1277,8 @@ impl Screen {
     fn incremental_search(&mut self, query: String, ke: KeyEvent) {
         let editor = &mut self.editor;
 
-        // Rehighlight when going to a different selection or ending search
-        if let LastMatch::RowIndex(l) = editor.last_match() {
-            let syntax = editor.get_buf().syntax();
-            editor.get_buf_mut().rows_mut()[l].update_highlight(syntax);
-        }
+        // Clear the match highlight when going to a different selection or ending search
+        editor.view_mut().set_search_match(None);
 
         match ke {
             KeyEvent { 
@@ -449,17 +1320,22 @@ impl Screen {
 
         // This may be a bit not good, so perhaps later clean it up. But it works! I think
 
-        for _ in editor.get_buf().rows() {
+        let mut is_wrapped = false;
+        self.prompt_note = None;
+
+        let num_rows = editor.get_buf().num_rows();
+        for _ in 0..num_rows {
             current_line += if editor.is_search_forward() { 1 } else { -1 };
 
             if current_line == -1 {
-                current_line = (editor.get_buf().num_rows() - 1) as isize;
-            } else if current_line == editor.get_buf().num_rows() as isize {
+                current_line = (num_rows - 1) as isize;
+                is_wrapped = true;
+            } else if current_line == num_rows as isize {
                 current_line = 0;
+                is_wrapped = true;
             }
     
-            let row = &editor.get_buf().rows()[current_line.abs() as usize];
-            let found_at = row.render().find(&query);
+            let found_at = editor.get_buf().rows()[current_line.abs() as usize].render().find(&query);
 
             if let Some(idx) = found_at {
                 (*editor.last_match_mut()) = if current_line == -1 {
@@ -468,12 +1344,23 @@ impl Screen {
                     LastMatch::RowIndex(current_line as usize)
                 };
                 self.cy = current_line.abs() as usize;
-                self.cx = editor.get_buf().rows()[current_line.abs() as usize].rx_to_cx(idx, &*self.config);
-                self.row_offset = editor.get_buf().num_rows();    // For scrolling behavior
-
-                let row = &mut editor.get_buf_mut().rows_mut()[current_line.abs() as usize];
-                for i in 0..query.len() {
-                    row.hl_mut()[self.cx + i].set_select_hl(SelectHighlight::Search);
+                // `idx` is a byte index into the render, so get the column it's at to find the char it's on
+                let rx = editor.get_buf().rows()[current_line.abs() as usize].render()[..idx].width();
+                self.cx = editor.get_buf().rows()[current_line.abs() as usize].rx_to_cx(rx);
+                self.row_offset = num_rows;    // For scrolling behavior
+
+                let end_rx = editor.get_buf().rows()[current_line.abs() as usize].render()[..idx + query.len()].width();
+                let end_cx = editor.get_buf().rows()[current_line.abs() as usize].rx_to_cx(end_rx);
+                editor.view_mut().set_search_match(Some((Pos(self.cx, self.cy), Pos(end_cx, self.cy))));
+
+                if is_wrapped {
+                    let note = if editor.is_search_forward() {
+                        "Search wrapped to the top"
+                    } else {
+                        "Search wrapped to the bottom"
+                    };
+                    self.prompt_note = Some(note.to_owned());
+                    self.alert();
                 }
 
                 break;
@@ -486,168 +1373,89 @@ impl Screen {
 
         self.col_start = self.calc_col_start();
 
-        let buf = self.editor.get_buf();
-        let num_rows = buf.num_rows();
-        let y_max = self.screen_rows;
-
-        // For welcome screen
-        // welcome str is 16+MINO_VER.len()
-        let mut welcome = format!("Mino -- version {MINO_VER}");
-        let ver_len = MINO_VER.len();
-        let mut welcome_len = welcome.len();
-        if welcome_len > self.screen_cols {
-            welcome_len = self.screen_cols;
-        }
-        let mut px = (self.screen_cols - welcome_len) / 2;
-
-        for y in 0..y_max {
-            let file_row = y + self.row_offset;
+        let y_max = self.text_rows();
+        let row_offset = self.row_offset;
+        let screen_lines = self.screen_lines(y_max);
 
-            self.queue(Print(format!("\x1b[48;2;{}m", self.config.theme().bg())))?;
-            self.queue(Print(format!("\x1b[{} q", *self.config.theme().cursor() as usize)))?;
-
-            if file_row >= num_rows {
-                let str = if num_rows == 0 && y == self.screen_rows / 3 {
-                    // Display welcome screen
-                    if px != 0 {
-                        self.queue(Print(format!(
-                            "\x1b[38;2;{}m~{}", 
-                            self.config.theme().dimmed(),
-                            Style::FG_RESET
-                        )))?;
-                        px -= 1;
-                    }
-
-                    for _ in 0..px {
-                        self.queue(Print(" "))?;
-                    }
-
-                    welcome.truncate(welcome_len);
-                    format!("{}{welcome}{}\r\n", self.config.theme().title(), Style::RESET)
-                } else if num_rows == 0 && y == self.screen_rows / 3 + 2 && self.screen_rows >= 16 {
-                    // Display New help
-                    px += 1;
-                    if px != 0 {
-                        self.queue(Print(format!("\x1b[38;2;{}m~", self.config.theme().dimmed())))?;
-                        px -= 1;
-                    }
-
-                    for _ in 0..px {
-                        self.queue(Print(" "))?;
-                    }
-
-                    let mut msg = format!("New{:>width$}", "Ctrl N", width=16+ver_len-3);
-                    let msg_len = msg.len();
-
-                    msg.truncate(msg_len);
-                    format!("{msg}\x1b[39m\r\n")
-                } else if num_rows == 0 && y == self.screen_rows / 3 + 3 && self.screen_rows >= 16 {
-                    // Display Open help
-                    px += 1;
-                    if px != 0 {
-                        self.queue(Print(format!("\x1b[38;2;{}m~", self.config.theme().dimmed())))?;
-                        px -= 1;
-                    }
-
-                    for _ in 0..px {
-                        self.queue(Print(" "))?;
-                    }
-
-                    let mut msg = format!("Open{:>width$}", "Ctrl O", width=16+ver_len-4);
-                    let msg_len = msg.len();
-
-                    msg.truncate(msg_len);
-                    format!("{msg}\x1b[39m\r\n")
-                } else if num_rows == 0 && y == self.screen_rows / 3 + 4 && self.screen_rows >= 16 {
-                    // Display Find help
-                    px += 1;
-                    if px != 0 {
-                        self.queue(Print(format!("\x1b[38;2;{}m~", self.config.theme().dimmed())))?;
-                        px -= 1;
-                    }
+        // Long rows are only highlighted as far as they are shown
+        let cols_end = screen_lines
+            .iter()
+            .map(|(_, cols)| cols.end)
+            .max()
+            .unwrap_or(0);
+        self.editor.get_buf_mut().highlight_rows(row_offset..row_offset + y_max, cols_end, self.config.rainbow_brackets());
 
-                    for _ in 0..px {
-                        self.queue(Print(" "))?;
-                    }
+        let num_rows = self.editor.get_buf().num_rows();
 
-                    let mut msg = format!("Find Text{:>width$}", "Ctrl F", width=16+ver_len-9);
-                    let msg_len = msg.len();
-
-                    msg.truncate(msg_len);
-                    format!("{msg}\x1b[39m\r\n")
-                } else if num_rows == 0 && y == self.screen_rows / 3 + 5 && self.screen_rows >= 16 {
-                    // Display Close help
-                    px += 1;
-                    if px != 0 {
-                        self.queue(Print(format!("\x1b[38;2;{}m~", self.config.theme().dimmed())))?;
-                        px -= 1;
-                    }
+        // For Markdown preview
+        let config = Rc::clone(&self.config);
+        let text_cols = self.text_cols();
+        let preview_cols = self.screen_cols - text_cols;
+        let is_previewing = self.is_previewing() && preview_cols > 1;
+        let preview = if is_previewing {
+            let end = cmp::min(num_rows, self.row_offset + y_max);
+
+            markdown::render(
+                self.editor.get_buf().rows()[..end].iter().map(Row::chars), 
+                preview_cols - 1, 
+                self.config.theme()
+            )
+        } else {
+            vec![]
+        };
+        let preview_at = |file_row: usize| -> String {
+            if !is_previewing {
+                return String::new();
+            }
 
-                    for _ in 0..px {
-                        self.queue(Print(" "))?;
-                    }
+            let line = preview.get(file_row).cloned().unwrap_or_default();
+            format!(
+                "\x1b[38;2;{}m│{}",
+                config.theme().superdim(),
+                markdown::fit_line(&line, preview_cols - 1, config.theme())
+            )
+        };
 
-                    let mut msg = format!("Close Tab{:>width$}", "Ctrl W", width=16+ver_len-9);
-                    let msg_len = msg.len();
-
-                    msg.truncate(msg_len);
-                    format!("{msg}\x1b[39m\r\n")
-                } else if num_rows == 0 && y == self.screen_rows / 3 + 6 && self.screen_rows >= 16 {
-                    // Display Save help
-                    px += 1;
-                    if px != 0 {
-                        self.queue(Print(format!("\x1b[38;2;{}m~", self.config.theme().dimmed())))?;
-                        px -= 1;
-                    }
+        // For comparing side by side
+        let is_comparing = self.is_comparing() && preview_cols > 2;
+        let pane = if is_comparing {
+            self.diff_pane_lines(&screen_lines, preview_cols)
+        } else {
+            vec![]
+        };
+        let pane_at = |y: usize| pane.get(y).map_or("", String::as_str);
 
-                    for _ in 0..px {
-                        self.queue(Print(" "))?;
-                    }
+        // For welcome screen
+        let welcome = if num_rows == 0 {
+            self.welcome_lines()
+        } else {
+            vec![]
+        };
+        let welcome_start = self.screen_rows / 3;
 
-                    let mut msg = format!("Save{:>width$}", "Ctrl S", width=16+ver_len-4);
-                    let msg_len = msg.len();
-
-                    msg.truncate(msg_len);
-                    format!("{msg}\x1b[39m\r\n")
-                } else if num_rows == 0 && y == self.screen_rows / 3 + 7 && self.screen_rows >= 16 {
-                    // Display Quit help
-                    px += 1;
-                    if px != 0 {
-                        self.queue(Print(format!("\x1b[38;2;{}m~", self.config.theme().dimmed())))?;
-                        px -= 1;
-                    }
+        self.queue(Print(format!("\x1b[{} q", *self.config.theme().cursor() as usize)))?;
 
-                    for _ in 0..px {
-                        self.queue(Print(" "))?;
-                    }
+        // Reused for each row, to avoid allocating
+        let mut line = String::new();
 
-                    let mut msg = format!("Quit{:>width$}", "Ctrl Q", width=16+ver_len-4);
-                    let msg_len: usize = msg.len();
-
-                    msg.truncate(msg_len);
-                    format!("{msg}\x1b[39m\r\n")
-                } else if num_rows == 0 && y == self.screen_rows / 3 + 8 && self.screen_rows >= 16 {
-                    // Display Keybind help
-                    px += 1;
-                    if px != 0 {
-                        self.queue(Print(format!("\x1b[38;2;{}m~", self.config.theme().dimmed())))?;
-                        px -= 1;
-                    }
+        for (y, (file_row, cols)) in screen_lines.into_iter().enumerate() {
+            // Whether this line continues a row wrapped from the line before
+            let is_wrapped = cols.start > 0 && self.soft_wrap();
 
-                    for _ in 0..px {
-                        self.queue(Print(" "))?;
-                    }
+            self.queue(Print(format!("\x1b[48;2;{}m", self.config.theme().bg())))?;
 
-                    let mut msg = format!("Keybinds{:>width$}", "Ctrl ?", width=16+ver_len-8);
-                    let msg_len = msg.len();
+            if file_row >= num_rows {
+                let welcome_line = y
+                    .checked_sub(welcome_start)
+                    .and_then(|i| welcome.get(i));
 
-                    msg.truncate(msg_len);
-                    format!("{msg}\x1b[39m\r\n")
+                let str = if let Some(welcome_line) = welcome_line {
+                    format!("\x1b[38;2;{}m~{}{welcome_line}{}\r\n", self.config.theme().dimmed(), Style::FG_RESET, Style::RESET)
                 } else {
-                    let mut s = format!("\x1b[38;2;{}m~", self.config.theme().dimmed());
-                    for _ in 0..self.screen_cols-1 {
-                        s.push(' ');
-                    }
+                    let filler = if self.config.screen_reader() { ' ' } else { '~' };
+                    let mut s = format!("\x1b[38;2;{}m{filler}{}", self.config.theme().dimmed(), " ".repeat(text_cols - 1));
+                    s.push_str(&preview_at(file_row));
+                    s.push_str(pane_at(y));
                     s.push_str("\x1b[39m\r\n");
 
                     s
@@ -656,40 +1464,97 @@ impl Screen {
                 self.queue(Print(str))?;
             } else {
                 // self.queue(Show)?;
-                self.queue(Print(format!("{}{:width$}\x1b[38;2;{}m ", if file_row == self.cy {
-                    format!("\x1b[38;2;{}m", self.config.theme().current_line())
+                line.clear();
+                if self.col_start == 0 {
+                    // Line numbers are hidden
+                } else if is_wrapped {
+                    let separator = self.config.line_number_separator();
+                    let _ = write!(
+                        line,
+                        "{}\x1b[38;2;{}m{separator}",
+                        " ".repeat(self.col_start - separator.width()),
+                        self.config.theme().dimmed()
+                    );
                 } else {
-                    format!("\x1b[38;2;{}m", self.config.theme().dimmed())
-                }, 1 + file_row, self.config.theme().fg(), width=self.col_start - 1)))?;
+                    self.write_gutter(file_row, &mut line);
+                }
+                let _ = write!(line, "\x1b[38;2;{}m", self.config.theme().fg());
 
-                let buf = self.editor.get_buf();
-                let row_size = buf.rows()[file_row].rsize();
+                let row_size = self.editor.get_buf().rows()[file_row].rwidth();
+
+                let len = cmp::min(cols.end, row_size).saturating_sub(cols.start);
+
+                // Rulers are only drawn on the first line of wrapped rows, as the columns after it aren't below them
+                let rulers: Vec<usize> = if is_wrapped || !self.editor.view().options().rulers() {
+                    vec![]
+                } else {
+                    let lang_rulers = self.editor.get_buf().syntax().lang().rulers();
+                    let rulers = if lang_rulers.is_empty() { self.config.rulers() } else { lang_rulers };
+
+                    rulers.iter().map(|col| col - 1).collect()
+                };
 
-                let len = if row_size <= self.col_offset {
-                    0
-                } else if row_size - self.col_offset > self.screen_cols - self.col_start {
-                    self.screen_cols - self.col_start
+                // A guide at each tab stop in the row's indentation
+                let guides: Vec<usize> = if self.config.indent_guides() {
+                    (0..self.editor.get_buf().rows()[file_row].indent_width())
+                        .step_by(self.config.tab_stop())
+                        .collect()
                 } else {
-                    row_size - self.col_offset
+                    vec![]
                 };
 
-                let mut msg = buf
+                let start = Instant::now();
+                let decorations = Decorations {
+                    overlays: &self.editor.view().overlays_at(file_row, pos!(self)),
+                    rulers: &rulers,
+                    guides: &guides,
+                    show_trailing_ws: self.config.highlight_trailing_whitespace()
+                };
+                self.editor
+                    .get_buf()
                     .rows()[file_row]
-                    .hlchars_at(
-                        self.col_offset
-                        ..self.col_offset + len,
-                        self.config.theme()
+                    .write_hlchars_at(
+                        cols.start
+                        ..cols.start + len,
+                        &decorations,
+                        self.config.theme(),
+                        &mut line
                     );
-                
-                if y == 0 {
-                    let msg_len = buf.rows()[file_row].rchars_at(self.col_offset..self.col_offset+len).len();
+                if let Some(profiler) = &mut self.profiler {
+                    profiler.add(Stage::Highlight, start.elapsed());
+                }
 
-                    for _ in msg_len..self.screen_cols - self.col_start {
-                        msg.push(' ');
+                // The rulers past the end of the row
+                let mut width = len;
+                if cols.end >= row_size {
+                    let theme = self.config.theme();
+                    let mut xs: Vec<usize> = rulers
+                        .iter()
+                        .filter(|&&rx| rx >= cols.start + len && rx - cols.start < text_cols - self.col_start)
+                        .map(|rx| rx - cols.start)
+                        .collect();
+                    xs.sort_unstable();
+                    xs.dedup();
+
+                    for x in xs {
+                        let _ = write!(line, "{}\x1b[48;2;{}m \x1b[48;2;{}m", " ".repeat(x - width), theme.superdim(), theme.bg());
+                        width = x + 1;
                     }
                 }
+                
+                if y == 0 || is_previewing || is_comparing {
+                    line.push_str(&" ".repeat(text_cols - self.col_start - width));
+                }
 
-                self.queue(Print(format!("{msg}\x1b[22;23;24;29m\r\n")))?;
+                if is_wrapped {
+                    line.push_str(&preview_at(usize::MAX));
+                } else {
+                    line.push_str(&preview_at(file_row));
+                }
+                line.push_str(pane_at(y));
+                line.push_str("\x1b[22;23;24;29m\r\n");
+
+                self.queue(Print(&line))?;
             }
             self.queue(Clear(ClearType::UntilNewLine))?;
         }
@@ -699,15 +1564,226 @@ impl Screen {
         Ok(())
     }
 
+    /// Gets the lines of the pane that shows the file being compared with next to the current buffer, `width` columns wide, for each of the `screen_lines`. Each row of the current buffer is shown next to the row of the other file that lines up with it, with a marker for how that row changed.
+    fn diff_pane_lines(&self, screen_lines: &[(usize, Range<usize>)], width: usize) -> Vec<String> {
+        let id = self.editor.get_buf().id();
+        let diff_view = match &self.diff_view {
+            Some(diff_view) => diff_view,
+            None => return vec![]
+        };
+        let other_id = match diff_view.other_id(id) {
+            Some(other_id) => other_id,
+            None => return vec![]
+        };
+        let other = match self.diff_buf(other_id) {
+            Some(view) => view,
+            None => return vec![]
+        };
+
+        // The separator and marker take a column each
+        let text_width = width - 2;
+        let theme = self.config.theme();
+
+        // Lines that continue a wrapped row are left empty
+        let other_rows: Vec<Option<usize>> = screen_lines
+            .iter()
+            .map(|(file_row, cols)| if cols.start > 0 && self.soft_wrap() {
+                None
+            } else {
+                diff_view.counterpart(id, *file_row)
+            })
+            .collect();
+
+        if let (Some(start), Some(end)) = (other_rows.iter().flatten().min(), other_rows.iter().flatten().max()) {
+            let cols_end = self.col_offset + text_width;
+            other.buf_mut().highlight_rows(*start..end + 1, cols_end, self.config.rainbow_brackets());
+        }
+
+        let other = other.buf();
+
+        screen_lines
+            .iter()
+            .zip(other_rows)
+            .map(|((file_row, cols), other_row)| {
+                let mut s = format!("{}{}\x1b[38;2;{}m│", FontStyle::RESET, Style::default(theme), theme.superdim());
+                let row = other_row.and_then(|r| other.rows().get(r));
+                let is_wrapped = cols.start > 0 && self.soft_wrap();
+
+                let marker = if is_wrapped {
+                    " ".to_owned()
+                } else if diff_view.skipped_before(id, *file_row) > 0 {
+                    format!("\x1b[38;2;{}m⋯", theme.dimmed())
+                } else {
+                    match other_row.and_then(|r| diff_view.change_at(other_id, r)) {
+                        Some(LineChange::Added) => format!("\x1b[38;2;{}m+", theme.added()),
+                        Some(LineChange::Removed) => format!("\x1b[38;2;{}m-", theme.error()),
+                        Some(LineChange::Changed) => format!("\x1b[38;2;{}m~", theme.warning()),
+                        None => " ".to_owned()
+                    }
+                };
+                let _ = write!(s, "{marker}{}", Style::default(theme));
+
+                let mut len = 0;
+                if let Some(row) = row {
+                    len = cmp::min(cols.start + text_width, row.rwidth()).saturating_sub(cols.start);
+                    row.write_hlchars_at(cols.start..cols.start + len, &Decorations::default(), theme, &mut s);
+                }
+
+                let _ = write!(s, "{}{}{:pad$}", FontStyle::RESET, Style::default(theme), "", pad = text_width - len);
+                s
+            })
+            .collect()
+    }
+
+    /// Writes the line number of `file_row` to `line`, followed by a marker for its diagnostic or how it changed in the diff, if it has one.
+    fn write_gutter(&self, file_row: usize, line: &mut String) {
+        let theme = self.config.theme();
+        let buf = self.editor.get_buf();
+        let change = self.diff_view
+            .as_ref()
+            .and_then(|d| d.change_at(buf.id(), file_row));
+        let change_color = match change {
+            Some(LineChange::Added) => Some(theme.added()),
+            Some(LineChange::Removed) => Some(theme.error()),
+            Some(LineChange::Changed) => Some(theme.warning()),
+            None => None
+        };
+
+        let marker = match (buf.diagnostic_at(file_row).map(lint::Diagnostic::severity), change) {
+            (Some(Severity::Error), _) => format!("\x1b[38;2;{}m●", theme.error()),
+            (Some(Severity::Warning), _) => format!("\x1b[38;2;{}m●", theme.warning()),
+            (None, Some(LineChange::Added)) => format!("\x1b[38;2;{}m+", theme.added()),
+            (None, Some(LineChange::Removed)) => format!("\x1b[38;2;{}m-", theme.error()),
+            (None, Some(LineChange::Changed)) => format!("\x1b[38;2;{}m~", theme.warning()),
+            (None, None) => " ".to_owned()
+        };
+
+        let line_num_color = if file_row == self.cy {
+            theme.current_line()
+        } else if let Some(color) = change_color {
+            color
+        } else {
+            theme.dimmed()
+        };
+
+        let _ = write!(
+            line, 
+            "\x1b[38;2;{line_num_color}m{:width$}{marker}\x1b[38;2;{}m{}", 
+            1 + file_row, 
+            theme.dimmed(),
+            self.config.line_number_separator(),
+            width=self.line_num_width()
+        );
+    }
+
+    /// The number of columns that line numbers are padded to, which is enough for the last row's number (or the configured minimum, if that's more).
+    fn line_num_width(&self) -> usize {
+        cmp::max(self.editor.get_buf().num_rows().len(), self.config.line_number_min_width())
+    }
+
+    /// Draws the diagnostics panel (if it is open) below the buffer's rows.
+    pub fn draw_panel(&mut self) -> error::Result<()> {
+        let height = self.panel.height(self.screen_rows);
+        if height == 0 {
+            return Ok(());
+        }
+
+        let config = Rc::clone(&self.config);
+        let theme = config.theme();
+        let entries = DiagnosticsPanel::entries(&self.editor);
+        self.panel.scroll(entries.len(), height - 1);
+
+        let mut lines = Vec::with_capacity(height);
+
+        let header = format!(" DIAGNOSTICS ({}) ", entries.len());
+        lines.push(format!("\x1b[7m{header:<width$}\x1b[m", width=self.screen_cols));
+
+        for (i, (buf_idx, diagnostic)) in entries.iter().enumerate().skip(self.panel.offset()).take(height - 1) {
+            let color = match diagnostic.severity() {
+                Severity::Error => theme.error(),
+                Severity::Warning => theme.warning()
+            };
+            let bg = if i == self.panel.selected() && self.panel.is_focused() {
+                theme.select()
+            } else {
+                theme.bg()
+            };
+
+            let text: String = format!(" {}:{}:{} {}", self.editor.bufs()[*buf_idx].buf().file_name(), diagnostic.row() + 1, diagnostic.col() + 1, diagnostic.msg())
+                .chars()
+                .take(self.screen_cols.saturating_sub(1))
+                .collect();
+
+            lines.push(format!(
+                "\x1b[48;2;{bg}m\x1b[38;2;{color}m●\x1b[38;2;{}m{text:<width$}\x1b[m",
+                theme.fg(),
+                width=self.screen_cols.saturating_sub(1)
+            ));
+        }
+
+        while lines.len() < height {
+            lines.push(format!("\x1b[48;2;{}m{:width$}\x1b[m", theme.bg(), "", width=self.screen_cols));
+        }
+
+        for line in lines {
+            self.queue(Print(format!("{line}\r\n")))?;
+        }
+
+        Ok(())
+    }
+
+    /// Processes a key event while the diagnostics panel is focused.
+    fn process_panel_key(&mut self, key: &KeyEvent) {
+        let num_entries = DiagnosticsPanel::entries(&self.editor).len();
+
+        match *key {
+            KeyEvent { code: KeyCode::Up, .. } => self.panel.select_prev(),
+            KeyEvent { code: KeyCode::Down, .. } => self.panel.select_next(num_entries),
+            KeyEvent { code: KeyCode::Esc, .. } => self.panel.unfocus(),
+            KeyEvent { 
+                code: KeyCode::Char('d'), 
+                modifiers: KeyModifiers::ALT, 
+                .. 
+            } => self.panel.close(),
+            KeyEvent { code: KeyCode::Enter, .. } => {
+                let (buf_idx, row, col) = match DiagnosticsPanel::entries(&self.editor).get(self.panel.selected()) {
+                    Some((buf_idx, d)) => (*buf_idx, d.row(), d.col()),
+                    None => return
+                };
+
+                if self.editor.view().is_in_select_mode() {
+                    self.exit_select_mode();
+                }
+
+                self.switch_to_buf(buf_idx);
+
+                let buf = self.editor.get_buf();
+                if buf.num_rows() > 0 {
+                    self.cy = cmp::min(row, buf.num_rows() - 1);
+                    self.cx = cmp::min(col, buf.row_at(self.cy).size());
+                } else {
+                    (self.cx, self.cy) = (0, 0);
+                }
+
+                self.panel.unfocus();
+            }
+            _ => ()
+        }
+    }
+
     pub fn move_cursor(&mut self, key: KeyCode) {
         let buf = self.editor.get_buf();
 
         let row = if self.cy >= buf.num_rows() {
             None
         } else {
-            Some(self.get_row())
+            Some(&buf.rows()[self.cy])
         };
 
+        // Keep the cursor in the same column when moving between rows
+        let rx = row.map_or(0, |r| r.cx_to_rx(self.cx));
+        let prev_cy = self.cy;
+
         match key {
             KeyCode::Up     => if self.cy != 0 {
                 self.cy -= 1;
@@ -715,21 +1791,25 @@ impl Screen {
                 self.cx = 0;
             }
             KeyCode::Left   => if self.cx != 0 {
-                self.cx -= 1;
+                let cx = self.get_row().prev_grapheme(self.cx);
+                self.cx = cx;
             } else if self.cy != 0 {
                 self.cy -= 1;
-                self.cx = self.get_row().size();
+                let cx = self.get_row().size();
+                self.cx = cx;
             },
             KeyCode::Down   => if buf.num_rows() > 0 {
                 if self.cy < buf.num_rows() - 1 {
                     self.cy += 1;
                 } else if self.cy == buf.num_rows() - 1 {
-                    self.cx = self.get_row().rsize();
+                    let cx = self.get_row().size();
+                    self.cx = cx;
                 }
             },
             KeyCode::Right  => if row.is_some() {
                 if self.cx < row.unwrap().size() {
-                    self.cx += 1;
+                    let cx = self.get_row().next_grapheme(self.cx);
+                    self.cx = cx;
                 } else if self.cy < buf.num_rows() - 1 {
                     self.cy += 1;
                     self.cx = 0;
@@ -741,25 +1821,188 @@ impl Screen {
         let buf = self.editor.get_buf();
 
         // Cursor jump back to end of line when going from longer line to shorter one
-        let row = if self.cy >= buf.num_rows() {
-            None
+        if self.cy < buf.num_rows() {
+            let row = &buf.rows()[self.cy];
+
+            if self.cy != prev_cy && matches!(key, KeyCode::Up | KeyCode::Down) {
+                self.cx = row.rx_to_cx(rx);
+            }
+
+            self.cx = row.grapheme_start(self.cx);
+        } else {
+            self.cx = 0;
+        }
+    }
+
+    /// Moves the cursor by a page, up for [`KeyCode::PageUp`] and down for [`KeyCode::PageDown`].
+    pub fn move_page(&mut self, key: KeyCode) {
+        let num_rows = self.editor.get_buf().num_rows();
+
+        if key == KeyCode::PageUp {
+            self.cy = self.row_offset;
+        } else {
+            self.cy = if num_rows == 0 { 
+                0 
+            } else { 
+                cmp::min(num_rows - 1, self.row_offset + self.text_rows() - 1) 
+            };
+        }
+
+        for _ in 0..self.text_rows() {
+            self.move_cursor(if key == KeyCode::PageUp {
+                KeyCode::Up
+            } else {
+                KeyCode::Down
+            });
+        }
+    }
+
+    /// Moves the cursor to the start of the line for [`KeyCode::Home`], or to the end for [`KeyCode::End`].
+    pub fn move_line_edge(&mut self, key: KeyCode) {
+        if key == KeyCode::Home {
+            self.cx = 0;
+        } else if self.cy < self.editor.get_buf().num_rows() {
+            let cx = self.get_row().size();
+            self.cx = cx;
+        }
+    }
+
+    /// Moves the cursor using `move_fn`, extending the selection to where it ends up (starting one at the cursor if there isn't one).
+    pub fn move_select<F>(&mut self, move_fn: F) 
+    where
+        F: FnOnce(&mut Self)
+    {
+        if !self.editor.view().is_in_select_mode() {
+            self.enter_select_mode();
+        }
+
+        move_fn(self);
+    }
+
+    /// Selects the word that the cursor is in (or just after).
+    pub fn select_word(&mut self) {
+        if self.cy >= self.editor.get_buf().num_rows() {
+            return;
+        }
+
+        if self.editor.view().is_in_select_mode() {
+            self.exit_select_mode();
+        }
+
+        let row = self.get_row();
+        let chars = row.chars();
+        let start = chars[..self.cx]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, ch)| !is_sep(ch))
+            .last()
+            .map_or(self.cx, |(i, _)| i);
+        let end = chars[self.cx..]
+            .char_indices()
+            .find(|&(_, ch)| is_sep(ch))
+            .map_or(chars.len(), |(i, _)| self.cx + i);
+        drop(row);
+
+        if start == end {
+            self.set_status_msg("No word under the cursor".to_owned());
+            return;
+        }
+
+        self.cx = start;
+        self.enter_select_mode();
+        self.cx = end;
+    }
+
+    /// Selects the text inside the nearest brackets or quotes around the cursor (see [`TextBuffer::enclosing_pair`]), or the brackets or quotes as well if `is_around` is set. If something is already selected, the next ones out from the selection are used instead, so that doing this again extends it.
+    pub fn select_enclosed(&mut self, is_around: bool) {
+        // Brackets can be closed much further down
+        self.editor.get_buf_mut().load_all_rows(&self.config);
+
+        let is_on_opener = self.editor.get_buf().rows()
+            .get(self.cy)
+            .and_then(|row| row.chars()[self.cx..].chars().next())
+            .is_some_and(|ch| matches!(ch, '(' | '[' | '{'));
+
+        let (from, to) = if self.editor.view().is_in_select_mode() {
+            self.get_select_region()
+        } else if is_on_opener {
+            // On an opening bracket, it's the one that is meant
+            (Pos(self.cx + 1, self.cy), Pos(self.cx + 1, self.cy))
         } else {
-            Some(self.get_row())
+            (pos!(self), pos!(self))
         };
 
-        let len = if let Some(r) = row {
-            r.rsize()
+        let mut pair = self.editor.get_buf().enclosing_pair(from, to);
+        // What's inside is already selected, so the next ones out are meant
+        if let Some((open, close)) = pair {
+            if !is_around && (Pos(open.x() + 1, open.y()), close) == (from, to) {
+                pair = self.editor.get_buf().enclosing_pair(open, Pos(close.x() + 1, close.y()));
+            }
+        }
+
+        let (open, close) = match pair {
+            Some(pair) => pair,
+            None => {
+                self.set_status_msg("No brackets or quotes around the cursor".to_owned());
+                self.alert();
+                return;
+            }
+        };
+
+        // The brackets and quotes are all one byte long
+        let (start, end) = if is_around {
+            (open, Pos(close.x() + 1, close.y()))
         } else {
-            0
+            (Pos(open.x() + 1, open.y()), close)
         };
 
-        if self.cx > len {
-            self.cx = len;
+        if self.editor.view().is_in_select_mode() {
+            self.exit_select_mode();
+        }
+
+        Pos(self.cx, self.cy) = start;
+        if start == end {
+            self.set_status_msg("Nothing inside".to_owned());
+            return;
+        }
+
+        self.enter_select_mode();
+        Pos(self.cx, self.cy) = end;
+    }
+
+    /// Selects the line that the cursor is on. If whole lines are already selected (ie. by doing this before), the selection is extended by the next line instead.
+    pub fn select_line(&mut self) {
+        let num_rows = self.editor.get_buf().num_rows();
+        if num_rows == 0 {
+            return;
+        }
+
+        let is_line_select = matches!(
+            self.editor.view().select_anchor(), 
+            Some(anchor) if anchor.x() == 0 && anchor.y() < self.cy && self.cx == 0
+        );
+
+        if !is_line_select {
+            if self.editor.view().is_in_select_mode() {
+                self.exit_select_mode();
+            }
+
+            self.cx = 0;
+            self.cy = cmp::min(self.cy, num_rows - 1);
+            self.enter_select_mode();
         }
+
+        // Select up to the start of the next line, so that the newline is included, unless it's the last line
+        self.move_select(|s| if s.cy + 1 < num_rows {
+            s.cy += 1;
+        } else {
+            let cx = s.get_row().size();
+            s.cx = cx;
+        });
     }
 
     pub fn move_cursor_select(&mut self, key: KeyCode) {
-        let anchor = self.editor.get_buf().select_anchor().unwrap();
+        let anchor = self.editor.view().select_anchor().unwrap();
         let cpos = pos!(self);
         
         let front = cmp::min(anchor, cpos);
@@ -799,11 +2042,11 @@ impl Screen {
         let row = if self.cy >= buf.num_rows() {
             None
         } else {
-            Some(self.get_row())
+            Some(&buf.rows()[self.cy])
         };
 
         let len = if let Some(r) = row {
-            r.rsize()
+            r.size()
         } else {
             0
         };
@@ -813,12 +2056,62 @@ impl Screen {
         }
     }
 
+    /// Moves the cursor to the start of the next cell (or previous cell if `forward` is false) of a table.
+    pub fn move_to_cell(&mut self, forward: bool) {
+        let buf = self.editor.get_buf();
+        let delim = match buf.syntax().lang().table_delim() {
+            Some(delim) => delim,
+            None => return
+        };
+
+        if self.cy >= buf.num_rows() {
+            return;
+        }
+
+        let num_rows = buf.num_rows();
+        let starts: Vec<usize> = table::cell_ranges(self.get_row().chars(), delim)
+            .into_iter()
+            .map(|r| r.start)
+            .collect();
+
+        if forward {
+            if let Some(&x) = starts.iter().find(|&&x| x > self.cx) {
+                self.cx = x;
+            } else if self.cy + 1 < num_rows {
+                self.cy += 1;
+                self.cx = 0;
+            }
+        } else if let Some(&x) = starts.iter().rev().find(|&&x| x < self.cx) {
+            self.cx = x;
+        } else if self.cy > 0 {
+            self.cy -= 1;
+            self.cx = table::cell_ranges(buf.row_at(self.cy).chars(), delim)
+                .last()
+                .map_or(0, |r| r.start);
+        }
+    }
+
     /// Processes the given `&KeyEvent`.
     /// 
-    /// Takes ownership of `self`, but returns it back out if it didn't exit the program.
-    pub fn process_key_event(mut self, key: &KeyEvent) -> error::Result<Self> {
+    /// Returns [`ControlFlow::Break`] if the editor should quit.
+    pub fn process_key_event(&mut self, key: &KeyEvent) -> error::Result<ControlFlow<()>> {
         let config = Rc::clone(&self.config);
-        let num_rows = self.editor.get_buf().num_rows();
+        let prev_cy = self.cy;
+
+        if self.msg_log.is_some() {
+            self.process_msg_log_key(key);
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        if self.picker.is_some() {
+            self.process_picker_key(key);
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        if self.panel.is_focused() {
+            self.process_panel_key(key);
+            return Ok(ControlFlow::Continue(()));
+        }
         
         match *key {
             // Quit (CTRL+Q)
@@ -827,32 +2120,8 @@ impl Screen {
                 modifiers: KeyModifiers::CONTROL,
                 ..
             } => {
-                let mut is_dirty = false;
-
-                for buf in self.editor.bufs() {
-                    if buf.is_dirty() {
-                        is_dirty = true;
-                        break;
-                    }
-                }
-
-                if is_dirty && self.editor.quit_times() > 0 {
-                    let remaining = self.editor.quit_times();
-                    let s = if remaining == 1 {
-                        "again".to_owned()
-                    } else {
-                        format!("{} more times", remaining)
-                    };
-
-                    let msg = format!("\x1b[31mWARNING!\x1b[m At least one file has unsaved changes. Press CTRL+S to save or CTRL+Q {s} to force quit all files without saving.");
-                    
-                    self.set_status_msg(msg);
-                    self.editor.set_quit_times(self.editor.quit_times() - 1);
-
-                    return Ok(self);    // Return so that quit_times is not reset
-                } else {
-                    drop(self);
-                    std::process::exit(0);
+                if self.confirm_quit()? {
+                    return Ok(ControlFlow::Break(()));
                 }
             }
 
@@ -877,10 +2146,15 @@ impl Screen {
                 modifiers: KeyModifiers::CONTROL, 
                 ..
             } => {
-                let text = self.prompt("Open file (Use ESC/Enter): ", &|_, _, _| { })?;
+                let text = self.prompt_path("Open file (Use ESC/Enter): ")?;
                 if text.is_some() {
                     let text = text.unwrap();
 
+                    if Path::new(&text).is_dir() {
+                        self.open_picker(&text);
+                        return Ok(ControlFlow::Continue(()));
+                    }
+
                     if let Err(_) | Ok(false) = Path::new(&text).try_exists() {
                         let res = self.prompt(&format!("File '{text}' doesn't exist. Would you like to create it (Y/n) "), &|_, _, _| { })?;
 
@@ -891,20 +2165,22 @@ impl Screen {
                         }
                     }
 
-                    // When there is only 1 empty buffer in the editor, replace that buffer instead of creating a new one
-                    if self.editor.num_bufs() == 1 && self.editor.bufs()[0].num_rows() == 0 {
-                        self.editor.remove_buf(0);
-                    }
-
-                    let mut buf = TextBuffer::new(config.readonly());
-                    buf.open(&text, &*self.config)?;
+                    self.open_file(&text);
+                }
+            }
 
-                    self.editor.append_buf(buf);
-                    self.editor.set_current_buf(self.editor.bufs().len() - 1);
+            // Browse Project Files (ALT+O)
+            KeyEvent { 
+                code: KeyCode::Char('o'), 
+                modifiers: KeyModifiers::ALT, 
+                ..
+            } => {
+                let dir = match self.editor.project() {
+                    Some(project) => project.root().to_owned(),
+                    None => self.editor.get_buf().dir().to_owned()
+                };
 
-                    self.cx = 0;
-                    self.cy = 0;
-                }
+                self.open_picker(&dir.to_string_lossy());
             }
 
             // Close Tab (CTRL+W)
@@ -913,9 +2189,10 @@ impl Screen {
                 modifiers: KeyModifiers::CONTROL, 
                 ..
             } => {
-                let buf = self.editor.get_buf();
+                // Changes aren't lost when the buffer is still shown in another tab
+                let is_dirty = self.editor.get_buf().is_dirty() && !self.editor.is_buf_shared();
 
-                if buf.is_dirty() && self.editor.close_times() > 0 {
+                if is_dirty && self.editor.close_times() > 0 {
                     let remaining = self.editor.close_times();
                     let s = if remaining == 1 {
                         "again".to_owned()
@@ -923,32 +2200,49 @@ impl Screen {
                         format!("{} more times", remaining)
                     };
 
-                    let msg = format!("\x1b[31mWARNING!\x1b[m File has unsaved changes. Press CTRL+S to save or CTRL+W {s} to force quit without saving.");
+                    let msg = format!("File has unsaved changes. Press CTRL+S to save or CTRL+W {s} to force quit without saving.");
 
-                    self.set_status_msg(msg);
+                    self.set_status_warning(msg);
                     self.editor.set_close_times(self.editor.close_times() - 1);
 
-                    return Ok(self);    // Return so that close_times is not reset
+                    return Ok(ControlFlow::Continue(()));    // Return so that close_times is not reset
                 } else {
                     self.editor.remove_current_buf();
 
                     if self.editor.num_bufs() == 0 {
                         self.editor.append_buf(TextBuffer::new(config.readonly()));
-                        self.cx = 0;
-                        self.cy = 0;
                     }
+                    self.restore_view();
 
                     self.set_status_msg(String::new());
                 }
             }
 
+            // Close Other Tabs (CTRL+ALT+W)
+            KeyEvent {
+                code: KeyCode::Char('w'),
+                modifiers: m,
+                ..
+            } if m == KeyModifiers::CONTROL | KeyModifiers::ALT => {
+                self.close_bufs(true)?;
+            }
+
+            // Close All Tabs (CTRL+SHIFT+W)
+            KeyEvent {
+                code: KeyCode::Char('W'),
+                modifiers: m,
+                ..
+            } if m == KeyModifiers::CONTROL | KeyModifiers::SHIFT => {
+                self.close_bufs(false)?;
+            }
+
             // Rename (CTRL+R)
             KeyEvent {
                 code: KeyCode::Char('r'),
                 modifiers: KeyModifiers::CONTROL,
                 ..
             } => 'edit_event: {
-                if let &Mode::View = self.editor.get_buf().mode() {
+                if self.editor.view().mode() == Mode::View {
                     self.report_readonly();
                     break 'edit_event;
                 }
@@ -956,12 +2250,50 @@ impl Screen {
                 self.rename("Rename (ESC to cancel): ")?;
             }
 
+            // Revert To Saved (ALT+R)
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.revert()?;
+            }
+
+            // Export To HTML (CTRL+ALT+E)
+            KeyEvent {
+                code: KeyCode::Char('e'),
+                modifiers: m,
+                ..
+            } if m == KeyModifiers::CONTROL | KeyModifiers::ALT => {
+                self.export_html()?;
+            }
+
+            // Write Selection To File (CTRL+ALT+S)
+            KeyEvent {
+                code: KeyCode::Char('s'),
+                modifiers: m,
+                ..
+            } if m == KeyModifiers::CONTROL | KeyModifiers::ALT => {
+                self.write_selection()?;
+            }
+
+            // Convert Line Endings (ALT+E)
+            KeyEvent {
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.change_line_ending()?;
+            }
+
             // Refresh (CTRL+SHIFT+R)
             KeyEvent { 
                 code: KeyCode::Char('R'), 
                 modifiers: m, 
                 ..
             } if m == KeyModifiers::CONTROL | KeyModifiers::SHIFT => {
+                // Redraw every cell, rather than only those that changed
+                self.prev_frame = None;
                 self.refresh()?;
             }
 
@@ -980,7 +2312,7 @@ impl Screen {
                 modifiers: m ,
                 ..
             } if m == KeyModifiers::CONTROL | KeyModifiers::SHIFT => 'edit_event: {
-                if let &Mode::View = self.editor.get_buf().mode() {
+                if self.editor.view().mode() == Mode::View {
                     self.report_readonly();
                     break 'edit_event;
                 }
@@ -998,164 +2330,333 @@ impl Screen {
                 self.find()?;
             }
 
-            // Select All (CTRL+A)
+            // Replace All (ALT+S)
             KeyEvent {
-                code: KeyCode::Char('a'),
+                code: KeyCode::Char('s'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => 'edit_event: {
+                if !self.check_editable() {
+                    break 'edit_event;
+                }
+
+                self.replace_all()?;
+            }
+
+            // Toggle Table View (CTRL+T)
+            KeyEvent {
+                code: KeyCode::Char('t'),
                 modifiers: KeyModifiers::CONTROL,
                 ..
             } => {
-                if self.editor.get_buf().is_in_select_mode() {
+                if self.editor.view().is_in_select_mode() {
                     self.exit_select_mode();
                 }
 
-                (self.cx, self.cy) = (0, 0);
-                self.enter_select_mode();
+                let is_table_view = self.editor.get_buf_mut().toggle_table_view(&config);
+                match is_table_view {
+                    Some(true) => self.set_status_msg("Table view on".to_owned()),
+                    Some(false) => self.set_status_msg("Table view off".to_owned()),
+                    None => self.set_status_msg("Table view is only available for CSV/TSV files".to_owned())
+                }
+            }
 
-                self.cy = self.editor.get_buf().num_rows() - 1;
-                self.cx = self.get_row().rsize();
-                self.select();
+            // Compare With Another Tab Side By Side (CTRL+ALT+D)
+            KeyEvent {
+                code: KeyCode::Char('d'),
+                modifiers: m,
+                ..
+            } if m == KeyModifiers::CONTROL | KeyModifiers::ALT => {
+                self.toggle_diff_pane()?;
             }
 
-            // Copy (CTRL+C)
+            // Toggle Markdown Preview (CTRL+P)
             KeyEvent {
-                code: KeyCode::Char('c'),
+                code: KeyCode::Char('p'),
                 modifiers: KeyModifiers::CONTROL,
                 ..
             } => {
-                self.copy();
+                if *self.editor.get_buf().syntax().lang() == Language::Markdown {
+                    self.show_preview = !self.show_preview;
+                } else {
+                    self.set_status_msg("Preview is only available for Markdown files".to_owned());
+                }
             }
-            
-            // Paste (CTRL+V)
-            KeyEvent { 
-                code: KeyCode::Char('v'), 
-                modifiers: KeyModifiers::CONTROL, 
+
+            // Format File (ALT+F)
+            KeyEvent {
+                code: KeyCode::Char('f'),
+                modifiers: KeyModifiers::ALT,
                 ..
             } => 'edit_event: {
-                if let &Mode::View = self.editor.get_buf().mode() {
-                    self.report_readonly();
+                if !self.check_editable() {
                     break 'edit_event;
                 }
 
-                if self.editor.get_buf().is_in_select_mode() {
-                    let (from, to) = self.get_select_region();
-                    let msg = self.editor.get_buf().create_remove_msg_region(from, to, &config);
-
-                    Pos(self.cx, self.cy) = self.editor.get_buf_mut().remove_rows(from, msg, &config);
-                    self.exit_select_mode();
+                match self.format_buf() {
+                    Ok(Some(true)) => self.set_status_msg("File formatted".to_owned()),
+                    Ok(Some(false)) => self.set_status_msg("File already formatted".to_owned()),
+                    Ok(None) => (),
+                    Err(err) => {
+                        log::warn(&format!("Format failed: {err}"));
+                        self.set_status_error(format!("Format failed: {err}"));
+                    }
                 }
-                
-                self.paste();
             }
 
-            // Undo (CTRL+Z)
-            KeyEvent { 
-                code: KeyCode::Char('z'), 
-                modifiers: KeyModifiers::CONTROL, 
+            // Lint File (ALT+L)
+            KeyEvent {
+                code: KeyCode::Char('l'),
+                modifiers: KeyModifiers::ALT,
                 ..
-            } => 'edit_event: {
-                if let &Mode::View = self.editor.get_buf().mode() {
-                    self.report_readonly();
-                    break 'edit_event;
+            } => {
+                match self.lint() {
+                    Ok(Some(0)) => self.set_status_msg("No problems found".to_owned()),
+                    Ok(Some(n)) => self.set_status_msg(format!("{n} problem{} found", if n == 1 { "" } else { "s" })),
+                    Ok(None) => (),
+                    Err(err) => {
+                        log::warn(&format!("Lint failed: {err}"));
+                        self.set_status_error(format!("Lint failed: {err}"));
+                    }
                 }
-
-                self.undo();
             }
 
-            // Redo (CTRL+Y)
+            // Diagnostics Panel (ALT+D)
             KeyEvent {
-                code: KeyCode::Char('y'),
-                modifiers: KeyModifiers::CONTROL,
+                code: KeyCode::Char('d'),
+                modifiers: KeyModifiers::ALT,
                 ..
-            } => 'edit_event: {
-                if let &Mode::View = self.editor.get_buf().mode() {
-                    self.report_readonly();
-                    break 'edit_event;
+            } => {
+                if self.panel.is_open() {
+                    self.panel.focus();
+                } else {
+                    self.panel.open();
                 }
-
-                self.redo();
             }
 
-            // Move (arrows)
+            // Message History (ALT+H)
             KeyEvent {
-                code: KeyCode::Up       |
-                    KeyCode::Down       |
-                    KeyCode::Left       |
-                    KeyCode::Right,
-                modifiers: KeyModifiers::NONE,
+                code: KeyCode::Char('h'),
+                modifiers: KeyModifiers::ALT,
                 ..
             } => {
-                if self.editor.get_buf().is_in_select_mode() {
-                    self.move_cursor_select(key.code);
-                } else {
-                    self.move_cursor(key.code);
-                }
+                self.msg_log = Some(0);
             }
 
-            // Select & Move (SHIFT + arrows)
-            KeyEvent { 
-                code: KeyCode::Up   |
-                    KeyCode::Down   |
-                    KeyCode::Left   |
-                    KeyCode::Right, 
-                modifiers: KeyModifiers::SHIFT, 
+            // Next/Previous Difference (ALT+N/ALT+P)
+            KeyEvent {
+                code: code @ (KeyCode::Char('n') | KeyCode::Char('p')),
+                modifiers: KeyModifiers::ALT,
                 ..
             } => {
-                if !self.editor.get_buf().is_in_select_mode() {
-                    self.enter_select_mode();
-                }   
+                self.jump_to_hunk(code == KeyCode::Char('n'));
+            }
+
+            // Pretty-print/Minify JSON (ALT+J/ALT+M)
+            KeyEvent {
+                code: code @ (KeyCode::Char('j') | KeyCode::Char('m')),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => 'edit_event: {
+                if !self.check_editable() {
+                    break 'edit_event;
+                }
 
-                let syntax = self.editor.get_buf().syntax();
-                self.get_row_mut().update_highlight(syntax);
-                self.move_cursor(key.code);
-                self.get_row_mut().update_highlight(syntax);
-                self.select();
+                self.format_json(code == KeyCode::Char('m'));
             }
 
-            // Page Up/Page Down (pg up/dn)
-            KeyEvent { 
-                code: code @ (KeyCode::PageUp | KeyCode::PageDown), 
-                modifiers: KeyModifiers::NONE, 
+            // Select All (CTRL+A)
+            KeyEvent {
+                code: KeyCode::Char('a'),
+                modifiers: KeyModifiers::CONTROL,
                 ..
             } => {
-                if code == KeyCode::PageUp {
-                    self.cy = self.row_offset;
-                } else {
-                    self.cy = if num_rows == 0 { 
-                        0 
-                    } else { 
-                        cmp::min(num_rows - 1, self.row_offset + self.screen_rows - 1) 
-                    };
+                if self.editor.view().is_in_select_mode() {
+                    self.exit_select_mode();
                 }
 
-                for _ in 0..self.screen_rows {
-                    self.move_cursor(if code == KeyCode::PageUp {
-                        KeyCode::Up
-                    } else {
-                        KeyCode::Down
-                    });
-                }
+                self.editor.get_buf_mut().load_all_rows(&config);
+                (self.cx, self.cy) = (0, 0);
+                self.enter_select_mode();
+
+                self.cy = self.editor.get_buf().num_rows() - 1;
+                let cx = self.get_row().size();
+                self.cx = cx;
             }
 
-            // Select & Page Up/Page Down (SHIFT + pg up/dn)
+            // Select Word (CTRL+D)
             KeyEvent {
-                code: code @ (KeyCode::PageUp | KeyCode::PageDown),
-                modifiers: KeyModifiers::SHIFT,
+                code: KeyCode::Char('d'),
+                modifiers: KeyModifiers::CONTROL,
                 ..
             } => {
-                () // TODO
+                self.select_word();
             }
 
-            // Home/End
-            KeyEvent { 
-                code: code @ (KeyCode::Home | KeyCode::End), 
-                modifiers: KeyModifiers::NONE, 
+            // Select Inside Brackets Or Quotes (ALT+I)
+            KeyEvent {
+                code: KeyCode::Char('i'),
+                modifiers: KeyModifiers::ALT,
                 ..
             } => {
-                if code == KeyCode::Home {
-                    self.cx = 0;
-                } else if self.cy < self.editor.get_buf_mut().num_rows() {
-                    self.cx = self.get_row().size();
-                }
+                self.select_enclosed(false);
+            }
+
+            // Select Around Brackets Or Quotes (ALT+A)
+            KeyEvent {
+                code: KeyCode::Char('a'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.select_enclosed(true);
+            }
+
+            // Select Line (CTRL+L)
+            KeyEvent {
+                code: KeyCode::Char('l'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.select_line();
+            }
+
+            // Copy (CTRL+C)
+            KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.copy();
+            }
+            
+            // Paste (CTRL+V)
+            KeyEvent { 
+                code: KeyCode::Char('v'), 
+                modifiers: KeyModifiers::CONTROL, 
+                ..
+            } => {
+                self.paste();
+            }
+
+            // Repeat Last Edit (ALT+.)
+            KeyEvent {
+                code: KeyCode::Char('.'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.repeat_edit();
+            }
+
+            // Paste From Primary Selection (CTRL+ALT+V)
+            KeyEvent {
+                code: KeyCode::Char('v'),
+                modifiers: m,
+                ..
+            } if m == KeyModifiers::CONTROL | KeyModifiers::ALT => {
+                self.paste_primary();
+            }
+
+            // Suspend (CTRL+Z, if set in the config)
+            KeyEvent {
+                code: KeyCode::Char('z'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } if config.ctrl_z_suspends() => {
+                self.suspend();
+            }
+
+            // Undo (CTRL+Z or ALT+Z)
+            KeyEvent { 
+                code: KeyCode::Char('z'), 
+                modifiers: KeyModifiers::CONTROL, 
+                ..
+            } | KeyEvent {
+                code: KeyCode::Char('z'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => 'edit_event: {
+                if !self.check_editable() {
+                    break 'edit_event;
+                }
+
+                self.undo();
+            }
+
+            // Redo (CTRL+Y)
+            KeyEvent {
+                code: KeyCode::Char('y'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => 'edit_event: {
+                if !self.check_editable() {
+                    break 'edit_event;
+                }
+
+                self.redo();
+            }
+
+            // Move (arrows)
+            KeyEvent {
+                code: KeyCode::Up       |
+                    KeyCode::Down       |
+                    KeyCode::Left       |
+                    KeyCode::Right,
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                if self.editor.view().is_in_select_mode() {
+                    self.move_cursor_select(key.code);
+                } else {
+                    self.move_cursor(key.code);
+                }
+            }
+
+            // Select & Move (SHIFT + arrows)
+            KeyEvent { 
+                code: KeyCode::Up   |
+                    KeyCode::Down   |
+                    KeyCode::Left   |
+                    KeyCode::Right, 
+                modifiers: KeyModifiers::SHIFT, 
+                ..
+            } => {
+                self.move_select(|s| s.move_cursor(key.code));
+            }
+
+            // Page Up/Page Down (pg up/dn)
+            KeyEvent { 
+                code: code @ (KeyCode::PageUp | KeyCode::PageDown), 
+                modifiers: KeyModifiers::NONE, 
+                ..
+            } => {
+                self.move_page(code);
+            }
+
+            // Select & Page Up/Page Down (SHIFT + pg up/dn)
+            KeyEvent {
+                code: code @ (KeyCode::PageUp | KeyCode::PageDown),
+                modifiers: KeyModifiers::SHIFT,
+                ..
+            } => {
+                self.move_select(|s| s.move_page(code));
+            }
+
+            // Home/End
+            KeyEvent { 
+                code: code @ (KeyCode::Home | KeyCode::End), 
+                modifiers: KeyModifiers::NONE, 
+                ..
+            } => {
+                self.move_line_edge(code);
+            }
+
+            // Select & Home/End (SHIFT + home/end)
+            KeyEvent {
+                code: code @ (KeyCode::Home | KeyCode::End),
+                modifiers: KeyModifiers::SHIFT,
+                ..
+            } => {
+                self.move_select(|s| s.move_line_edge(code));
             }
 
             // Ctrl+Tab (go to next buffer)
@@ -1164,9 +2665,86 @@ impl Screen {
                 modifiers: KeyModifiers::CONTROL,
                 ..
             } => {
-                self.editor.get_buf_mut().set_cursor_pos(Pos(self.cx, self.cy));
+                let prev_id = self.editor.get_buf().id();
+                let (prev_cy, prev_row_offset) = (self.cy, self.row_offset);
+
+                self.save_view();
                 self.editor.next_buf();
-                Pos(self.cx, self.cy) = self.editor.get_buf().saved_cursor_pos();
+                self.restore_view();
+                self.sync_diff_pane(prev_id, prev_cy, prev_row_offset);
+            }
+
+            // Open Another View Of File (ALT+V)
+            KeyEvent {
+                code: KeyCode::Char('v'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.split_buf();
+            }
+
+            // Show File Statistics (CTRL+G)
+            KeyEvent {
+                code: KeyCode::Char('g'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                self.show_file_stats();
+            }
+
+            // Toggle Soft Wrap In This Tab (ALT+W)
+            KeyEvent {
+                code: KeyCode::Char('w'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.toggle_view_option("Soft wrap", ViewOptions::soft_wrap, ViewOptions::set_soft_wrap);
+            }
+
+            // Toggle Line Numbers In This Tab (ALT+G)
+            KeyEvent {
+                code: KeyCode::Char('g'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.toggle_view_option("Line numbers", ViewOptions::line_numbers, ViewOptions::set_line_numbers);
+            }
+
+            // Toggle Rulers In This Tab (ALT+C)
+            KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.toggle_view_option("Rulers", |options, _| options.rulers(), ViewOptions::set_rulers);
+            }
+
+            // Open URL Under Cursor (ALT+U)
+            KeyEvent {
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                self.open_url();
+            }
+
+            // Go To Tab (ALT+1 to ALT+9, or ALT+0 for the last tab)
+            KeyEvent {
+                code: KeyCode::Char(ch @ '0'..='9'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => {
+                let num_bufs = self.editor.num_bufs();
+                let idx = match ch {
+                    '0' => num_bufs - 1,
+                    _ => ch as usize - '1' as usize
+                };
+
+                if idx < num_bufs {
+                    self.switch_to_buf(idx);
+                } else {
+                    self.set_status_msg(format!("There is no tab {}", idx + 1));
+                }
             }
 
             // Enter (make new line)
@@ -1175,12 +2753,14 @@ impl Screen {
                 modifiers: KeyModifiers::NONE, 
                 .. 
             } => 'edit_event: {
-                if let &Mode::View = self.editor.get_buf().mode() {
-                    self.report_readonly();
+                if !self.check_editable() {
                     break 'edit_event;
                 }
 
-                Pos(self.cx, self.cy) = self.editor.get_buf_mut().insert_rows(pos!(self), vec![Row::new(); 2], &config);
+                let start = pos!(self);
+                let mut typed = String::new();
+                self.expand_abbreviation(|screen| typed = screen.insert_newline());
+                self.last_edit.record(Edit::Type(typed), start, pos!(self));
             }
 
             // Backspace/Delete (remove char)
@@ -1189,17 +2769,24 @@ impl Screen {
                 modifiers: KeyModifiers::NONE, 
                 ..
             } => 'edit_event: {
-                if let &Mode::View = self.editor.get_buf().mode() {
-                    self.report_readonly();
+                if !self.check_editable() {
                     break 'edit_event;
                 }
 
-                if self.editor.get_buf().is_in_select_mode() {
+                if self.editor.view().is_in_select_mode() {
                     let (from, to) = self.get_select_region();
-                    let msg = self.editor.get_buf().create_remove_msg_region(from, to, &config);
-                    Pos(self.cx, self.cy) = self.editor.get_buf_mut().remove_rows(from, msg, &config);
+                    let msg = self.editor.get_buf().create_remove_msg_region(from, to);
+                    Pos(self.cx, self.cy) = self.editor.get_buf_mut().remove_rows(from, msg, pos!(self), &config);
+                    self.exit_select_mode();
                 } else {
+                    let start = pos!(self);
+                    let changes = self.editor.get_buf().changes();
                     self.remove_char(code == KeyCode::Delete);
+
+                    // Nothing is removed at the start or end of the buffer, which isn't an edit to repeat
+                    if self.editor.get_buf().changes() != changes {
+                        self.last_edit.record(Edit::Remove { count: 1, is_delete: code == KeyCode::Delete }, start, pos!(self));
+                    }
                 }
             }
 
@@ -1220,25 +2807,38 @@ impl Screen {
                 self.open_keybind_buf()?;
             }
 
-            // Tab (insert tab)
+            // Tab (insert tab, or move to next cell in table view)
             KeyEvent {
                 code: KeyCode::Tab,
                 modifiers: KeyModifiers::NONE,
                 ..
             } => 'edit_event: {
-                if let &Mode::View = self.editor.get_buf().mode() {
-                    self.report_readonly();
+                if self.editor.get_buf().is_table_view() {
+                    self.move_to_cell(true);
                     break 'edit_event;
                 }
 
-                if self.editor.get_buf().is_in_select_mode() {
-                    let (from, to) = self.get_select_region();
-                    let msg = self.editor.get_buf().create_remove_msg_region(from, to, &config);
+                if !self.check_editable() {
+                    break 'edit_event;
+                }
 
-                    Pos(self.cx, self.cy) = self.editor.get_buf_mut().remove_rows(from, msg, &config);
+                let start = pos!(self);
+                if self.editor.view().is_in_select_mode() {
+                    self.replace_selection(vec![Row::from_chars(String::from('\t'), &config)]);
+                } else {
+                    self.insert_char('\t');
                 }
+                self.last_edit.record(Edit::Type('\t'.to_string()), start, pos!(self));
+            }
 
-                self.insert_char('\t');
+            // Shift+Tab (move to previous cell in table view)
+            KeyEvent {
+                code: KeyCode::BackTab,
+                ..
+            } => {
+                if self.editor.get_buf().is_table_view() {
+                    self.move_to_cell(false);
+                }
             }
 
             // Any other character with nothing or with Shift (write it)
@@ -1247,93 +2847,754 @@ impl Screen {
                 modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT, 
                 .. 
             } => 'edit_event: {
-                if let &Mode::View = self.editor.get_buf().mode() {
-                    self.report_readonly();
+                if !self.check_editable() {
                     break 'edit_event;
                 }
 
-                if self.editor.get_buf().is_in_select_mode() {
-                    let (from, to) = self.get_select_region();
-                    let msg = self.editor.get_buf().create_remove_msg_region(from, to, &config);
+                let start = pos!(self);
+                let mut typed = ch.to_string();
+                if self.editor.view().is_in_select_mode() {
+                    self.replace_selection(vec![Row::from_chars(ch.to_string(), &config)]);
+                } else if is_sep(ch) {
+                    self.expand_abbreviation(|screen| typed = screen.type_char(ch));
+                } else {
+                    typed = self.type_char(ch);
+                }
 
-                    Pos(self.cx, self.cy) = self.editor.get_buf_mut().remove_rows(from, msg, &config)
+                let is_pair = typed.len() > ch.len_utf8();
+                self.last_edit.record(Edit::Type(typed), start, pos!(self));
+                // The cursor is left inside the pair, so what is typed next doesn't carry on from the end of it
+                if is_pair {
+                    self.last_edit.finish();
                 }
-                
-                self.insert_char(ch);
             }
 
-            // Escape (do nothing; catch so that they can't accidentally enter an ANSI code)
+            // Escape (dismisses the status message; also caught so that they can't accidentally enter an ANSI code)
             KeyEvent {
                 code: KeyCode::Esc,
                 modifiers: KeyModifiers::NONE,
                 .. 
-            } => { }
+            } => self.status.dismiss(),
+
+            _ => ()
+        }
+
+        // Show the diagnostic for the line the cursor moved onto
+        if self.cy != prev_cy {
+            let diagnostic = self.editor.get_buf().diagnostic_at(self.cy).map(|diagnostic| (diagnostic.msg().to_owned(), diagnostic.severity()));
+            match diagnostic {
+                Some((msg, Severity::Error)) => self.set_status_error(msg),
+                Some((msg, Severity::Warning)) => self.set_status_warning(msg),
+                None => ()
+            }
+        }
+
+        self.editor.set_close_times(config.close_times());
+
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Renders the lines of the welcome screen (after the `~` that starts each line), centered on the screen. Only the first line is shown if they don't all fit.
+    fn welcome_lines(&self) -> Vec<String> {
+        let welcome = self.config.welcome();
+        if !welcome.is_enabled() {
+            return vec![];
+        }
+
+        let theme = self.config.theme();
+        let width = cmp::min(welcome.width(), self.screen_cols.saturating_sub(1));
+        let px = (self.screen_cols.saturating_sub(width) / 2).saturating_sub(1);
+
+        let mut lines = welcome.lines().iter().map(|line| {
+            let (style, mut text) = match line {
+                WelcomeLine::Title(title) => (theme.title().to_string(), title.clone()),
+                WelcomeLine::Shortcut(action, keys) => {
+                    let gap = width.saturating_sub(action.width() + keys.width());
+                    (String::new(), format!("{action}{}{keys}", " ".repeat(gap)))
+                }
+                WelcomeLine::Blank => (String::new(), String::new())
+            };
+            util::truncate_to_width(&mut text, width);
+
+            format!("{}{style}{text}", " ".repeat(px))
+        }).collect::<Vec<_>>();
+
+        if self.screen_rows / 3 + lines.len() > self.text_rows() {
+            lines.truncate(1);
+        }
+
+        lines
+    }
+
+    pub fn keybinds_help_text(&self) -> String {
+        let dim = format!("\x1b[38;2;{}m", self.config.theme().superdim());
+        let undim = self.config.theme().normal();
+
+        let mut text = "\
+\x1b[1mKEYBINDS HELP\x1b[22m
+
+\x1b[4mKeybind\x1b[24m             \x1b[4mAction\x1b[24m".to_owned();
+
+        for keybind in KEYBINDS {
+            let dashes = "-".repeat(18_usize.saturating_sub(keybind.keys().len()));
+            let _ = write!(text, "\n{} {dim}{dashes}{undim} {}", keybind.keys(), keybind.action());
+        }
+
+        text
+    }
+
+    pub fn open_keybind_buf(&mut self) -> error::Result<()> {
+        self.editor.append_buf(TextBuffer::from_text(&self.keybinds_help_text(), true, &self.config));
+        self.editor.set_current_buf(self.editor.bufs().len() - 1);
+
+        self.cx = 0;
+        self.cy = 0;
+
+        self.refresh()
+    }
+
+    /// Moves the cursor to where the user asked to start from on the command line (eg. `+45`).
+    pub fn start_at(&mut self, start: &Start) {
+        match start {
+            Start::Line(line) => Pos(self.cx, self.cy) = self.editor.get_buf().pos_at_line_col(*line, 1),
+            Start::Search(pattern) => {
+                let found = self.editor
+                    .get_buf()
+                    .rows()
+                    .iter()
+                    .enumerate()
+                    .find_map(|(y, row)| row.chars().find(pattern.as_str()).map(|x| Pos(x, y)));
+
+                match found {
+                    Some(pos) => Pos(self.cx, self.cy) = pos,
+                    None => self.set_status_msg(format!("Pattern not found: {pattern}"))
+                }
+            }
+        }
+    }
+
+    /// Asks what to do with any unsaved changes before quitting: saving them all, discarding them all, or deciding for each file in turn. Returns whether to quit.
+    fn confirm_quit(&mut self) -> error::Result<bool> {
+        let dirty = self.editor
+            .unique_bufs()
+            .filter(|(_, view)| view.buf().is_dirty())
+            .map(|(idx, view)| (idx, view.buf().display_name().to_owned()))
+            .collect::<Vec<_>>();
+
+        if dirty.is_empty() {
+            return Ok(true);
+        }
+
+        let names = dirty.iter().map(|(_, name)| name.as_str()).collect::<Vec<_>>().join(", ");
+        let msg = format!("Unsaved changes in {names}. Save all, discard all, decide for each, or cancel? (s/d/e/C) ");
+        let res = self.prompt_maybe_empty(&msg)?.map(|s| s.trim().to_lowercase());
+
+        match res.as_deref() {
+            Some("s") => self.save_bufs(&dirty),
+            Some("d") => Ok(true),
+            Some("e") => {
+                for (idx, name) in dirty {
+                    // Show each file while deciding what to do with it
+                    self.switch_to_buf(idx);
+
+                    let msg = format!("Save changes to '{name}'? (y/n/C) ");
+                    let res = self.prompt_maybe_empty(&msg)?.map(|s| s.trim().to_lowercase());
+
+                    match res.as_deref() {
+                        Some("y") if !self.save_bufs(&[(idx, name)])? => return Ok(false),
+                        Some("y" | "n") => (),
+                        _ => {
+                            self.set_status_msg("Cancelled".to_owned());
+                            return Ok(false);
+                        }
+                    }
+                }
+
+                Ok(true)
+            }
+            _ => {
+                self.set_status_msg("Cancelled".to_owned());
+                Ok(false)
+            }
+        }
+    }
+
+    /// Saves each of the buffers at the given indices, stopping at the first one that isn't saved (eg. if no file name is given for it). Returns whether they all were.
+    fn save_bufs(&mut self, bufs: &[(usize, String)]) -> error::Result<bool> {
+        for (idx, name) in bufs {
+            self.switch_to_buf(*idx);
+
+            if let Err(err) = self.save() {
+                self.set_status_error(format!("Could not save '{name}': {err}"));
+                return Ok(false);
+            }
+            if self.editor.get_buf().is_dirty() {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Closes every buffer, or every buffer except the current one if `keep_current` is `true`. If any of the buffers to close have unsaved changes, the user is asked once to confirm, with the names of those files listed.
+    fn close_bufs(&mut self, keep_current: bool) -> error::Result<()> {
+        let current = self.editor.current_buf();
+        let dirty = self.editor
+            .unique_bufs()
+            .filter(|(_, view)| view.buf().is_dirty() && !(keep_current && view.shares_buf(self.editor.view())))
+            .map(|(_, view)| view.buf().display_name().to_owned())
+            .collect::<Vec<_>>();
+
+        if !dirty.is_empty() {
+            let msg = format!("Unsaved changes in {}. Close anyway? (y/N) ", dirty.join(", "));
+            let res = self.prompt(&msg, &|_, _, _| { })?;
+
+            if !matches!(res, Some(s) if s.to_lowercase() == "y") {
+                self.set_status_msg("Cancelled".to_owned());
+                return Ok(());
+            }
+        }
+
+        let num_closed;
+        if keep_current {
+            let bufs = self.editor.bufs_mut();
+            num_closed = bufs.len() - 1;
+
+            let buf = bufs.swap_remove(current);
+            bufs.clear();
+            bufs.push(buf);
+        } else {
+            num_closed = self.editor.num_bufs();
+
+            self.editor.bufs_mut().clear();
+            self.editor.append_buf(TextBuffer::new(self.config.readonly()));
+            self.cx = 0;
+            self.cy = 0;
+        }
+        self.editor.set_current_buf(0);
+
+        self.set_status_msg(format!("Closed {num_closed} tab{}", if num_closed == 1 { "" } else { "s" }));
+
+        Ok(())
+    }
+
+    /// Switches to the tab at `idx`, saving the cursor and scroll position of the current tab so that they are restored when switching back.
+    pub fn switch_to_buf(&mut self, idx: usize) {
+        let prev_id = self.editor.get_buf().id();
+        let (prev_cy, prev_row_offset) = (self.cy, self.row_offset);
+
+        self.save_view();
+        self.editor.set_current_buf(idx);
+        self.restore_view();
+        self.sync_diff_pane(prev_id, prev_cy, prev_row_offset);
+    }
+
+    /// Saves the cursor and scroll position in the current tab's view.
+    fn save_view(&mut self) {
+        let view = self.editor.view_mut();
+        view.set_cursor_pos(Pos(self.cx, self.cy));
+        view.set_offset(Pos(self.col_offset, self.row_offset));
+    }
+
+    /// Moves the cursor and scroll position to where they were saved in the current tab's view.
+    fn restore_view(&mut self) {
+        let view = self.editor.view();
+        Pos(self.cx, self.cy) = view.saved_cursor_pos();
+        Pos(self.col_offset, self.row_offset) = view.saved_offset();
+
+        self.update_config();
+    }
+
+    /// Gets the config to use for `buf`, which has its own tab stop if its modeline sets one.
+    fn config_for(&self, buf: &TextBuffer) -> Rc<Config> {
+        match buf.tab_stop() {
+            Some(tab_stop) if tab_stop != self.base_config.tab_stop() => Rc::new(self.base_config.with_tab_stop(tab_stop)),
+            _ => Rc::clone(&self.base_config)
+        }
+    }
+
+    /// Switches to the config for the current buffer, if it is different from the one in use.
+    fn update_config(&mut self) {
+        let tab_stop = self.editor.get_buf().tab_stop().unwrap_or(self.base_config.tab_stop());
+
+        if tab_stop != self.config.tab_stop() {
+            self.config = self.config_for(&self.editor.get_buf());
+        }
+    }
+
+    /// Whether rows that don't fit on the screen are wrapped onto the next lines in the current view (see [`ViewOptions`]).
+    fn soft_wrap(&self) -> bool {
+        self.editor.view().options().soft_wrap(&self.config)
+    }
+
+    /// Turns one of the current view's options (see [`ViewOptions`]) on or off, without changing it in any other view.
+    fn toggle_view_option(&mut self, name: &str, is_on: fn(&ViewOptions, &Config) -> bool, set: fn(&mut ViewOptions, bool)) {
+        let is_on = !is_on(self.editor.view().options(), &self.config);
+        set(self.editor.view_mut().options_mut(), is_on);
+
+        self.col_start = self.calc_col_start();
+        self.scroll();
+        self.set_status_msg(format!("{name} {} in this tab", if is_on { "on" } else { "off" }));
+    }
+
+    /// Opens another view of the current buffer in a new tab, so that two parts of it can be looked at (and edited) at once.
+    pub fn split_buf(&mut self) {
+        if self.editor.view().is_in_select_mode() {
+            self.exit_select_mode();
+        }
+
+        self.save_view();
+        self.editor.split_buf();
+        self.restore_view();
+
+        self.set_status_msg(format!("Opened another view of '{}'", self.editor.get_buf().display_name()));
+    }
+
+    /// Opens the URL that the cursor is on (or just after) in the system's browser.
+    pub fn open_url(&mut self) {
+        let url = self.editor.get_buf().rows().get(self.cy).and_then(|row| {
+            let chars = row.chars();
+
+            url::find_urls(chars)
+                .into_iter()
+                .find(|range| (range.start..=range.end).contains(&self.cx))
+                .map(|range| chars[range].to_owned())
+        });
+
+        let Some(url) = url else {
+            self.set_status_msg("There is no URL under the cursor".to_owned());
+            self.alert();
+            return;
+        };
+
+        match url::open(&url) {
+            Ok(()) => self.set_status_msg(format!("Opened {url}")),
+            Err(err) => {
+                log::warn(&format!("Could not open '{url}': {err}"));
+                self.set_status_error(format!("Could not open '{url}': {err}"));
+            }
+        }
+    }
+
+    /// Opens the file at `path` in a new tab, and switches to it. Returns whether it could be opened.
+    pub fn open_file(&mut self, path: &str) -> bool {
+        let mut buf = TextBuffer::new(self.config.readonly());
+        if let Err(err) = buf.open(path, &self.base_config) {
+            log::warn(&format!("Could not open '{path}': {err}"));
+            self.set_status_error(format!("Could not open '{path}': {err}"));
+            return false;
+        }
+
+        self.add_buf(buf);
+        true
+    }
+
+    /// Opens `text` (eg. from stdin) as an unnamed file in a new tab, and switches to it.
+    pub fn open_text(&mut self, text: &str) {
+        let buf = TextBuffer::from_text(text, self.config.readonly(), &self.base_config);
+        self.add_buf(buf);
+    }
+
+    /// Adds `buf` as a new tab, and switches to it.
+    fn add_buf(&mut self, buf: TextBuffer) {
+        // When there is only 1 empty buffer in the editor, replace that buffer instead of creating a new one
+        if self.editor.num_bufs() == 1 && self.editor.get_buf().num_rows() == 0 {
+            self.editor.remove_buf(0);
+        }
+
+        self.editor.append_buf(buf);
+        self.editor.set_current_buf(self.editor.bufs().len() - 1);
+
+        self.cx = 0;
+        self.cy = 0;
+    }
+
+    /// Starts comparing the first two open files, marking the lines that differ between them.
+    pub fn start_diff(&mut self) {
+        let bufs = self.editor.bufs();
+        if bufs.len() < 2 {
+            self.set_status_msg("Need 2 files to compare".to_owned());
+            return;
+        }
+
+        if bufs[0].shares_buf(&bufs[1]) {
+            self.set_status_msg("Tabs 1 and 2 have the same buffer open".to_owned());
+            return;
+        }
+
+        self.diff_view = Some(DiffView::new(bufs[0].buf().id(), bufs[1].buf().id()));
+        for view in &bufs[..2] {
+            view.buf_mut().load_all_rows(&self.config);
+        }
+        self.update_diff();
+        self.report_differences();
+    }
+
+    /// Shows the current buffer side by side with the file in another tab, marking the lines that differ between them. If there are more than 2 tabs, the user is asked which one. Turns it off if it is already on.
+    pub fn toggle_diff_pane(&mut self) -> error::Result<()> {
+        if self.is_comparing() {
+            self.show_diff_pane = false;
+            self.set_status_msg("Side by side comparison off".to_owned());
+            return Ok(());
+        }
+
+        let num_bufs = self.editor.num_bufs();
+        let current = self.editor.current_buf();
+        let idx = match num_bufs {
+            0 | 1 => {
+                self.set_status_msg("Need 2 files to compare".to_owned());
+                self.alert();
+                return Ok(());
+            }
+            2 => 1 - current,
+            _ => {
+                let res = self.prompt(&format!("Compare with tab (1-{num_bufs}, ESC to cancel): "), &|_, _, _| { })?;
+                let res = match res {
+                    Some(res) => res,
+                    None => {
+                        self.set_status_msg("Cancelled".to_owned());
+                        return Ok(());
+                    }
+                };
+
+                match res.trim().parse::<usize>() {
+                    Ok(n) if (1..=num_bufs).contains(&n) => n - 1,
+                    _ => {
+                        self.set_status_msg(format!("There is no tab {}", res.trim()));
+                        self.alert();
+                        return Ok(());
+                    }
+                }
+            }
+        };
+
+        let bufs = self.editor.bufs();
+        if bufs[idx].shares_buf(&bufs[current]) {
+            self.set_status_msg(format!("Tab {} has the same buffer open", idx + 1));
+            self.alert();
+            return Ok(());
+        }
+
+        // The other file is treated as the old one, so the lines of this one are marked as added
+        self.diff_view = Some(DiffView::new(bufs[idx].buf().id(), bufs[current].buf().id()));
+        self.show_diff_pane = true;
+        for i in [idx, current] {
+            self.editor.bufs()[i].buf_mut().load_all_rows(&self.config);
+        }
+        self.update_diff();
+        self.report_differences();
+
+        Ok(())
+    }
+
+    /// Shows how many differences were found between the files being compared.
+    fn report_differences(&mut self) {
+        if let Some(diff_view) = &self.diff_view {
+            let n = diff_view.hunks().len();
+            self.set_status_msg(format!("{n} difference{} found. Press ALT+N/ALT+P to jump between them", if n == 1 { "" } else { "s" }));
+        }
+    }
+
+    /// After switching tabs away from the buffer with `prev_id`, moves the cursor and scroll position to the lines that line up with the ones it had (`prev_cy` and `prev_row_offset`), if the current buffer is the file that it is being compared with side by side. This keeps the two scrolled together.
+    fn sync_diff_pane(&mut self, prev_id: usize, prev_cy: usize, prev_row_offset: usize) {
+        let diff_view = match &self.diff_view {
+            Some(diff_view) if self.show_diff_pane => diff_view,
+            _ => return
+        };
+
+        let buf = self.editor.get_buf();
+        if diff_view.other_id(prev_id) != Some(buf.id()) {
+            return;
+        }
+
+        // Lines with nothing to line up with (eg. added ones) go to the nearest line before them that does
+        let line_up = |row: usize| (0..=row)
+            .rev()
+            .find_map(|r| diff_view.counterpart(prev_id, r))
+            .unwrap_or(0);
+
+        let Pos(cx, cy) = buf.clamp_pos(Pos(self.cx, line_up(prev_cy)));
+        let row_offset = cmp::min(line_up(prev_row_offset), cy);
+
+        (self.cx, self.cy, self.row_offset) = (cx, cy, row_offset);
+    }
+
+    /// Diffs the files being compared again soon, once the user stops editing for a moment (see [`Screen::DIFF_DELAY`]).
+    fn schedule_diff(&mut self) {
+        if self.diff_view.is_some() {
+            self.diff_due = Some(Instant::now() + Self::DIFF_DELAY);
+        }
+    }
+
+    /// Diffs the files being compared again, to keep up with any edits. Diff mode ends if either file was closed.
+    pub fn update_diff(&mut self) {
+        self.diff_due = None;
+
+        let diff_view = match self.diff_view.as_mut() {
+            Some(diff_view) => diff_view,
+            None => return
+        };
+
+        let find = |id: usize| self.editor
+            .bufs()
+            .iter()
+            .find(|view| view.buf().id() == id)
+            .map(View::buf);
+
+        match (find(diff_view.old_id()), find(diff_view.new_id())) {
+            (Some(old), Some(new)) => diff_view.update(
+                &old.rows().iter().map(Row::chars).collect::<Vec<_>>(), 
+                &new.rows().iter().map(Row::chars).collect::<Vec<_>>()
+            ),
+            _ => {
+                self.diff_view = None;
+                self.show_diff_pane = false;
+            }
+        }
+    }
+
+    /// Moves the cursor to the start of the next (or previous) difference in the current file, when in diff mode.
+    pub fn jump_to_hunk(&mut self, is_forward: bool) {
+        // The hunks may not have caught up with the latest edits yet
+        if self.diff_due.is_some() {
+            self.update_diff();
+        }
+
+        let starts = match &self.diff_view {
+            Some(diff_view) => diff_view.hunk_starts(self.editor.get_buf().id()),
+            None => None
+        };
+
+        let starts = match starts {
+            Some(starts) => starts,
+            None => {
+                self.set_status_msg("Not comparing this file. Use CTRL+ALT+D or --diff to compare it with another".to_owned());
+                return;
+            }
+        };
+
+        let target = if is_forward {
+            starts.iter().find(|&&row| row > self.cy)
+        } else {
+            starts.iter().rev().find(|&&row| row < self.cy)
+        };
+
+        match target {
+            Some(&row) => {
+                let num_rows = self.editor.get_buf().num_rows();
+                self.cy = cmp::min(row, num_rows.saturating_sub(1));
+                self.cx = 0;
+            }
+            None => self.set_status_msg(format!("No more differences {}", if is_forward { "below" } else { "above" }))
+        }
+    }
+
+    /// Reports to the user that they cannot edit in readonly mode.
+    pub fn report_readonly(&mut self) {
+        self.set_status_msg(String::from("Cannot edit in readonly mode."));
+        self.alert();
+    }
+
+    /// Alerts the user that something couldn't be done, in the way set by the config. The alert is only shown briefly.
+    pub fn alert(&mut self) {
+        if self.config.alert() != Alert::None {
+            self.alert_until = Some(Instant::now() + Self::ALERT_LIFE);
+        }
+    }
+
+    fn is_alerting(&self) -> bool {
+        self.alert_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    /// Checks whether the current buffer can be edited, telling the user why if it can't.
+    /// 
+    /// The first edit to a write-protected file is also stopped, with a warning that the changes won't be able to be saved. Any edits after that are let through.
+    pub fn check_editable(&mut self) -> bool {
+        if self.editor.get_buf().is_table_view() {
+            self.set_status_msg("Cannot edit in table view (CTRL+T to turn it off)".to_owned());
+            self.alert();
+            false
+        } else if !self.editor.get_buf().is_editable() {
+            self.report_readonly();
+            false
+        } else if self.editor.get_buf().load_progress().is_some() {
+            self.set_status_msg("Wait for the file to finish loading before editing it".to_owned());
+            false
+        } else if self.editor.get_buf().is_write_protected() && !self.editor.get_buf().is_write_protect_warned() {
+            self.editor.get_buf_mut().set_write_protect_warned();
+            self.set_status_warning(String::from("This file is write-protected, so changes cannot be saved to it. Edit again to continue anyway."));
+            self.alert();
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Asks for the line ending to save the current buffer with, converting it if it's different.
+    pub fn change_line_ending(&mut self) -> error::Result<()> {
+        if !self.check_editable() {
+            return Ok(());
+        }
+
+        let current = self.editor.get_buf().line_ending();
+        let res = self.prompt(&format!("Convert line endings from {} to (LF/CRLF): ", current.name()), &|_, _, _| { })?;
+        let name = match res {
+            Some(name) => name,
+            None => {
+                self.set_status_msg("Cancelled".to_owned());
+                return Ok(());
+            }
+        };
+
+        let msg = match LineEnding::from_name(name.trim()) {
+            Some(line_ending) if line_ending == current => format!("Line endings are already {}", current.name()),
+            Some(line_ending) => {
+                self.editor.get_buf_mut().set_line_ending(line_ending);
+                format!("Line endings converted to {}", line_ending.name())
+            }
+            None => format!("Unknown line ending '{}' (expected LF or CRLF)", name.trim())
+        };
+        self.set_status_msg(msg);
+
+        Ok(())
+    }
+
+    /// Reloads the current buffer from its file, discarding any unsaved changes after the user confirms.
+    pub fn revert(&mut self) -> error::Result<()> {
+        if self.editor.get_buf().file_name().is_empty() {
+            self.set_status_msg("File has never been saved, so there is nothing to revert to".to_owned());
+            return Ok(());
+        }
+
+        if self.editor.get_buf().is_dirty() {
+            let res = self.prompt("Discard all unsaved changes? (y/N) ", &|_, _, _| { })?;
+
+            if !matches!(res, Some(s) if s.to_lowercase() == "y") {
+                self.set_status_msg("Cancelled".to_owned());
+                return Ok(());
+            }
+        }
+
+        if self.editor.view().is_in_select_mode() {
+            self.exit_select_mode();
+        }
+
+        let config = Rc::clone(&self.base_config);
+        let res = self.editor.get_buf_mut().revert(&config);
+        if let Err(err) = res {
+            let path = self.editor.get_buf().file_name().to_owned();
+            log::warn(&format!("Could not revert '{path}': {err}"));
+            self.set_status_error(format!("Could not revert '{path}': {err}"));
+            return Ok(());
+        }
+
+        let num_rows = self.editor.get_buf().num_rows();
+        self.cy = self.cy.min(num_rows);
+        self.cx = if self.cy < num_rows { self.cx.min(self.editor.get_buf().row_at(self.cy).size()) } else { 0 };
+        self.update_config();
+
+        self.set_status_msg("Reverted to the saved file".to_owned());
+
+        Ok(())
+    }
 
-            _ => ()
+    /// Writes the selected text to a file, with the buffer's line endings, without changing the buffer or the clipboard.
+    fn write_selection(&mut self) -> error::Result<()> {
+        if !self.editor.view().is_in_select_mode() {
+            self.set_status_msg("Select the text to write first".to_owned());
+            self.alert();
+            return Ok(());
         }
 
-        self.editor.set_quit_times(config.quit_times());
-        self.editor.set_close_times(config.close_times());
+        let path = match self.prompt_path("Write selection to file (ESC to cancel): ")? {
+            Some(path) => path,
+            None => {
+                self.set_status_msg("Cancelled".to_owned());
+                return Ok(());
+            }
+        };
 
-        Ok(self)
-    }
+        if Path::new(&path).exists() {
+            let res = self.prompt(&format!("'{path}' already exists. Overwrite it? (y/N) "), &|_, _, _| { })?;
 
-    pub fn keybinds_help_text(&self) -> String {
-        format!("\
-\x1b[1mKEYBINDS HELP\x1b[22m
+            if !matches!(res, Some(s) if s.to_lowercase() == "y") {
+                self.set_status_msg("Cancelled".to_owned());
+                return Ok(());
+            }
+        }
+
+        let (from, to) = self.get_select_region();
+        let text = self.get_region_chars(from, to).join(self.editor.get_buf().line_ending().as_str());
+
+        match fs::write(&path, &text) {
+            Ok(()) => self.set_status_msg(format!("{} bytes written to '{path}'", text.len())),
+            Err(err) => {
+                self.set_status_error(format!("Could not write to '{path}': {}", Error::from(err)));
+                self.alert();
+            }
+        }
 
-\x1b[4mKeybind\x1b[24m             \x1b[4mAction\x1b[24m
-CTRL + Q {dim}----------{undim} Quit Mino Editor
-CTRL + W {dim}----------{undim} Close Current Tab
-CTRL + N {dim}----------{undim} Create New File
-CTRL + O {dim}----------{undim} Open File
-CTRL + S {dim}----------{undim} Save File
-CTRL + SHIFT + S {dim}--{undim} Rename & Save File (Save As)
-CTRL + F {dim}----------{undim} Find Text
-CTRL + R {dim}----------{undim} Rename File
-CTRL + SHIFT + R {dim}--{undim} Reload Editor (\x1b[3min case of visual bug\x1b[23m)
-CTRL + A {dim}----------{undim} Select Entire File
-CTRL + C {dim}----------{undim} Copy Selection To Clipboard
-CTRL + V {dim}----------{undim} Paste From Clipboard
-CTRL + Z {dim}----------{undim} Undo
-CTRL + Y {dim}----------{undim} Redo
-CTRL + Tab {dim}--------{undim} Go To Next Tab
-CTRL + ? {dim}----------{undim} Open This Help Page
-CTRL + SHIFT + / {dim}--{undim} Open This Help Page", 
-        dim=format!("\x1b[38;2;{}m", self.config.theme().superdim()), undim=self.config.theme().normal())
+        Ok(())
     }
 
-    pub fn open_keybind_buf(&mut self) -> error::Result<()> {
-        self.editor.append_buf(TextBuffer::from_text(&self.keybinds_help_text(), true));
-        self.editor.set_current_buf(self.editor.bufs().len() - 1);
+    /// Exports the current buffer to an HTML file at a path the user enters, drawn with the theme's colors and font styles (see [`html::render`]).
+    fn export_html(&mut self) -> error::Result<()> {
+        let path = match self.prompt_path("Export to HTML file (ESC to cancel): ")? {
+            Some(path) => path,
+            None => {
+                self.set_status_msg("Cancelled".to_owned());
+                return Ok(());
+            }
+        };
 
-        self.cx = 0;
-        self.cy = 0;
+        if Path::new(&path).exists() {
+            let res = self.prompt(&format!("'{path}' already exists. Overwrite it? (y/N) "), &|_, _, _| { })?;
 
-        self.refresh()
-    }
+            if !matches!(res, Some(s) if s.to_lowercase() == "y") {
+                self.set_status_msg("Cancelled".to_owned());
+                return Ok(());
+            }
+        }
 
-    /// Reports to the user that they cannot edit in readonly mode.
-    pub fn report_readonly(&mut self) {
-        self.set_status_msg(String::from("Cannot edit in readonly mode."));
+        let config = Rc::clone(&self.config);
+        let res = html::export(&mut self.editor.get_buf_mut(), &path, &config);
+        match res {
+            Ok(()) => self.set_status_msg(format!("Exported to '{path}'")),
+            Err(err) => {
+                self.set_status_error(format!("Could not export to '{path}': {}", Error::from(err)));
+                self.alert();
+            }
+        }
+
+        Ok(())
     }
 
     pub fn undo(&mut self) {
-        Pos(self.cx, self.cy) = match self.editor.get_buf_mut().undo(&self.config) {
+        let cpos = self.editor.get_buf_mut().undo(&self.config);
+        Pos(self.cx, self.cy) = match cpos {
             Some(cpos) => cpos,
-            None => return
+            None => {
+                self.set_status_msg("Nothing to undo".to_owned());
+                self.alert();
+                return;
+            }
         };
     }
 
     pub fn redo(&mut self) {
-        Pos(self.cx, self.cy) = match self.editor.get_buf_mut().redo(&self.config) {
+        let cpos = self.editor.get_buf_mut().redo(&self.config);
+        Pos(self.cx, self.cy) = match cpos {
             Some(cpos) => cpos,
-            None => return
+            None => {
+                self.set_status_msg("Nothing to redo".to_owned());
+                self.alert();
+                return;
+            }
         }
     }
 
     pub fn copy(&mut self) {
-        if !self.editor.get_buf().is_in_select_mode() {
+        if !self.editor.view().is_in_select_mode() {
             return;
         }
 
@@ -1342,128 +3603,293 @@ CTRL + SHIFT + / {dim}--{undim} Open This Help Page",
         self.editor.clipboard_mut().save_context(&context[..]);
     }
 
+    /// Pastes the clipboard's contents at the cursor, replacing the selection if there is one.
     pub fn paste(&mut self) {
-        let syntax = self.editor.get_buf().syntax();
-
-        let rows: Vec<Row> = self.editor.clipboard()
+        let text = self.editor.clipboard()
             .load_context()
-            .into_iter()
-            .map(|s| Row::from_chars(s, &self.config, syntax))
-            .collect();
+            .join("\n");
 
-        Pos(self.cx, self.cy) = self.editor.get_buf_mut().insert_rows(pos!(self), rows, &self.config);
+        self.paste_text(&text);
     }
 
-    pub fn enter_select_mode(&mut self) {
-        self.editor.get_buf_mut().set_anchor(Some(pos!(self)));
-        self.editor.get_buf_mut().enter_select_mode();
+    /// Pastes the primary selection's contents at the cursor, replacing the selection if there is one.
+    #[cfg(target_os = "linux")]
+    pub fn paste_primary(&mut self) {
+        match self.editor.clipboard().load_primary() {
+            Some(rows) => self.paste_text(&rows.join("\n")),
+            None => self.set_status_msg(String::from("Couldn't read the primary selection (needs wl-paste, xclip or xsel)"))
+        }
     }
 
-    pub fn exit_select_mode(&mut self) {
-        let anchor_y = if let Some(anchor) = self.editor.get_buf().select_anchor() {
-            anchor.y()
-        } else {
-            self.editor.get_buf_mut().exit_select_mode();
-            return;
-        };
-        let cpos_y = pos!(self).y();
+    /// Pastes the primary selection's contents at the cursor, which only exists on Linux.
+    #[cfg(not(target_os = "linux"))]
+    pub fn paste_primary(&mut self) {
+        self.set_status_msg(String::from("There is no primary selection on this platform"));
+    }
 
-        let syntax = self.editor.get_buf().syntax();
-        for y in 
-            cmp::min(anchor_y, cpos_y)..=
-            cmp::max(anchor_y, cpos_y)
-        {
-            self.editor.get_buf_mut().rows_mut()[y].update_highlight(syntax);
-        }
+    /// Pastes `text` at the cursor (see [`Screen::insert_text`]), keeping it as the last edit so that it can be repeated. It isn't kept if the buffer couldn't be edited.
+    fn paste_text(&mut self, text: &str) {
+        let start = pos!(self);
+        let changes = self.editor.get_buf().changes();
+        self.insert_text(text);
 
-        self.editor.get_buf_mut().exit_select_mode();
+        if self.editor.get_buf().changes() != changes {
+            self.last_edit.record(Edit::Paste(text.to_owned()), start, pos!(self));
+        }
     }
 
-    pub fn select(&mut self) {
-        let anchor = if let Some(a) = self.editor.get_buf().select_anchor() {
-            *a
-        } else {
-            return;
+    /// Makes the last edit (see [`LastEdit`]) again at the cursor, as a single edit.
+    pub fn repeat_edit(&mut self) {
+        let edit = match self.last_edit.edit() {
+            Some(edit) => edit.clone(),
+            None => {
+                self.set_status_msg("No edit to repeat".to_owned());
+                return;
+            }
         };
-        let cpos = pos!(self);
 
-        // Equal (shouldn't happen?)
-        if anchor == cpos {
+        if !self.check_editable() {
             return;
         }
 
-        // Same line
-        if anchor.y() == cpos.y() {
-            let start;
-            let end;
-            
-            // anchor ... cursor
-            if anchor < cpos {
-                start = anchor.x();
-                end = cpos.x();
-            // cursor ... anchor
-            } else {
-                start = cpos.x();
-                end = anchor.x();
+        match edit {
+            Edit::Type(text) | Edit::Paste(text) => self.insert_text(&text),
+            Edit::Remove { count, is_delete } => {
+                if self.editor.view().is_in_select_mode() {
+                    self.exit_select_mode();
+                }
+
+                self.editor.get_buf_mut().history_mut().begin_group();
+                for _ in 0..count {
+                    self.remove_char(is_delete);
+                }
+                self.editor.get_buf_mut().history_mut().end_group();
             }
+        }
 
-            let hl = self.get_row_mut().hl_mut();
+        self.last_edit.finish();
+    }
 
-            for i in start..end {
-                hl[i].set_select_hl(SelectHighlight::Select);
-            }
-        // Anchor then cursor
-        } else if anchor.y() < cpos.y() {
-            // anchor .. \n
-            let row = &mut self.editor.get_buf_mut().rows_mut()[anchor.y()];
-            for i in anchor.x()..row.rsize() {
-                row.hl_mut()[i].set_select_hl(SelectHighlight::Select);
-            }
+    /// Inserts `text` (eg. from a bracketed paste, or committed by an IME) at the cursor as a single edit, replacing the selection if there is one.
+    pub fn insert_text(&mut self, text: &str) {
+        if !self.check_editable() {
+            return;
+        }
+
+        // Terminals send newlines in pastes as carriage returns
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        let rows = text
+            .split('\n')
+            .map(|l| Row::from_chars(l.to_owned(), &self.config))
+            .collect();
+
+        if self.editor.view().is_in_select_mode() {
+            self.replace_selection(rows);
+        } else {
+            Pos(self.cx, self.cy) = self.editor.get_buf_mut().insert_rows(pos!(self), rows, pos!(self), &self.config);
+        }
+    }
+
+    /// Replaces the selected text with `rows`, as a single edit, leaving the cursor after them and ending the selection.
+    fn replace_selection(&mut self, rows: Vec<Row>) {
+        let (from, to) = self.get_select_region();
+        self.replace_region(from, to, rows);
+        self.exit_select_mode();
+    }
 
-            // ... \n ... \n
-            for y in anchor.y()+1..cpos.y() {
-                let hls = self.editor.get_buf_mut().rows_mut()[y].hl_mut();
+    /// Replaces the text from `from` to `to` with `rows`, leaving the cursor after them. Removing the text and inserting the rows are undone as one edit.
+    fn replace_region(&mut self, from: Pos, to: Pos, rows: Vec<Row>) {
+        let mut buf = self.editor.get_buf_mut();
+        let old = buf.create_remove_msg_region(from, to);
 
-                for hl in hls {
-                    hl.set_select_hl(SelectHighlight::Select);
+        buf.history_mut().begin_group();
+        let cursor = buf.remove_rows(from, old, pos!(self), &self.config);
+        Pos(self.cx, self.cy) = buf.insert_rows(from, rows, cursor, &self.config);
+        buf.history_mut().end_group();
+    }
+
+    /// Reformats the JSON in the current selection (or the whole buffer if nothing is selected). It is pretty-printed, unless `minify` is true.
+    pub fn format_json(&mut self, minify: bool) {
+        let num_rows = self.editor.get_buf().num_rows();
+        if num_rows == 0 {
+            return;
+        }
+
+        let (from, to) = if self.editor.view().is_in_select_mode() {
+            self.get_select_region()
+        } else {
+            let last = num_rows - 1;
+            (Pos(0, 0), Pos(self.editor.get_buf().row_at(last).size(), last))
+        };
+
+        let old = self.editor.get_buf().create_remove_msg_region(from, to);
+        let indent = if minify { None } else { Some(self.config.json_indent()) };
+
+        match json::format(&old.join("\n"), indent) {
+            Ok(text) => {
+                if self.editor.view().is_in_select_mode() {
+                    self.exit_select_mode();
                 }
-            }
 
-            // \n .. cursor
-            let row = &mut self.editor.get_buf_mut().rows_mut()[cpos.y()];
-            for i in 0..cpos.x() {
-                row.hl_mut()[i].set_select_hl(SelectHighlight::Select);
+                let rows = text
+                    .lines()
+                    .map(|l| Row::from_chars(l.to_owned(), &self.config))
+                    .collect();
+
+                self.editor.get_buf_mut().replace_rows(from, old, rows, pos!(self), &self.config);
+                self.editor.get_buf_mut().history_mut().set_cursor_after(from);
+                Pos(self.cx, self.cy) = from;
+
+                self.set_status_msg(if minify { "JSON minified" } else { "JSON formatted" }.to_owned());
             }
-        // Cursor then anchor
-        } else if anchor.y() > cpos.y() {
-            // cursor .. \n
-            let row = &mut self.editor.get_buf_mut().rows_mut()[cpos.y()];
-            for i in cpos.x()..row.rsize() {
-                row.hl_mut()[i].set_select_hl(SelectHighlight::Select);
+            Err(err) => {
+                self.set_status_error(format!("Invalid JSON: {err}"));
             }
+        }
+    }
 
-            // ... \n ... \n
-            for y in cpos.y()+1..anchor.y() {
-                let hls = self.editor.get_buf_mut().rows_mut()[y].hl_mut();
-
-                for hl in hls {
-                    hl.set_select_hl(SelectHighlight::Select);
-                }
+    /// Pipes the current buffer through the formatter configured for its language, replacing its contents while keeping the cursor in place.
+    /// 
+    /// Returns whether the formatter changed the buffer, or `None` if there is no formatter for the language (or the buffer is empty).
+    pub fn format_buf(&mut self) -> error::Result<Option<bool>> {
+        let syntax = self.editor.get_buf().syntax();
+        let cmd = match self.config.formatter(syntax.lang()) {
+            Some(cmd) => cmd.to_owned(),
+            None => {
+                self.set_status_msg(format!("No formatter configured for {} files", syntax.name()));
+                return Ok(None);
             }
+        };
+
+        let num_rows = self.editor.get_buf().num_rows();
+        if num_rows == 0 {
+            return Ok(None);
+        }
+
+        let text = TextBuffer::rows_to_string(self.editor.get_buf().rows());
+        let formatted = {
+            let buf = self.editor.get_buf();
+            formatter::run(&cmd, buf.dir(), buf.base_name(), &text)?
+        };
+
+        // The rows are compared rather than the text, as formatters don't always end the output with a newline
+        if formatted.lines().eq(text.lines()) {
+            return Ok(Some(false));
+        }
+
+        if self.editor.view().is_in_select_mode() {
+            self.exit_select_mode();
+        }
+
+        let last = num_rows - 1;
+        let old = self.editor.get_buf().create_remove_msg_region(Pos(0, 0), Pos(self.editor.get_buf().row_at(last).size(), last));
+        let rows = formatted
+            .lines()
+            .map(|l| Row::from_chars(l.to_owned(), &self.config))
+            .collect();
+
+        // Keep the cursor where it was, as long as it's still in the buffer
+        Pos(self.cx, self.cy) = self.editor.get_buf_mut().replace_rows(Pos(0, 0), old, rows, pos!(self), &self.config);
+
+        Ok(Some(true))
+    }
+
+    /// Prompts for text to find and what to replace it with, then replaces every occurrence in the buffer. All of the replacements are undone together.
+    pub fn replace_all(&mut self) -> error::Result<()> {
+        if self.editor.view().is_in_select_mode() {
+            self.exit_select_mode();
+        }
+
+        let find = match self.prompt("Replace (ESC to cancel): ", &|_, _, _| { })? {
+            Some(find) => find,
+            None => return Ok(())
+        };
+
+        let with = match self.prompt_maybe_empty(&format!("Replace '{find}' with (ESC to cancel): "))? {
+            Some(with) => with,
+            None => return Ok(())
+        };
+
+        let counts = self.editor
+            .get_buf()
+            .rows()
+            .iter()
+            .map(|row| row.chars().matches(find.as_str()).count())
+            .collect::<Vec<_>>();
+
+        let num_found = counts.iter().sum::<usize>();
+        if num_found == 0 {
+            self.set_status_msg(format!("No occurrences of '{find}' found"));
+            self.alert();
+            return Ok(());
+        }
+
+        // Only replace the rows from the first to the last that changed, so the rest aren't part of the undo
+        let first = counts.iter().position(|&n| n > 0).unwrap_or(0);
+        let last = counts.iter().rposition(|&n| n > 0).unwrap_or(first);
+        let num_lines = counts.iter().filter(|&&n| n > 0).count();
+
+        let old = self.editor.get_buf().create_remove_msg_region(Pos(0, first), Pos(self.editor.get_buf().row_at(last).size(), last));
+        let rows = self.editor.get_buf().rows()[first..=last]
+            .iter()
+            .map(|row| Row::from_chars(row.chars().replace(find.as_str(), &with), &self.config))
+            .collect();
 
-            // \n .. anchor
-            let row = &mut self.editor.get_buf_mut().rows_mut()[anchor.y()];
-            for i in 0..anchor.x() {
-                row.hl_mut()[i].set_select_hl(SelectHighlight::Select);
+        Pos(self.cx, self.cy) = self.editor.get_buf_mut().replace_rows(Pos(0, first), old, rows, pos!(self), &self.config);
+        // The cursor's row may have changed around it, so it could now be inside a char
+        self.cx = self.editor.get_buf().row_at(self.cy).grapheme_start(self.cx);
+
+        self.set_status_msg(format!(
+            "Replaced {num_found} occurrence{} on {num_lines} line{}",
+            if num_found == 1 { "" } else { "s" },
+            if num_lines == 1 { "" } else { "s" }
+        ));
+
+        Ok(())
+    }
+
+    /// Runs the linter configured for the current buffer's language on its file, replacing the buffer's diagnostics.
+    /// 
+    /// Returns the number of problems found, or `None` if there is no linter for the language (or the buffer has no file).
+    pub fn lint(&mut self) -> error::Result<Option<usize>> {
+        let syntax = self.editor.get_buf().syntax();
+        let cmd = match self.config.linter(syntax.lang()) {
+            Some(cmd) => cmd.to_owned(),
+            None => {
+                self.set_status_msg(format!("No linter configured for {} files", syntax.name()));
+                return Ok(None);
             }
+        };
+
+        if self.editor.get_buf().file_name().is_empty() {
+            self.set_status_msg("Save the file before linting it".to_owned());
+            return Ok(None);
         }
+
+        let diagnostics = {
+            let buf = self.editor.get_buf();
+            lint::run(&cmd, buf.dir(), buf.base_name())?
+        };
+        let num_problems = diagnostics.len();
+        self.editor.get_buf_mut().set_diagnostics(diagnostics);
+
+        Ok(Some(num_problems))
+    }
+
+    pub fn enter_select_mode(&mut self) {
+        let pos = pos!(self);
+        self.editor.view_mut().enter_select_mode(pos);
+    }
+
+    pub fn exit_select_mode(&mut self) {
+        self.editor.view_mut().exit_select_mode();
     }
 
     /// Gets the start and end positions for the current selection.
     /// 
     /// Assumes that a select anchor exists (ie. buffer is in select mode)
     pub fn get_select_region(&self) -> (Pos, Pos) {
-        let anchor = self.editor.get_buf().select_anchor().unwrap();
+        let anchor = self.editor.view().select_anchor().unwrap();
 
         let mut res = [anchor, pos!(self)];
         res.sort();
@@ -1471,6 +3897,78 @@ CTRL + SHIFT + / {dim}--{undim} Open This Help Page",
         res.into()
     }
 
+    /// Shows the size of the file as it would be saved (in bytes, lines, words and chars), and how many bytes into it the cursor is.
+    pub fn show_file_stats(&mut self) {
+        // The rows of a mapped file are only loaded as they are needed, so the rest have to be loaded to be counted
+        self.editor.get_buf_mut().load_rows(usize::MAX, &self.config);
+
+        let line_ending = self.editor.get_buf().line_ending().as_str();
+        let (mut bytes, mut words, mut chars, mut offset) = (0, 0, 0, 0);
+
+        for (y, row) in self.editor.get_buf().rows().iter().enumerate() {
+            let text = row.chars();
+
+            if y < self.cy {
+                offset += text.len() + line_ending.len();
+            } else if y == self.cy {
+                offset += cmp::min(self.cx, text.len());
+            }
+
+            bytes += text.len() + line_ending.len();
+            words += text.split_whitespace().count();
+            chars += text.chars().count() + line_ending.chars().count();
+        }
+
+        let num_rows = self.editor.get_buf().num_rows();
+        let note = if self.editor.get_buf().load_progress().is_some() {
+            " (still loading)"
+        } else if !self.editor.get_buf().is_fully_loaded() {
+            " (only the part that could be read)"
+        } else {
+            ""
+        };
+
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        self.set_status_msg(format!(
+            "{bytes} byte{}, {num_rows} line{}, {words} word{}, {chars} char{}; cursor at byte {offset}{}",
+            plural(bytes),
+            plural(num_rows),
+            plural(words),
+            plural(chars),
+            note
+        ));
+    }
+
+    /// Counts the chars (including line breaks) and lines that are selected, without copying them. The count is kept until the selection or the buffer changes, as it is needed every time the status bar is drawn.
+    fn selection_size(&mut self) -> (usize, usize) {
+        let (from, to) = self.get_select_region();
+        let buf = self.editor.get_buf();
+        if buf.num_rows() == 0 {
+            return (0, 0);
+        }
+
+        let key = (buf.id(), buf.changes(), from, to);
+        if let Some((cached, size)) = self.selection_size {
+            if cached == key {
+                return size;
+            }
+        }
+
+        let chars = (from.y()..=to.y())
+            .map(|y| {
+                let row = buf.row_at(y).chars();
+                let start = if y == from.y() { from.x() } else { 0 };
+                let end = if y == to.y() { to.x() } else { row.len() };
+
+                row.get(start..end).map_or(0, |s| s.chars().count())
+            })
+            .sum::<usize>();
+
+        let size = (chars + to.y() - from.y(), to.y() - from.y() + 1);
+        self.selection_size = Some((key, size));
+        size
+    }
+
     /// Gets the chars of the rows for a given region.
     pub fn get_region_chars(&self, from: Pos, to: Pos) -> Vec<String> {        
         if from == to {
@@ -1478,8 +3976,8 @@ CTRL + SHIFT + / {dim}--{undim} Open This Help Page",
         }
 
         let buf = self.editor.get_buf();
-        let from_cx = buf.row_at(from.y()).rx_to_cx(from.x(), &self.config);
-        let to_cx = buf.row_at(to.y()).rx_to_cx(to.x(), &self.config);
+        let from_cx = from.x();
+        let to_cx = to.x();
 
         if from.y() == to.y() {
             return vec![buf.row_at(from.y()).chars_at(from_cx..to_cx).to_owned()];
@@ -1499,7 +3997,7 @@ CTRL + SHIFT + / {dim}--{undim} Open This Help Page",
 
     /// Renames current buffer. 
     pub fn rename(&mut self, msg: &str) -> error::Result<()> {
-        let path = self.prompt(msg, &|_, _, _| { })?;
+        let path = self.prompt_path(msg)?;
 
         if path.is_some() {
             let path = path.unwrap();
@@ -1519,8 +4017,8 @@ CTRL + SHIFT + / {dim}--{undim} Open This Help Page",
                             break;
                         }
 
-                        if i != self.editor.current_buf() &&
-                            self.editor.bufs()[i].file_name() == path.trim()
+                        if !self.editor.bufs()[i].shares_buf(self.editor.view()) &&
+                            self.editor.bufs()[i].buf().file_name() == path.trim()
                         {
                             self.editor.remove_buf(i);
                             continue;
@@ -1539,9 +4037,23 @@ CTRL + SHIFT + / {dim}--{undim} Open This Help Page",
 
     /// Attempts to save current `TextBuffer` to the file. Returns the number of bytes written.
     pub fn save(&mut self) -> error::Result<usize> {
+        // Saving now would cut off the rest of the file
+        if self.editor.get_buf().is_partial() {
+            self.set_status_error("The file couldn't be read all the way, so saving it would cut off the rest of it".to_owned());
+            self.alert();
+
+            return Ok(0);
+        }
+
+        if self.editor.get_buf().load_progress().is_some() {
+            self.set_status_msg("Wait for the file to finish loading before saving it".to_owned());
+
+            return Ok(0);
+        }
+
         // Did not enter a file name when opening text editor
         if self.editor.get_buf().file_name().is_empty() {
-            *self.editor.get_buf_mut().file_name_mut() = match self.prompt("Save as (ESC to cancel): ", &|_, _, _| {})? {
+            *self.editor.get_buf_mut().file_name_mut() = match self.prompt_path("Save as (ESC to cancel): ")? {
                 Some(val) => val,
                 None => {
                     self.set_status_msg("Save aborted".to_owned());
@@ -1551,36 +4063,207 @@ CTRL + SHIFT + / {dim}--{undim} Open This Help Page",
             };
         }
 
+        let format_err = if self.config.format_on_save() && self.config.formatter(self.editor.get_buf().syntax().lang()).is_some() {
+            self.format_buf().err()
+        } else {
+            None
+        };
+
         let path = self.editor.get_buf().file_name().to_owned();
-        self.save_file(&path)
+        let bytes_wrote = self.save_file(&path)?;
+
+        if let Some(err) = format_err {
+            log::warn(&format!("Format on save failed: {err}"));
+            self.set_status_warning(format!("{bytes_wrote} bytes written to disk, but format failed: {err}"));
+        }
+
+        // Linters can be slow, so this one runs in the background and its problems are shown once it finishes
+        if let Some(cmd) = self.config.linter(self.editor.get_buf().syntax().lang()) {
+            let buf = self.editor.get_buf();
+            let linting = Linting::spawn(cmd.to_owned(), buf.dir().to_owned(), buf.base_name().to_owned());
+            self.linting = Some((linting, buf.id()));
+        }
+
+        Ok(bytes_wrote)
     }
 
     /// Attempts to save to given file. Returns the number of bytes written.
     fn save_file(&mut self, path: &str) -> error::Result<usize> {
-        let buf = self.editor.get_buf_mut();
+        let bytes_wrote = {
+            let mut buf = self.editor.get_buf_mut();
 
-        if let Some(ext) = buf.get_file_ext() {
-            *buf.syntax_mut() = Syntax::select_syntax(ext);
-        }
+            if let Some(syntax) = buf.detect_syntax() {
+                *buf.syntax_mut() = syntax;
+            }
 
-        let text = TextBuffer::rows_to_string(buf.rows());
-        let bytes = text.as_bytes();
-        let bytes_wrote = bytes.len();
+            let text = buf.file_text();
+            let bytes = text.as_bytes();
 
-        File::create(path)?.write_all(bytes)?;
+            File::create(path)?.write_all(bytes)?;
 
-        buf.make_clean();
+            buf.make_clean();
+            buf.set_write_protected(false);
+            bytes.len()
+        };
+        log::debug(&format!("Saved '{path}' ({bytes_wrote} bytes)"));
         self.set_status_msg(format!("{} bytes written to disk", bytes_wrote));
 
         Ok(bytes_wrote)
     }
 
+    /// Types `ch` at the cursor. If `auto_pairs` is on, an opening bracket or quote of the buffer's language (see [`Syntax::bracket_pairs`]) is typed along with the closing one, where it wouldn't be in front of a word, and a closing one that is already just after the cursor is moved over instead of typed.
+    /// 
+    /// Returns the text that was typed, which has both chars of a pair if one was typed.
+    pub fn type_char(&mut self, ch: char) -> String {
+        if !self.config.auto_pairs() {
+            self.insert_char(ch);
+            return ch.to_string();
+        }
+
+        let syntax = self.editor.get_buf().syntax();
+        let (line_start, after) = match self.editor.get_buf().rows().get(self.cy) {
+            Some(row) => (row.chars()[..self.cx].to_owned(), row.chars()[self.cx..].chars().next()),
+            None => (String::new(), None)
+        };
+        let before = line_start.chars().next_back();
+
+        // A closing bracket at the start of a line goes back to the indentation of the line it closes
+        let is_indent = !line_start.is_empty() && line_start.chars().all(|ch| ch == ' ' || ch == '\t');
+        if self.config.auto_indent() && is_indent && after != Some(ch) && syntax.is_closing_bracket(ch) {
+            let dedented = match line_start.strip_suffix('\t') {
+                Some(rest) => rest,
+                None => {
+                    let spaces = line_start.len() - line_start.trim_end_matches(' ').len();
+                    &line_start[..line_start.len() - cmp::min(spaces, self.config.tab_stop())]
+                }
+            };
+            let row = Row::from_chars(format!("{dedented}{ch}"), &self.config);
+            self.replace_region(Pos(0, self.cy), pos!(self), vec![row]);
+            return ch.to_string();
+        }
+
+        let is_quote = syntax.string_delims().contains(&ch);
+        let is_space_after = after.is_none_or(|next| next.is_whitespace() || syntax.is_closing_bracket(next));
+        // Quotes after a word are more likely to be apostrophes (eg. in `don't`)
+        let is_space_before = before.is_none_or(|prev| prev != ch && (prev.is_whitespace() || is_sep(prev)));
+
+        let close = if after == Some(ch) && (is_quote || syntax.is_closing_bracket(ch)) {
+            self.cx += ch.len_utf8();
+            return ch.to_string();
+        } else if is_space_after {
+            syntax.closing_bracket(ch).or((is_quote && is_space_before).then_some(ch))
+        } else {
+            None
+        };
+
+        let close = match close {
+            Some(close) => close,
+            None => {
+                self.insert_char(ch);
+                return ch.to_string();
+            }
+        };
+
+        let start = pos!(self);
+        let pair = format!("{ch}{close}");
+        let mut buf = self.editor.get_buf_mut();
+        buf.insert_rows(start, vec![Row::from_chars(pair.clone(), &self.config)], start, &self.config);
+
+        // Between the two
+        Pos(self.cx, self.cy) = Pos(start.x() + ch.len_utf8(), start.y());
+        buf.history_mut().set_cursor_after(pos!(self));
+
+        pair
+    }
+
+    /// Gets the closing bracket or quote just after the opening one at `pos`, if `auto_pairs` is on and there is nothing between them.
+    fn typed_pair_at(&self, pos: Pos) -> Option<char> {
+        if !self.config.auto_pairs() {
+            return None;
+        }
+
+        let buf = self.editor.get_buf();
+        let syntax = buf.syntax();
+        let mut chars = buf.rows().get(pos.y())?.chars()[pos.x()..].chars();
+        let open = chars.next()?;
+        let close = syntax.closing_bracket(open).or(syntax.string_delims().contains(&open).then_some(open))?;
+
+        (chars.next() == Some(close)).then_some(close)
+    }
+
+    /// Breaks the line at the cursor, returning the text that was typed. If `auto_indent` is on, the new line starts with the same indentation as the one before it, and one more level if it comes after one of the language's indent triggers (see [`Syntax::indent_triggers`]). Between a pair of brackets, the closing one goes on a line of its own.
+    pub fn insert_newline(&mut self) -> String {
+        let syntax = self.editor.get_buf().syntax();
+        let (indent, extra, is_in_pair) = match self.editor.get_buf().rows().get(self.cy) {
+            Some(row) if self.config.auto_indent() => {
+                let chars = row.chars();
+                let indent: String = chars
+                    .chars()
+                    .take_while(|ch| *ch == ' ' || *ch == '\t')
+                    .collect();
+                // Tabs are used unless the line is indented with spaces
+                let unit = if indent.starts_with(' ') { " ".repeat(self.config.tab_stop()) } else { "\t".to_owned() };
+                let extra = if syntax.triggers_indent(&chars[..self.cx]) { unit } else { String::new() };
+
+                let is_in_pair = !extra.is_empty() && chars[..self.cx]
+                    .chars()
+                    .next_back()
+                    .and_then(|open| syntax.closing_bracket(open))
+                    .is_some_and(|close| chars[self.cx..].trim_start().starts_with(close));
+
+                (indent, extra, is_in_pair)
+            }
+            _ => (String::new(), String::new(), false)
+        };
+
+        let mut lines = vec![String::new(), format!("{indent}{extra}")];
+        if is_in_pair {
+            lines.push(indent);
+        }
+
+        let start = pos!(self);
+        let rows = lines
+            .iter()
+            .map(|line| Row::from_chars(line.clone(), &self.config))
+            .collect();
+        let mut buf = self.editor.get_buf_mut();
+        buf.insert_rows(start, rows, start, &self.config);
+
+        // At the end of the indentation of the first new line
+        Pos(self.cx, self.cy) = Pos(lines[1].len(), start.y() + 1);
+        buf.history_mut().set_cursor_after(pos!(self));
+
+        lines.join("\n")
+    }
+
     pub fn insert_char(&mut self, ch: char) {
         let config = &self.config;
-        let buf = self.editor.get_buf_mut();
-        let syntax = buf.syntax();
+        let mut buf = self.editor.get_buf_mut();
+
+        Pos(self.cx, self.cy) = buf.insert_rows(pos!(self), vec![Row::from_chars(ch.to_string(), config)], pos!(self), config);
+    }
+
+    /// Replaces the abbreviation that ends at the cursor (see [`Config::abbreviation`]), if there is one, with its expansion, and then calls `type_sep` to type the separator that was typed after it. Both are undone as one edit.
+    fn expand_abbreviation(&mut self, type_sep: impl FnOnce(&mut Self)) {
+        let syntax = self.editor.get_buf().syntax();
+        let found = self.editor
+            .get_buf()
+            .rows()
+            .get(self.cy)
+            .and_then(|row| self.config.abbreviation(syntax.lang(), &row.chars()[..self.cx]))
+            .map(|(trigger, expansion)| (trigger.len(), expansion.to_owned()));
+
+        let (len, expansion) = match found {
+            Some(found) => found,
+            None => return type_sep(self)
+        };
 
-        Pos(self.cx, self.cy) = buf.insert_rows(pos!(self), vec![Row::from_chars(ch.to_string(), config, syntax)], config);
+        self.editor.get_buf_mut().history_mut().begin_group();
+
+        let row = Row::from_chars(expansion, &self.config);
+        self.replace_region(Pos(self.cx - len, self.cy), pos!(self), vec![row]);
+        type_sep(self);
+        self.editor.get_buf_mut().history_mut().end_group();
     }
 
     /// Removes a character at the cursor.
@@ -1594,17 +4277,17 @@ CTRL + SHIFT + / {dim}--{undim} Open This Help Page",
         let config = &*self.config;
 
         let mut from = pos!(self);
-        let to;
+        let mut to;
 
         if is_delete {
-            if from.x() == self.get_row().rsize() {
+            if from.x() >= self.get_row().size() {
                 if from.y() == self.editor.get_buf().num_rows() - 1 {
                     return;
                 }
 
                 to = Pos(0, from.y() + 1);
             } else {
-                to = Pos(from.x() + 1, from.y());
+                to = Pos(self.get_row().next_grapheme(from.x()), from.y());
             }
         } else {
             if from.x() == 0 {
@@ -1612,35 +4295,71 @@ CTRL + SHIFT + / {dim}--{undim} Open This Help Page",
                     return;
                 } else {
                     to = from;
-                    from = Pos(self.editor.get_buf().rows()[from.y() - 1].rsize(), from.y() - 1);
+                    from = Pos(self.editor.get_buf().rows()[from.y() - 1].size(), from.y() - 1);
                 }
             } else {
                 to = from;
-                from = Pos(from.x() - 1, from.y())
+                from = Pos(self.get_row().prev_grapheme(from.x()), from.y());
+
+                // An empty pair of brackets or quotes is removed together
+                if let Some(close) = self.typed_pair_at(from) {
+                    to = Pos(to.x() + close.len_utf8(), to.y());
+                }
             }
         }
 
-        let msg = self.editor.get_buf().create_remove_msg_region(from, to, config);
-        Pos(self.cx, self.cy) = self.editor.get_buf_mut().remove_rows(from, msg, config);
+        let msg = self.editor.get_buf().create_remove_msg_region(from, to);
+        Pos(self.cx, self.cy) = self.editor.get_buf_mut().remove_rows(from, msg, pos!(self), config);
     }
 
     /// Gets the row according to `self`'s `cy` attribute.
-    pub fn get_row(&self) -> &Row {
-        &self.editor.get_buf().rows()[self.cy]
+    pub fn get_row(&self) -> Ref<'_, Row> {
+        Ref::map(self.editor.get_buf(), |buf| &buf.rows()[self.cy])
     }
 
-    pub fn get_row_mut(&mut self) -> &mut Row {
-        &mut self.editor.get_buf_mut().rows_mut()[self.cy]
-    }
+    /// Calculates col_start value. The line numbers are hidden if they would leave no room for the text (eg. with a wide `line_number_min_width` or `line_number_separator`).
+    pub fn calc_col_start(&self) -> usize {
+        if !self.editor.view().options().line_numbers(&self.config) {
+            return 0;
+        }
+
+        // The numbers, the diagnostic or diff marker after them, and the separator
+        let width = self.line_num_width() + 1 + self.config.line_number_separator().width();
 
-    /// Calculates col_start value
-    pub fn calc_col_start(&mut self) -> usize {
-        self.editor.get_buf().num_rows().len() + 1
+        if width < self.text_cols() { width } else { 0 }
     }
 
     /// Does any clean up actions that require the `Screen` (eg. clearing the screen). When it gets dropped `_clean_up.drop` will get triggered to complete any clean up action that don't require the screen (eg. disabling raw mode).
     pub fn clean_up(&mut self) {
         let _ = self.clear();
+        let _ = self.flush();
+    }
+
+    /// Suspends the editor in the background, like CTRL+Z does in a shell. The terminal is restored while it is stopped, and everything is redrawn once it is brought back (eg. with `fg`).
+    #[cfg(unix)]
+    pub fn suspend(&mut self) {
+        log::debug("Suspending");
+
+        self.clean_up();
+        cleanup::restore_terminal();
+
+        // Safety: `raise` has no preconditions. The process stops here until it is continued
+        unsafe {
+            libc::raise(libc::SIGTSTP);
+        }
+
+        cleanup::set_up_terminal();
+        log::debug("Resumed");
+
+        // The terminal's contents, title and colors can't be relied on after being in the shell
+        self.prev_frame = None;
+        self.title.clear();
+        self.is_inverted = false;
+    }
+
+    #[cfg(not(unix))]
+    pub fn suspend(&mut self) {
+        self.set_status_msg("Suspending isn't supported on this platform".to_owned());
     }
 
     pub fn editor_mut(&mut self) -> &mut Editor {
@@ -1657,3 +4376,14 @@ impl Drop for Screen {
         self.clean_up();
     }
 }
+
+/// What kind of text a prompt asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PromptKind {
+    /// Text that can't be empty
+    Text,
+    /// A file path, which Tab completes
+    Path,
+    /// Text that can be empty
+    MaybeEmpty
+}
@@ -0,0 +1,242 @@
+use std::fs;
+use std::io;
+
+use crate::buffer::{Row, TextBuffer};
+use crate::config::Config;
+use crate::config_file::{ConfigError, ConfigFile, Entry};
+use crate::util::Pos;
+
+/// An edit made by a [`Script`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Op {
+    /// Moves the cursor to a line, and a column in it, both starting at 1
+    Goto(usize, usize),
+    /// Inserts text at the cursor, moving the cursor to the end of it
+    Insert(String),
+    /// Removes the given number of chars after the cursor, where a line break counts as one
+    Delete(usize),
+    /// Replaces every occurrence of some text in the file
+    Replace(String, String),
+    /// Writes the file, to another path if one is given
+    Save(Option<String>)
+}
+
+/// A list of edits to make to a file without opening the editor, written in the same format as the config file (see [`ConfigFile`]), with one edit on each line, made in order. Strings can use `\n`, `\t` and `\\`.
+///
+/// For example:
+/// ```toml
+/// goto = "3:5"            # line 3, column 5
+/// insert = "hello\n"
+/// delete = 4
+/// replace = "foo"
+/// with = "bar"            # every 'foo' becomes 'bar'
+/// save = true             # or save_as = "out.txt"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Script {
+    ops: Vec<(Op, Entry)>
+}
+
+impl Script {
+    /// Reads and parses the script at `path`, or from stdin if it is `-`.
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let text = if path == "-" {
+            io::read_to_string(io::stdin())
+        } else {
+            fs::read_to_string(path)
+        };
+
+        match text {
+            Ok(text) => Self::parse(&text),
+            Err(err) => Err(ConfigError::new(format!("Could not read '{path}': {err}"), 0))
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let file = ConfigFile::parse(text)?;
+        let mut ops: Vec<(Op, Entry)> = vec![];
+
+        for entry in file.section("") {
+            let value = entry.value();
+
+            let op = match entry.key() {
+                "goto" => {
+                    let parse_num = |s: &str| s.trim().parse::<usize>().ok().filter(|&n| n > 0);
+                    let (line, col) = match value.split_once(':') {
+                        Some((line, col)) => (parse_num(line), parse_num(col)),
+                        None => (parse_num(value), Some(1))
+                    };
+
+                    match line.zip(col) {
+                        Some((line, col)) => Op::Goto(line, col),
+                        None => return Err(entry.error("Expected 'LINE' or 'LINE:COL', starting at 1"))
+                    }
+                }
+                "insert" => Op::Insert(unescape(value)),
+                "delete" => match value.parse() {
+                    Ok(n) => Op::Delete(n),
+                    Err(_) => return Err(entry.error("Expected a number of chars"))
+                },
+                "replace" if value.is_empty() => return Err(entry.error("Expected text to find")),
+                "replace" => Op::Replace(unescape(value), String::new()),
+                // Finishes the `replace` before it
+                "with" => match ops.last_mut() {
+                    Some((Op::Replace(_, with), _)) => {
+                        *with = unescape(value);
+                        continue;
+                    }
+                    _ => return Err(entry.error("Nothing to replace"))
+                },
+                "save" => match value {
+                    "true" => Op::Save(None),
+                    "false" => continue,
+                    _ => return Err(entry.error("Expected true or false"))
+                },
+                "save_as" => Op::Save(Some(value.to_owned())),
+                _ => return Err(entry.error("Unknown edit"))
+            };
+
+            ops.push((op, entry.clone()));
+        }
+
+        Ok(Self { ops })
+    }
+
+    /// Gets whether the script writes the file at some point.
+    pub fn saves(&self) -> bool {
+        self.ops
+            .iter()
+            .any(|(op, _)| matches!(op, Op::Save(_)))
+    }
+
+    /// Makes each edit to `buf` in order, starting with the cursor at the start of the file. Stops at the first edit that can't be made, and fails straight away if `buf` can't be edited at all.
+    pub fn run(&self, buf: &mut TextBuffer, config: &Config) -> Result<(), ConfigError> {
+        // The edits would quietly do nothing, and the file would still be saved
+        if !buf.is_editable() {
+            return Err(ConfigError::new(format!("'{}' can't be edited", buf.display_name()), 0));
+        }
+
+        let mut cursor = Pos(0, 0);
+
+        for (op, entry) in &self.ops {
+            match op {
+                Op::Goto(line, col) => {
+                    // An empty file still has a first line to go to
+                    if *line > buf.num_rows().max(1) {
+                        return Err(entry.error(&format!("Line {line} is past the end of the file")));
+                    }
+
+                    let y = line - 1;
+                    let x = match buf.rows().get(y) {
+                        Some(row) => row.chars()
+                            .char_indices()
+                            .nth(col - 1)
+                            .map_or(row.size(), |(i, _)| i),
+                        None => 0
+                    };
+
+                    cursor = Pos(x, y);
+                }
+                Op::Insert(text) => {
+                    let rows = text
+                        .split('\n')
+                        .map(|line| Row::from_chars(line.to_owned(), config))
+                        .collect();
+
+                    cursor = buf.insert_rows(cursor, rows, cursor, config);
+                }
+                Op::Delete(n) => {
+                    let to = advance(buf, cursor, *n);
+                    // Nothing is left to delete at the end of the file
+                    if to == cursor {
+                        continue;
+                    }
+
+                    let old = buf.create_remove_msg_region(cursor, to);
+
+                    cursor = buf.remove_rows(cursor, old, cursor, config);
+                }
+                Op::Replace(find, with) => {
+                    let text = buf.rows()
+                        .iter()
+                        .map(Row::chars)
+                        .collect::<Vec<_>>()
+                        .join("\n");
+
+                    if !text.contains(find.as_str()) {
+                        return Err(entry.error(&format!("No occurrences of '{find}' found")));
+                    }
+
+                    let last = buf.num_rows() - 1;
+                    let old = buf.create_remove_msg_region(Pos(0, 0), Pos(buf.row_at(last).size(), last));
+                    let rows = text
+                        .replace(find.as_str(), with)
+                        .split('\n')
+                        .map(|line| Row::from_chars(line.to_owned(), config))
+                        .collect();
+
+                    cursor = buf.replace_rows(Pos(0, 0), old, rows, cursor, config);
+                }
+                Op::Save(path) => {
+                    let path = path.as_deref().unwrap_or(buf.file_name()).to_owned();
+                    if let Err(err) = fs::write(&path, buf.file_text()) {
+                        return Err(entry.error(&format!("Could not write '{path}': {err}")));
+                    }
+
+                    buf.make_clean();
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Gets the position `n` chars after `pos` in `buf`, where a line break counts as one, stopping at the end of the file.
+fn advance(buf: &TextBuffer, pos: Pos, n: usize) -> Pos {
+    let Pos(mut x, mut y) = pos;
+
+    for _ in 0..n {
+        let row = match buf.rows().get(y) {
+            Some(row) => row.chars(),
+            None => break
+        };
+
+        if let Some(ch) = row[x..].chars().next() {
+            x += ch.len_utf8();
+        } else if y + 1 < buf.num_rows() {
+            x = 0;
+            y += 1;
+        } else {
+            break;
+        }
+    }
+
+    Pos(x, y)
+}
+
+/// Replaces the `\n`, `\t` and `\\` escapes in `text` with the chars they stand for. Any other `\` is kept as it is.
+fn unescape(text: &str) -> String {
+    let mut s = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            s.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => s.push('\n'),
+            Some('t') => s.push('\t'),
+            Some('\\') => s.push('\\'),
+            Some(other) => {
+                s.push('\\');
+                s.push(other);
+            }
+            None => s.push('\\')
+        }
+    }
+
+    s
+}
@@ -1,4 +1,7 @@
-use std::time::Instant;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::util;
 
 /** A struct containing the important parts of the status portion of the screen: the status bar and the status message.
 
@@ -7,15 +10,25 @@ When rendering, based on how long the content and the size of the screen, some e
 #[derive(Debug)]
 pub struct Status {
     msg: String,
-    timestamp: Instant
+    level: MsgLevel,
+    timestamp: Instant,
+    log: VecDeque<LoggedMsg>,
+    /// The text of each indicator that is shown on the right of the status bar
+    indicators: BTreeMap<Indicator, String>
 }
 
 impl Status {
+    /// The maximum number of messages kept in the log.
+    const MAX_LOG_LEN: usize = 500;
+
     /// Creates status with no file or text
     pub fn new() -> Self {
         Self {
             msg: String::new(),
-            timestamp: Instant::now()
+            level: MsgLevel::Info,
+            timestamp: Instant::now(),
+            log: VecDeque::new(),
+            indicators: BTreeMap::new()
         }
     }
 
@@ -23,17 +36,138 @@ impl Status {
         &self.msg
     }
 
-    pub fn set_msg(&mut self, msg: String, max_len: usize) {
+    pub fn level(&self) -> MsgLevel {
+        self.level
+    }
+
+    /// Sets the status msg, also adding it to the log (unless it's empty, or the same as the last message logged).
+    pub fn set_msg(&mut self, msg: String, level: MsgLevel, max_len: usize) {
+        if !msg.is_empty() && self.log.back().is_none_or(|m| m.msg != msg) {
+            if self.log.len() == Self::MAX_LOG_LEN {
+                self.log.pop_front();
+            }
+
+            self.log.push_back(LoggedMsg {
+                msg: msg.clone(),
+                level,
+                timestamp: Instant::now()
+            });
+        }
+
+        self.set_prompt_msg(msg, max_len);
+        self.level = level;
+    }
+
+    /// Sets the status msg without adding it to the log, for text that changes as the user types (ie. prompts).
+    pub fn set_prompt_msg(&mut self, msg: String, max_len: usize) {
         self.msg = msg;
-        self.msg.truncate(max_len);
+        util::truncate_to_width(&mut self.msg, max_len);
+        self.level = MsgLevel::Info;
         self.timestamp = Instant::now();
     }
 
-    pub fn timestamp(&self) -> Instant {
-        self.timestamp
+    /// Gets whether the status msg should still be shown, where `life` is how long info messages last (see [`MsgLevel::life`]).
+    pub fn is_msg_shown(&self, life: Duration) -> bool {
+        self.level
+            .life(life)
+            .is_none_or(|life| self.timestamp.elapsed() < life)
+    }
+
+    /// Stops showing the status msg, such as an error that would otherwise stay. It is still kept in the log.
+    pub fn dismiss(&mut self) {
+        self.msg.clear();
+        self.level = MsgLevel::Info;
     }
 
     pub fn set_timestamp(&mut self, timestamp: Instant) {
         self.timestamp = timestamp;
     }
+
+    /// Gets every message logged, from oldest to newest.
+    pub fn log(&self) -> &VecDeque<LoggedMsg> {
+        &self.log
+    }
+
+    /// Shows `indicator` with the given `text`, or stops showing it if `text` is `None`.
+    pub fn set_indicator(&mut self, indicator: Indicator, text: Option<String>) {
+        match text {
+            Some(text) => self.indicators.insert(indicator, text),
+            None => self.indicators.remove(&indicator)
+        };
+    }
+
+    /// Gets the text of each indicator that is shown, in the order they are shown.
+    pub fn indicators(&self) -> impl Iterator<Item = &str> {
+        self.indicators.values().map(String::as_str)
+    }
+}
+
+/// A mode that is shown on the right of the status bar while it lasts. They are shown in the order they are declared in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Indicator {
+    /// Text is selected
+    Select,
+    /// The buffer can't be edited
+    Readonly
+}
+
+/// How important a status message is, which decides its color and how long it is shown for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MsgLevel {
+    #[default]
+    Info,
+    /// Something the user should know before carrying on (eg. that a file has unsaved changes)
+    Warning,
+    /// Something that couldn't be done. These are shown until they are dismissed, or another message replaces them
+    Error
+}
+
+impl MsgLevel {
+    /// Gets how long a message at this level is shown for, where info messages last for `life`, or `None` if it stays until dismissed.
+    pub fn life(self, life: Duration) -> Option<Duration> {
+        match self {
+            Self::Info => Some(life),
+            Self::Warning => Some(life * 3),
+            Self::Error => None
+        }
+    }
+
+    /// Gets the text put before a message at this level in the message log.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Info => "",
+            Self::Warning => "Warning: ",
+            Self::Error => "Error: "
+        }
+    }
+}
+
+/// A status message that was shown, kept so that it can be viewed later.
+#[derive(Debug, Clone)]
+pub struct LoggedMsg {
+    msg: String,
+    level: MsgLevel,
+    timestamp: Instant
+}
+
+impl LoggedMsg {
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+
+    pub fn level(&self) -> MsgLevel {
+        self.level
+    }
+
+    /// Gets how long ago the message was shown, in short form (eg. `12s ago`).
+    pub fn age(&self) -> String {
+        let secs = self.timestamp.elapsed().as_secs();
+
+        match secs {
+            0 => "now".to_owned(),
+            1..=59 => format!("{secs}s ago"),
+            60..=3599 => format!("{}m ago", secs / 60),
+            _ => format!("{}h ago", secs / 3600)
+        }
+    }
 }
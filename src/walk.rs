@@ -0,0 +1,289 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::project::Project;
+
+/// Paths to leave out when looking through directories, in the `.gitignore` format. They come from `.gitignore` files, and from patterns given in the config.
+#[derive(Debug, Clone, Default)]
+pub struct Ignore {
+    rules: Vec<Rule>
+}
+
+/// A single pattern of an [`Ignore`].
+#[derive(Debug, Clone)]
+struct Rule {
+    /// The directory that the pattern is relative to
+    base: PathBuf,
+    glob: Vec<char>,
+    /// Whether the pattern starts with `!`, bringing back paths ignored by the patterns before it
+    is_negated: bool,
+    /// Whether the pattern ends with `/`, so only matches directories
+    is_dir_only: bool,
+    /// Whether the pattern has a `/` before its end, so is matched against the whole path from `base`, instead of just the name
+    is_anchored: bool
+}
+
+impl Ignore {
+    /// Creates the rules that apply in `dir`: the `patterns` (eg. from the config), relative to the root of `dir`'s project (or to `root` if it isn't in one), and the `.gitignore` files of `dir`'s parents up to the root of its project. The `.gitignore` files in `dir` and under it are added with [`Ignore::add_gitignore`] as they are reached.
+    /// 
+    /// `root` is the directory that was opened (eg. the one the file picker started in), so that the patterns mean the same thing in every directory under it.
+    pub fn for_dir(dir: &Path, patterns: &[String], root: &Path) -> Self {
+        let mut ignore = Self::default();
+        let dir = match dir.canonicalize() {
+            Ok(dir) => dir,
+            Err(_) => return ignore
+        };
+        let project = Project::find(&dir);
+
+        let base = match &project {
+            Some(project) => project.root().to_owned(),
+            None => root.canonicalize().unwrap_or_else(|_| dir.clone())
+        };
+        for pattern in patterns {
+            ignore.add_pattern(pattern, &base);
+        }
+
+        if let Some(project) = project {
+            let mut parents: Vec<_> = dir
+                .ancestors()
+                .skip(1)
+                .take_while(|parent| parent.starts_with(project.root()))
+                .collect();
+            parents.reverse();
+
+            for parent in parents {
+                ignore.add_gitignore(parent);
+            }
+        }
+
+        ignore
+    }
+
+    /// Adds the patterns in `dir`'s `.gitignore` file, if it has one.
+    pub fn add_gitignore(&mut self, dir: &Path) {
+        if let Ok(text) = fs::read_to_string(dir.join(".gitignore")) {
+            for line in text.lines() {
+                self.add_pattern(line, dir);
+            }
+        }
+    }
+
+    /// Adds a line of a `.gitignore` file in the `base` directory. Blank lines and `#` comments are skipped.
+    fn add_pattern(&mut self, line: &str, base: &Path) {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+
+        let (is_negated, pattern) = match line.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, line)
+        };
+        let (is_dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern)
+        };
+        let is_anchored = pattern.contains('/');
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+
+        if pattern.is_empty() {
+            return;
+        }
+
+        self.rules.push(Rule {
+            base: base.to_owned(),
+            glob: pattern.chars().collect(),
+            is_negated,
+            is_dir_only,
+            is_anchored
+        });
+    }
+
+    /// Gets whether `path` (which should be absolute, and is a directory if `is_dir`) is ignored. The last pattern that matches it decides.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        // Git keeps its own files out of the way of everything else
+        if path.file_name().is_some_and(|name| name == ".git") {
+            return true;
+        }
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path, is_dir))
+            .is_some_and(|rule| !rule.is_negated)
+    }
+}
+
+impl Rule {
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.is_dir_only && !is_dir {
+            return false;
+        }
+
+        let rel = match path.strip_prefix(&self.base) {
+            Ok(rel) => rel,
+            Err(_) => return false
+        };
+
+        let text: Vec<char> = if self.is_anchored {
+            let parts: Vec<_> = rel.iter().map(|part| part.to_string_lossy()).collect();
+            parts.join("/").chars().collect()
+        } else {
+            match rel.file_name() {
+                Some(name) => name.to_string_lossy().chars().collect(),
+                None => return false
+            }
+        };
+
+        glob_match(&self.glob, &text)
+    }
+}
+
+/// Gets whether `text` matches the `glob`, where `*` matches any chars but `/`, `**` matches any chars, `?` matches any char but `/`, and `[...]` matches one of the chars in it (eg. `[abc]`, `[a-z]`, or `[!0-9]`).
+fn glob_match(glob: &[char], text: &[char]) -> bool {
+    match glob {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // `**/` can also match no directories at all
+            if let ['/', after @ ..] = rest {
+                if glob_match(after, text) {
+                    return true;
+                }
+            }
+
+            (0..=text.len()).any(|i| glob_match(rest, &text[i..]))
+        }
+        ['*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| glob_match(rest, &text[i..])),
+        ['?', rest @ ..] => matches!(text, [ch, ..] if *ch != '/') && glob_match(rest, &text[1..]),
+        ['[', class @ ..] => {
+            let (is_negated, class) = match class {
+                ['!' | '^', class @ ..] => (true, class),
+                _ => (false, class)
+            };
+
+            // A `]` right at the start is part of the class rather than closing it
+            let end = match class.iter().skip(1).position(|&ch| ch == ']') {
+                Some(i) => i + 1,
+                None => return text.first() == Some(&'[') && glob_match(&glob[1..], &text[1..])
+            };
+
+            match text.first() {
+                Some(&ch) if ch != '/' && class_match(&class[..end], ch) != is_negated => glob_match(&class[end + 1..], &text[1..]),
+                _ => false
+            }
+        }
+        ['\\', ch, rest @ ..] => text.first() == Some(ch) && glob_match(rest, &text[1..]),
+        [ch, rest @ ..] => text.first() == Some(ch) && glob_match(rest, &text[1..])
+    }
+}
+
+/// Gets whether `ch` is one of the chars or ranges (eg. `a-z`) in `class`.
+fn class_match(class: &[char], ch: char) -> bool {
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == '-' {
+            if (class[i]..=class[i + 2]).contains(&ch) {
+                return true;
+            }
+            i += 3;
+        } else {
+            if class[i] == ch {
+                return true;
+            }
+            i += 1;
+        }
+    }
+
+    false
+}
+
+/// Which files to find in a directory, besides the ones that are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct FileFilter {
+    /// The extensions (without the `.`) of the files to find, or empty to find files of any extension
+    exts: Vec<String>,
+    /// The size in bytes of the largest file to find
+    max_size: Option<u64>
+}
+
+impl FileFilter {
+    pub fn new(exts: Vec<String>, max_size: Option<u64>) -> Self {
+        Self { exts, max_size }
+    }
+
+    /// Gets whether the file at `path` passes the filter. Binary files (ie. ones with a null byte near the start) never do.
+    fn allows(&self, path: &Path) -> bool {
+        let has_ext = self.exts.is_empty() || path
+            .extension()
+            .is_some_and(|ext| self.exts.iter().any(|allowed| ext.eq_ignore_ascii_case(allowed.as_str())));
+        if !has_ext {
+            return false;
+        }
+
+        if let Some(max_size) = self.max_size {
+            match fs::metadata(path) {
+                Ok(metadata) if metadata.len() <= max_size => (),
+                _ => return false
+            }
+        }
+
+        !is_binary(path)
+    }
+}
+
+/// Gets whether the file at `path` looks like a binary file, by checking for a null byte in its first few KB, like Git does.
+fn is_binary(path: &Path) -> bool {
+    let mut start = [0; 8192];
+    match File::open(path).and_then(|mut file| file.read(&mut start)) {
+        Ok(len) => start[..len].contains(&0),
+        Err(_) => true
+    }
+}
+
+/// Finds the files under `dir` and all of its subdirectories that aren't ignored and pass the `filter`, sorted by path. The paths start with `dir`, as it was given.
+///
+/// The `ignore` rules are added to with the `.gitignore` files found along the way, which only apply under their own directory.
+pub fn find_files(dir: &Path, ignore: &Ignore, filter: &FileFilter) -> io::Result<Vec<PathBuf>> {
+    let root = dir.canonicalize()?;
+    let mut files = vec![];
+
+    // Errors in subdirectories (eg. ones that can't be read) leave them out, rather than stopping the search
+    find_files_in(&root, ignore.clone(), filter, &mut files, true)?;
+
+    files.sort();
+    Ok(files
+        .into_iter()
+        .map(|path| dir.join(path.strip_prefix(&root).unwrap_or(&path)))
+        .collect())
+}
+
+fn find_files_in(dir: &Path, mut ignore: Ignore, filter: &FileFilter, files: &mut Vec<PathBuf>, is_root: bool) -> io::Result<()> {
+    ignore.add_gitignore(dir);
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if is_root => return Err(err),
+        Err(_) => return Ok(())
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        // Symlinks to directories aren't followed, so that they can't loop
+        let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+
+        if ignore.is_ignored(&path, is_dir) {
+            continue;
+        }
+
+        if is_dir {
+            find_files_in(&path, ignore.clone(), filter, files, false)?;
+        } else if path.is_file() && filter.allows(&path) {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use unicode_width::UnicodeWidthStr;
+
+use crate::keybinds::KEYBINDS;
+
+/// A line of the welcome screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WelcomeLine {
+    /// Text drawn in the theme's title style
+    Title(String),
+    /// An action and the keys that do it, with the keys aligned to the right
+    Shortcut(String, String),
+    Blank
+}
+
+impl WelcomeLine {
+    /// Gets the least number of columns needed to draw the line.
+    pub fn width(&self) -> usize {
+        match self {
+            Self::Title(text) => text.width(),
+            Self::Shortcut(action, keys) => action.width() + 1 + keys.width(),
+            Self::Blank => 0
+        }
+    }
+}
+
+/// The lines shown in the middle of the screen when the editor has an empty buffer. Each line is padded to the width of the widest line, and then centered, so that they line up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Welcome {
+    is_enabled: bool,
+    lines: Vec<WelcomeLine>
+}
+
+impl Welcome {
+    /// Creates a welcome screen with the given lines.
+    pub fn new(lines: Vec<WelcomeLine>) -> Self {
+        Self {
+            is_enabled: true,
+            lines
+        }
+    }
+
+    /// Creates a welcome screen that isn't shown.
+    pub fn disabled() -> Self {
+        Self {
+            is_enabled: false,
+            lines: vec![]
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
+
+    pub fn lines(&self) -> &Vec<WelcomeLine> {
+        &self.lines
+    }
+
+    /// Gets the width of the widest line.
+    pub fn width(&self) -> usize {
+        self.lines
+            .iter()
+            .map(WelcomeLine::width)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Default for Welcome {
+    /// The version of mino, followed by the keybinds that are marked to be shown on the welcome screen.
+    fn default() -> Self {
+        let mut lines = vec![
+            WelcomeLine::Title(format!("Mino -- version {}", crate::MINO_VER)),
+            WelcomeLine::Blank
+        ];
+
+        lines.extend(KEYBINDS
+            .iter()
+            .filter_map(|k| k
+                .welcome()
+                .map(|name| WelcomeLine::Shortcut(name.to_owned(), k.short_keys()))
+            )
+        );
+
+        Self::new(lines)
+    }
+}